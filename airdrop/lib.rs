@@ -0,0 +1,242 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+/// airdrop of `YToken` to the (account, amount) pairs of a Merkle tree.
+///
+/// The contract only stores the root, recipients claim their amount with the proof of their leaf.
+/// `airdrop_tool` builds the tree and the proofs from a CSV file.
+#[openbrush::contract]
+pub mod airdrop {
+    use ink::env::hash::{Blake2x256, HashOutput};
+    use ink::prelude::vec::Vec;
+    use ink::storage::Mapping;
+    use openbrush::contracts::psp22::{PSP22Error, PSP22Ref};
+    use y_psp22_receiver::{accept_tokens, PSP22Receiver, PSP22ReceiverError};
+
+    pub type Hash32 = <Blake2x256 as HashOutput>::Type;
+
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum AirdropError {
+        AlreadyClaimed,
+        InvalidProof,
+        /// the airdrop doesn't hold enough tokens or the token rejected the transfer
+        TransferFailed(PSP22Error),
+    }
+
+    #[ink(event)]
+    pub struct Claimed {
+        #[ink(topic)]
+        account: AccountId,
+        amount: Balance,
+    }
+
+    #[ink(storage)]
+    pub struct Airdrop {
+        token: AccountId,
+        merkle_root: Hash32,
+        claimed: Mapping<AccountId, ()>,
+    }
+
+    /// hash of the leaf of `account`, the blake2_256 hash of the scale encoded pair
+    pub fn leaf_hash(account: &AccountId, amount: Balance) -> Hash32 {
+        let mut hash = Hash32::default();
+        ink::env::hash_encoded::<Blake2x256, _>(&(account, amount), &mut hash);
+        hash
+    }
+
+    /// hash of an inner node, the children are sorted so proofs don't need to tell left from right
+    pub fn node_hash(a: &Hash32, b: &Hash32) -> Hash32 {
+        let (first, second) = if a <= b { (a, b) } else { (b, a) };
+        let mut hash = Hash32::default();
+        ink::env::hash_bytes::<Blake2x256>(
+            &[first.as_slice(), second.as_slice()].concat(),
+            &mut hash,
+        );
+        hash
+    }
+
+    /// folds the proof into the leaf and compares the result with `root`
+    pub fn verify_proof(root: &Hash32, leaf: Hash32, proof: &[Hash32]) -> bool {
+        proof
+            .iter()
+            .fold(leaf, |hash, sibling| node_hash(&hash, sibling))
+            == *root
+    }
+
+    impl Airdrop {
+        /// the airdrop of `token`, which has to be transferred to the contract before claiming
+        #[ink(constructor)]
+        pub fn new(token: AccountId, merkle_root: Hash32) -> Self {
+            Self {
+                token,
+                merkle_root,
+                claimed: Mapping::default(),
+            }
+        }
+
+        /// transfers `amount` to the caller if the tree contains the leaf of the caller and `amount`
+        #[ink(message)]
+        pub fn claim(&mut self, amount: Balance, proof: Vec<Hash32>) -> Result<(), AirdropError> {
+            let account = self.env().caller();
+            if self.claimed.contains(account) {
+                return Err(AirdropError::AlreadyClaimed);
+            }
+            if !verify_proof(&self.merkle_root, leaf_hash(&account, amount), &proof) {
+                return Err(AirdropError::InvalidProof);
+            }
+            self.claimed.insert(account, &());
+            PSP22Ref::transfer(&self.token, account, amount, Vec::new())
+                .map_err(AirdropError::TransferFailed)?;
+            self.env().emit_event(Claimed { account, amount });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn is_claimed(&self, account: AccountId) -> bool {
+            self.claimed.contains(account)
+        }
+
+        #[ink(message)]
+        pub fn token(&self) -> AccountId {
+            self.token
+        }
+
+        #[ink(message)]
+        pub fn merkle_root(&self) -> Hash32 {
+            self.merkle_root
+        }
+    }
+
+    /// accepts transfers of the airdropped token only
+    impl PSP22Receiver for Airdrop {
+        #[ink(message)]
+        fn before_received(
+            &mut self,
+            _operator: AccountId,
+            _from: AccountId,
+            _value: Balance,
+            _data: Vec<u8>,
+        ) -> Result<(), PSP22ReceiverError> {
+            accept_tokens(self.env().caller(), &[self.token])
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[ink::test]
+        fn verifies_proofs_of_every_leaf() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let leaves = [
+                leaf_hash(&accounts.alice, 10),
+                leaf_hash(&accounts.bob, 20),
+                leaf_hash(&accounts.charlie, 30),
+            ];
+            // the odd leaf is promoted to the next level
+            let left = node_hash(&leaves[0], &leaves[1]);
+            let root = node_hash(&left, &leaves[2]);
+
+            assert!(verify_proof(&root, leaves[0], &[leaves[1], leaves[2]]));
+            assert!(verify_proof(&root, leaves[1], &[leaves[0], leaves[2]]));
+            assert!(verify_proof(&root, leaves[2], &[left]));
+            assert!(!verify_proof(
+                &root,
+                leaf_hash(&accounts.charlie, 31),
+                &[left]
+            ));
+        }
+
+        #[ink::test]
+        fn rejects_claims_with_invalid_proof() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let root = node_hash(
+                &leaf_hash(&accounts.alice, 10),
+                &leaf_hash(&accounts.bob, 20),
+            );
+            let mut airdrop = Airdrop::new(accounts.django, root);
+
+            // alice claims the amount of bob
+            let proof = vec![leaf_hash(&accounts.alice, 10)];
+            assert_eq!(airdrop.claim(20, proof), Err(AirdropError::InvalidProof));
+            assert!(!airdrop.is_claimed(accounts.alice));
+        }
+    }
+
+    #[cfg(all(test, feature = "e2e-tests"))]
+    pub mod e2e_tests {
+        use super::*;
+        use ink_e2e::subxt::tx::Signer;
+        use ink_e2e::subxt::utils::AccountId32;
+        use ink_e2e::{build_message, Keypair, PolkadotConfig};
+        use openbrush::contracts::psp22::psp22_external::PSP22;
+        use y_psp22_token::y_psp22_token::YTokenRef;
+
+        type E2EResult<T> = Result<T, Box<dyn std::error::Error>>;
+
+        fn address_of(keypair: &Keypair) -> AccountId {
+            let address: AccountId32 = <Keypair as Signer<PolkadotConfig>>::account_id(keypair);
+            address.0.into()
+        }
+
+        #[ink_e2e::test(additional_contracts = "../contract/Cargo.toml")]
+        async fn recipients_claim_once(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            let (bob, charlie) = (address_of(&ink_e2e::bob()), address_of(&ink_e2e::charlie()));
+            let (bob_leaf, charlie_leaf) = (leaf_hash(&bob, 30), leaf_hash(&charlie, 70));
+            let root = node_hash(&bob_leaf, &charlie_leaf);
+
+            let token = client
+                .instantiate(
+                    "y_psp22_token",
+                    &ink_e2e::alice(),
+                    YTokenRef::new(1000),
+                    0,
+                    None,
+                )
+                .await
+                .expect("instantiate token failed")
+                .account_id;
+            let address = client
+                .instantiate(
+                    "airdrop",
+                    &ink_e2e::alice(),
+                    AirdropRef::new(token, root),
+                    0,
+                    None,
+                )
+                .await
+                .expect("instantiate airdrop failed")
+                .account_id;
+            let fund = build_message::<YTokenRef>(token.clone())
+                .call(|token| token.transfer(address, 100, vec![]));
+            client
+                .call(&ink_e2e::alice(), fund, 0, None)
+                .await
+                .expect("transfer failed");
+
+            let claim = build_message::<AirdropRef>(address.clone())
+                .call(|airdrop| airdrop.claim(30, vec![charlie_leaf]));
+            client
+                .call(&ink_e2e::bob(), claim, 0, None)
+                .await
+                .expect("claim failed");
+
+            let claim_again = build_message::<AirdropRef>(address.clone())
+                .call(|airdrop| airdrop.claim(30, vec![charlie_leaf]));
+            let result = client
+                .call_dry_run(&ink_e2e::bob(), &claim_again, 0, None)
+                .await;
+            assert_eq!(result.return_value(), Err(AirdropError::AlreadyClaimed));
+
+            let balance_of_bob =
+                build_message::<YTokenRef>(token.clone()).call(|token| token.balance_of(bob));
+            let balance = client
+                .call_dry_run(&ink_e2e::alice(), &balance_of_bob, 0, None)
+                .await
+                .return_value();
+            assert_eq!(balance, 30);
+
+            Ok(())
+        }
+    }
+}
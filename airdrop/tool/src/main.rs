@@ -0,0 +1,190 @@
+//! Builds the Merkle tree of an airdrop from a CSV file of `account,amount` rows, ss58 addresses and amounts
+//! in the smallest unit, and prints the root and the proof of every recipient as JSON:
+//!
+//! ```text
+//! cargo run -p airdrop_tool -- recipients.csv > airdrop.json
+//! ```
+//!
+//! The hashes are the same as in the airdrop contract: leaves are the blake2_256 hash of the scale encoded
+//! `(account, amount)`, inner nodes the hash of their sorted children.
+
+use scale::Encode;
+use serde::Serialize;
+use sp_core::crypto::{AccountId32, Ss58Codec};
+use std::collections::BTreeMap;
+use std::error::Error;
+
+type Hash32 = [u8; 32];
+
+#[derive(Debug, Serialize)]
+struct Claim {
+    /// as string, JSON numbers can't hold every u128
+    amount: String,
+    proof: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct Airdrop {
+    root: String,
+    total: String,
+    /// claim per ss58 address
+    claims: BTreeMap<String, Claim>,
+}
+
+fn leaf_hash(account: &AccountId32, amount: u128) -> Hash32 {
+    sp_core::blake2_256(&(<[u8; 32]>::from(account.clone()), amount).encode())
+}
+
+fn node_hash(a: &Hash32, b: &Hash32) -> Hash32 {
+    let (first, second) = if a <= b { (a, b) } else { (b, a) };
+    sp_core::blake2_256(&[first.as_slice(), second.as_slice()].concat())
+}
+
+/// every level of the tree from the leaves to the root, an odd node is promoted to the next level
+fn levels(leaves: Vec<Hash32>) -> Vec<Vec<Hash32>> {
+    let mut levels = vec![leaves];
+    while levels.last().map_or(false, |level| level.len() > 1) {
+        let next = levels
+            .last()
+            .unwrap()
+            .chunks(2)
+            .map(|pair| match pair {
+                [a, b] => node_hash(a, b),
+                [a] => *a,
+                _ => unreachable!(),
+            })
+            .collect();
+        levels.push(next);
+    }
+    levels
+}
+
+/// siblings of the leaf at `index` from the bottom up, promoted nodes have none
+fn proof(levels: &[Vec<Hash32>], mut index: usize) -> Vec<Hash32> {
+    let mut proof = Vec::new();
+    for level in &levels[..levels.len() - 1] {
+        if let Some(sibling) = level.get(index ^ 1) {
+            proof.push(*sibling);
+        }
+        index /= 2;
+    }
+    proof
+}
+
+fn verify(root: &Hash32, leaf: Hash32, proof: &[Hash32]) -> bool {
+    proof
+        .iter()
+        .fold(leaf, |hash, sibling| node_hash(&hash, sibling))
+        == *root
+}
+
+/// parses `account,amount` rows, a header row and empty lines are skipped
+fn parse_csv(input: &str) -> Result<Vec<(AccountId32, u128)>, Box<dyn Error>> {
+    let mut recipients: Vec<(AccountId32, u128)> = Vec::new();
+    for (line, row) in input.lines().enumerate() {
+        let row = row.trim();
+        if row.is_empty() || (line == 0 && row.to_lowercase().starts_with("account")) {
+            continue;
+        }
+        let (account, amount) = row
+            .split_once(',')
+            .ok_or(format!("line {}: expected account,amount", line + 1))?;
+        let account = AccountId32::from_ss58check(account.trim())
+            .map_err(|err| format!("line {}: invalid account: {err:?}", line + 1))?;
+        let amount: u128 = amount
+            .trim()
+            .parse()
+            .map_err(|err| format!("line {}: invalid amount: {err}", line + 1))?;
+        if recipients
+            .iter()
+            .any(|(recipient, _)| *recipient == account)
+        {
+            return Err(format!("line {}: duplicate account {account}", line + 1).into());
+        }
+        recipients.push((account, amount));
+    }
+    Ok(recipients)
+}
+
+fn build(recipients: &[(AccountId32, u128)]) -> Result<Airdrop, Box<dyn Error>> {
+    if recipients.is_empty() {
+        return Err("no recipients".into());
+    }
+    let leaves: Vec<Hash32> = recipients
+        .iter()
+        .map(|(account, amount)| leaf_hash(account, *amount))
+        .collect();
+    let levels = levels(leaves.clone());
+    let root = levels.last().unwrap()[0];
+
+    let mut claims = BTreeMap::new();
+    let mut total: u128 = 0;
+    for (index, (account, amount)) in recipients.iter().enumerate() {
+        let proof = proof(&levels, index);
+        debug_assert!(verify(&root, leaves[index], &proof));
+        total = total.checked_add(*amount).ok_or("total overflows u128")?;
+        claims.insert(
+            account.to_ss58check(),
+            Claim {
+                amount: amount.to_string(),
+                proof: proof
+                    .iter()
+                    .map(|hash| format!("0x{}", hex::encode(hash)))
+                    .collect(),
+            },
+        );
+    }
+    Ok(Airdrop {
+        root: format!("0x{}", hex::encode(root)),
+        total: total.to_string(),
+        claims,
+    })
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let path = std::env::args()
+        .nth(1)
+        .ok_or("usage: airdrop_tool <recipients.csv>")?;
+    let recipients = parse_csv(&std::fs::read_to_string(path)?)?;
+    let airdrop = build(&recipients)?;
+    println!("{}", serde_json::to_string_pretty(&airdrop)?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CSV: &str = "account,amount
+5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY,100
+5FHneW46xGXgs5mUiveU4sbTyGBzmstUspZC92UhjJM694ty,200
+5FLSigC9HGRKVhB9FiEo4Y3koPsNmBmLJbpXg2mp1hXcS59Y,300
+";
+
+    #[test]
+    fn proofs_verify_against_root() {
+        let recipients = parse_csv(CSV).unwrap();
+        let leaves: Vec<Hash32> = recipients
+            .iter()
+            .map(|(account, amount)| leaf_hash(account, *amount))
+            .collect();
+        let levels = levels(leaves.clone());
+        let root = levels.last().unwrap()[0];
+
+        for (index, leaf) in leaves.iter().enumerate() {
+            assert!(verify(&root, *leaf, &proof(&levels, index)));
+        }
+        // the third leaf is promoted, its proof is the root of the first two
+        assert_eq!(proof(&levels, 2), vec![node_hash(&leaves[0], &leaves[1])]);
+    }
+
+    #[test]
+    fn sums_amounts_and_rejects_duplicates() {
+        let airdrop = build(&parse_csv(CSV).unwrap()).unwrap();
+        assert_eq!(airdrop.total, "600");
+        assert_eq!(airdrop.claims.len(), 3);
+
+        let duplicate = format!("{CSV}5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY,1\n");
+        assert!(parse_csv(&duplicate).is_err());
+    }
+}
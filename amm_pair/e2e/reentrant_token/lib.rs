@@ -0,0 +1,88 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+/// PSP22 token used by the reentrancy e2e tests of `amm_pair`.
+///
+/// Once `reenter` set a pair, the next transfer of the token calls `swap` of that pair before moving the
+/// tokens, while the pair is still in the middle of its own message. The encoded result of that call is kept
+/// for `reentry_output`.
+#[openbrush::implementation(PSP22)]
+#[openbrush::contract]
+pub mod reentrant_token {
+    use ink::env::call::{build_call, ExecutionInput, Selector};
+    use ink::prelude::vec::Vec;
+    use openbrush::traits::Storage;
+
+    /// selector of `Pair::swap`
+    const SWAP_SELECTOR: [u8; 4] = [0x11, 0x00, 0x4f, 0xa6];
+
+    /// the output of a call, left encoded
+    struct RawOutput(Vec<u8>);
+
+    impl scale::Decode for RawOutput {
+        fn decode<I: scale::Input>(input: &mut I) -> Result<Self, scale::Error> {
+            let mut output = Vec::new();
+            while let Ok(byte) = input.read_byte() {
+                output.push(byte);
+            }
+            Ok(RawOutput(output))
+        }
+    }
+
+    #[ink(storage)]
+    #[derive(Default, Storage)]
+    pub struct ReentrantToken {
+        #[storage_field]
+        psp22: psp22::Data,
+        pair: Option<AccountId>,
+        reentry_output: Vec<u8>,
+    }
+
+    #[overrider(psp22::Internal)]
+    fn _before_token_transfer(
+        &mut self,
+        _from: Option<&AccountId>,
+        _to: Option<&AccountId>,
+        _amount: &Balance,
+    ) -> Result<(), PSP22Error> {
+        if let Some(pair) = self.pair.take() {
+            let token = self.env().account_id();
+            let result = build_call::<ink::env::DefaultEnvironment>()
+                .call(pair)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(SWAP_SELECTOR))
+                        .push_arg(token)
+                        .push_arg(1 as Balance)
+                        .push_arg(0 as Balance),
+                )
+                .returns::<RawOutput>()
+                .try_invoke();
+            self.reentry_output = match result {
+                Ok(Ok(RawOutput(output))) => output,
+                Ok(Err(_)) | Err(_) => Vec::new(),
+            };
+        }
+        Ok(())
+    }
+
+    impl ReentrantToken {
+        #[ink(constructor)]
+        pub fn new(total_supply: Balance) -> Self {
+            let mut instance = Self::default();
+            psp22::Internal::_mint_to(&mut instance, Self::env().caller(), total_supply)
+                .expect("Should mint");
+            instance
+        }
+
+        /// calls `swap` of `pair` on the next transfer
+        #[ink(message)]
+        pub fn reenter(&mut self, pair: AccountId) {
+            self.pair = Some(pair);
+        }
+
+        /// the encoded result of the last call of `swap`, empty if the call failed
+        #[ink(message)]
+        pub fn reentry_output(&self) -> Vec<u8> {
+            self.reentry_output.clone()
+        }
+    }
+}
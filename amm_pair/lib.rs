@@ -0,0 +1,633 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+/// constant product pool of two PSP22 tokens, the liquidity shares are the PSP22 tokens of the pair itself.
+///
+/// Swaps keep `reserve_0 * reserve_1` from decreasing, `FEE_BPS` of every input stays in the pool for the
+/// liquidity providers. The caller approves the pair to spend its tokens before adding liquidity or swapping.
+///
+/// The pair calls the tokens in the middle of its messages, a token calling back into `add_liquidity`,
+/// `remove_liquidity` or `swap` fails with `PairError::Locked`.
+#[openbrush::implementation(PSP22)]
+#[openbrush::contract]
+pub mod amm_pair {
+    use ink::prelude::vec::Vec;
+    use ink::storage::Lazy;
+    use openbrush::contracts::psp22::PSP22Ref;
+    use openbrush::traits::Storage;
+    use y_psp22_receiver::{accept_tokens, PSP22Receiver, PSP22ReceiverError};
+
+    /// fee of swaps in basis points of the input amount
+    pub const FEE_BPS: u128 = 30;
+
+    pub const BPS_DENOMINATOR: u128 = 10_000;
+
+    /// liquidity locked in the pair by the first deposit, so the pool can't be drained to zero
+    pub const MINIMUM_LIQUIDITY: Balance = 1_000;
+
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum PairError {
+        /// the token is neither `token_0` nor `token_1`
+        UnknownToken,
+        /// a deposit too small to mint liquidity or a swap of 0
+        InsufficientAmount,
+        /// the pool has no liquidity to swap against or to withdraw
+        InsufficientLiquidity,
+        /// the result is below the minimum the caller accepts
+        SlippageExceeded,
+        Overflow,
+        /// a token called back into the pair while it was adding liquidity, removing liquidity or swapping
+        Locked,
+        /// the balances of the pair after a swap multiply to less than the reserves before
+        ProductDecreased,
+        /// the transfer of a pair token or the liquidity token failed
+        PSP22(PSP22Error),
+    }
+
+    impl From<PSP22Error> for PairError {
+        fn from(error: PSP22Error) -> Self {
+            PairError::PSP22(error)
+        }
+    }
+
+    #[ink(event)]
+    pub struct LiquidityAdded {
+        #[ink(topic)]
+        provider: AccountId,
+        amount_0: Balance,
+        amount_1: Balance,
+        liquidity: Balance,
+    }
+
+    #[ink(event)]
+    pub struct LiquidityRemoved {
+        #[ink(topic)]
+        provider: AccountId,
+        amount_0: Balance,
+        amount_1: Balance,
+        liquidity: Balance,
+    }
+
+    #[ink(event)]
+    pub struct Swapped {
+        #[ink(topic)]
+        trader: AccountId,
+        token_in: AccountId,
+        amount_in: Balance,
+        amount_out: Balance,
+    }
+
+    #[ink(storage)]
+    #[derive(Default, Storage)]
+    pub struct Pair {
+        #[storage_field]
+        psp22: psp22::Data,
+        token_0: AccountId,
+        token_1: AccountId,
+        /// balances of the pair tokens held by the pool, updated after every change
+        reserve_0: Balance,
+        reserve_1: Balance,
+        /// set while a message calls the tokens, in its own cell so a call back into the pair reads it
+        locked: Lazy<bool>,
+    }
+
+    /// output of a swap of `amount_in` against the reserves, after the fee
+    pub fn amount_out(
+        amount_in: Balance,
+        reserve_in: Balance,
+        reserve_out: Balance,
+    ) -> Result<Balance, PairError> {
+        if amount_in == 0 {
+            return Err(PairError::InsufficientAmount);
+        }
+        if reserve_in == 0 || reserve_out == 0 {
+            return Err(PairError::InsufficientLiquidity);
+        }
+        let amount_in_with_fee = amount_in
+            .checked_mul(BPS_DENOMINATOR - FEE_BPS)
+            .ok_or(PairError::Overflow)?;
+        let numerator = amount_in_with_fee
+            .checked_mul(reserve_out)
+            .ok_or(PairError::Overflow)?;
+        let denominator = reserve_in
+            .checked_mul(BPS_DENOMINATOR)
+            .and_then(|reserve| reserve.checked_add(amount_in_with_fee))
+            .ok_or(PairError::Overflow)?;
+        Ok(numerator / denominator)
+    }
+
+    /// integer square root, rounded down
+    pub fn sqrt(value: u128) -> u128 {
+        if value < 2 {
+            return value;
+        }
+        let mut root = value;
+        let mut next = value / 2 + 1;
+        while next < root {
+            root = next;
+            next = (value / next + next) / 2;
+        }
+        root
+    }
+
+    impl Pair {
+        #[ink(constructor)]
+        pub fn new(token_0: AccountId, token_1: AccountId) -> Self {
+            assert!(token_0 != token_1, "the tokens of a pair must differ");
+            Self {
+                token_0,
+                token_1,
+                ..Default::default()
+            }
+        }
+
+        /// deposits up to the desired amounts in the ratio of the reserves and mints liquidity to the caller.
+        ///
+        /// The first deposit sets the ratio, `MINIMUM_LIQUIDITY` of its liquidity stays locked in the pair.
+        #[ink(message)]
+        pub fn add_liquidity(
+            &mut self,
+            amount_0_desired: Balance,
+            amount_1_desired: Balance,
+            min_liquidity: Balance,
+        ) -> Result<Balance, PairError> {
+            self.lock()?;
+            let provider = self.env().caller();
+            let total_supply = psp22::Internal::_total_supply(self);
+            let (amount_0, amount_1, liquidity) = if total_supply == 0 {
+                let product = amount_0_desired
+                    .checked_mul(amount_1_desired)
+                    .ok_or(PairError::Overflow)?;
+                let liquidity = sqrt(product)
+                    .checked_sub(MINIMUM_LIQUIDITY)
+                    .filter(|liquidity| *liquidity > 0)
+                    .ok_or(PairError::InsufficientAmount)?;
+                let pair = self.env().account_id();
+                psp22::Internal::_mint_to(self, pair, MINIMUM_LIQUIDITY)?;
+                (amount_0_desired, amount_1_desired, liquidity)
+            } else {
+                let (amount_0, amount_1) =
+                    self.optimal_amounts(amount_0_desired, amount_1_desired)?;
+                let liquidity = Self::share(amount_0, total_supply, self.reserve_0)?
+                    .min(Self::share(amount_1, total_supply, self.reserve_1)?);
+                if liquidity == 0 {
+                    return Err(PairError::InsufficientAmount);
+                }
+                (amount_0, amount_1, liquidity)
+            };
+            if liquidity < min_liquidity {
+                return Err(PairError::SlippageExceeded);
+            }
+
+            self.transfer_in(self.token_0, provider, amount_0)?;
+            self.transfer_in(self.token_1, provider, amount_1)?;
+            psp22::Internal::_mint_to(self, provider, liquidity)?;
+            self.update_reserves();
+            self.unlock();
+            self.env().emit_event(LiquidityAdded {
+                provider,
+                amount_0,
+                amount_1,
+                liquidity,
+            });
+            Ok(liquidity)
+        }
+
+        /// burns `liquidity` of the caller and pays out its share of both reserves
+        #[ink(message)]
+        pub fn remove_liquidity(
+            &mut self,
+            liquidity: Balance,
+            min_amount_0: Balance,
+            min_amount_1: Balance,
+        ) -> Result<(Balance, Balance), PairError> {
+            self.lock()?;
+            let provider = self.env().caller();
+            let total_supply = psp22::Internal::_total_supply(self);
+            if liquidity == 0 || total_supply == 0 {
+                return Err(PairError::InsufficientLiquidity);
+            }
+            let amount_0 = Self::share(liquidity, self.reserve_0, total_supply)?;
+            let amount_1 = Self::share(liquidity, self.reserve_1, total_supply)?;
+            if amount_0 < min_amount_0 || amount_1 < min_amount_1 {
+                return Err(PairError::SlippageExceeded);
+            }
+
+            psp22::Internal::_burn_from(self, provider, liquidity)?;
+            PSP22Ref::transfer(&self.token_0, provider, amount_0, Vec::new())?;
+            PSP22Ref::transfer(&self.token_1, provider, amount_1, Vec::new())?;
+            self.update_reserves();
+            self.unlock();
+            self.env().emit_event(LiquidityRemoved {
+                provider,
+                amount_0,
+                amount_1,
+                liquidity,
+            });
+            Ok((amount_0, amount_1))
+        }
+
+        /// swaps `amount_in` of `token_in` for the other token, fails if the output is below `min_amount_out`
+        #[ink(message)]
+        pub fn swap(
+            &mut self,
+            token_in: AccountId,
+            amount_in: Balance,
+            min_amount_out: Balance,
+        ) -> Result<Balance, PairError> {
+            self.lock()?;
+            let trader = self.env().caller();
+            let amount_out = self.get_amount_out(token_in, amount_in)?;
+            if amount_out < min_amount_out {
+                return Err(PairError::SlippageExceeded);
+            }
+            let token_out = if token_in == self.token_0 {
+                self.token_1
+            } else {
+                self.token_0
+            };
+
+            let product = self.product()?;
+            self.transfer_in(token_in, trader, amount_in)?;
+            PSP22Ref::transfer(&token_out, trader, amount_out, Vec::new())?;
+            self.update_reserves();
+            // fees on transfers of the tokens, taken from the input or paid by the pair, must not come out of the pool
+            if self.product()? < product {
+                return Err(PairError::ProductDecreased);
+            }
+            self.unlock();
+            self.env().emit_event(Swapped {
+                trader,
+                token_in,
+                amount_in,
+                amount_out,
+            });
+            Ok(amount_out)
+        }
+
+        /// output of a swap of `amount_in` of `token_in` at the current reserves
+        #[ink(message)]
+        pub fn get_amount_out(
+            &self,
+            token_in: AccountId,
+            amount_in: Balance,
+        ) -> Result<Balance, PairError> {
+            if token_in == self.token_0 {
+                amount_out(amount_in, self.reserve_0, self.reserve_1)
+            } else if token_in == self.token_1 {
+                amount_out(amount_in, self.reserve_1, self.reserve_0)
+            } else {
+                Err(PairError::UnknownToken)
+            }
+        }
+
+        #[ink(message)]
+        pub fn tokens(&self) -> (AccountId, AccountId) {
+            (self.token_0, self.token_1)
+        }
+
+        #[ink(message)]
+        pub fn reserves(&self) -> (Balance, Balance) {
+            (self.reserve_0, self.reserve_1)
+        }
+
+        /// the desired amount of one token and the matching amount of the other, whichever fits into both
+        fn optimal_amounts(
+            &self,
+            amount_0_desired: Balance,
+            amount_1_desired: Balance,
+        ) -> Result<(Balance, Balance), PairError> {
+            let amount_1 = Self::share(amount_0_desired, self.reserve_1, self.reserve_0)?;
+            if amount_1 <= amount_1_desired {
+                return Ok((amount_0_desired, amount_1));
+            }
+            let amount_0 = Self::share(amount_1_desired, self.reserve_0, self.reserve_1)?;
+            Ok((amount_0, amount_1_desired))
+        }
+
+        /// `amount * numerator / denominator`, rounded down
+        fn share(
+            amount: Balance,
+            numerator: Balance,
+            denominator: Balance,
+        ) -> Result<Balance, PairError> {
+            if denominator == 0 {
+                return Err(PairError::InsufficientLiquidity);
+            }
+            amount
+                .checked_mul(numerator)
+                .map(|product| product / denominator)
+                .ok_or(PairError::Overflow)
+        }
+
+        /// transfers `amount` of `token` from `from` to the pair with the allowance of the pair
+        fn transfer_in(
+            &self,
+            token: AccountId,
+            from: AccountId,
+            amount: Balance,
+        ) -> Result<(), PairError> {
+            y_psp22_receiver::transfer_in(token, from, amount).map_err(PairError::PSP22)
+        }
+
+        /// fails if another message of the pair is calling the tokens. A message failing after `lock` needs no
+        /// `unlock`, its changes of the storage are reverted.
+        fn lock(&mut self) -> Result<(), PairError> {
+            if self.locked.get_or_default() {
+                return Err(PairError::Locked);
+            }
+            self.locked.set(&true);
+            Ok(())
+        }
+
+        fn unlock(&mut self) {
+            self.locked.set(&false);
+        }
+
+        fn product(&self) -> Result<Balance, PairError> {
+            self.reserve_0
+                .checked_mul(self.reserve_1)
+                .ok_or(PairError::Overflow)
+        }
+
+        /// sets the reserves to the balances of the pair, which also covers fees of the tokens on transfers
+        fn update_reserves(&mut self) {
+            let pair = self.env().account_id();
+            self.reserve_0 = PSP22Ref::balance_of(&self.token_0, pair);
+            self.reserve_1 = PSP22Ref::balance_of(&self.token_1, pair);
+        }
+    }
+
+    /// accepts transfers of the pair tokens only
+    impl PSP22Receiver for Pair {
+        #[ink(message)]
+        fn before_received(
+            &mut self,
+            _operator: AccountId,
+            _from: AccountId,
+            _value: Balance,
+            _data: Vec<u8>,
+        ) -> Result<(), PSP22ReceiverError> {
+            accept_tokens(self.env().caller(), &[self.token_0, self.token_1])
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[ink::test]
+        fn amount_out_keeps_the_product_and_charges_the_fee() {
+            // without fee the output would be 1000 * 1000 / 1100 = 90
+            assert_eq!(amount_out(100, 1_000, 1_000), Ok(90));
+            assert_eq!(amount_out(10, 1_000, 1_000), Ok(9));
+            assert_eq!(
+                amount_out(0, 1_000, 1_000),
+                Err(PairError::InsufficientAmount)
+            );
+            assert_eq!(
+                amount_out(10, 0, 1_000),
+                Err(PairError::InsufficientLiquidity)
+            );
+            assert_eq!(amount_out(u128::MAX, 1, 1), Err(PairError::Overflow));
+
+            let (reserve_in, reserve_out) = (5_000u128, 20_000u128);
+            let out = amount_out(700, reserve_in, reserve_out).unwrap();
+            assert!((reserve_in + 700) * (reserve_out - out) >= reserve_in * reserve_out);
+        }
+
+        #[ink::test]
+        fn sqrt_rounds_down() {
+            assert_eq!(sqrt(0), 0);
+            assert_eq!(sqrt(1), 1);
+            assert_eq!(sqrt(15), 3);
+            assert_eq!(sqrt(16), 4);
+            assert_eq!(sqrt(1_000_000), 1_000);
+            assert_eq!(sqrt(u128::MAX), u64::MAX as u128);
+        }
+
+        #[ink::test]
+        fn unknown_tokens_have_no_quote() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let pair = Pair::new(accounts.alice, accounts.bob);
+
+            assert_eq!(
+                pair.get_amount_out(accounts.charlie, 10),
+                Err(PairError::UnknownToken)
+            );
+            assert_eq!(
+                pair.get_amount_out(accounts.alice, 10),
+                Err(PairError::InsufficientLiquidity)
+            );
+        }
+    }
+
+    #[cfg(all(test, feature = "e2e-tests"))]
+    pub mod e2e_tests {
+        use super::*;
+        use ink_e2e::subxt::tx::Signer;
+        use ink_e2e::subxt::utils::AccountId32;
+        use ink_e2e::{build_message, Keypair, PolkadotConfig};
+        use openbrush::contracts::psp22::psp22_external::PSP22;
+        use reentrant_token::reentrant_token::ReentrantTokenRef;
+        use y_psp22_token::y_psp22_token::YTokenRef;
+
+        type E2EResult<T> = Result<T, Box<dyn std::error::Error>>;
+
+        fn address_of(keypair: &Keypair) -> AccountId {
+            let address: AccountId32 = <Keypair as Signer<PolkadotConfig>>::account_id(keypair);
+            address.0.into()
+        }
+
+        #[ink_e2e::test(additional_contracts = "../contract/Cargo.toml")]
+        async fn provides_liquidity_and_swaps(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            let mut tokens = Vec::new();
+            for _ in 0..2 {
+                let token = client
+                    .instantiate(
+                        "y_psp22_token",
+                        &ink_e2e::alice(),
+                        YTokenRef::new(1_000_000),
+                        0,
+                        None,
+                    )
+                    .await
+                    .expect("instantiate token failed")
+                    .account_id;
+                tokens.push(token);
+            }
+            let (token_0, token_1) = (tokens[0], tokens[1]);
+            let pair = client
+                .instantiate(
+                    "amm_pair",
+                    &ink_e2e::alice(),
+                    PairRef::new(token_0, token_1),
+                    0,
+                    None,
+                )
+                .await
+                .expect("instantiate pair failed")
+                .account_id;
+            for token in [token_0, token_1] {
+                let approve = build_message::<YTokenRef>(token.clone())
+                    .call(|token| token.approve(pair, Balance::MAX));
+                client
+                    .call(&ink_e2e::alice(), approve, 0, None)
+                    .await
+                    .expect("approve failed");
+            }
+
+            let add = build_message::<PairRef>(pair.clone())
+                .call(|pair| pair.add_liquidity(100_000, 400_000, 0));
+            let liquidity = client
+                .call(&ink_e2e::alice(), add, 0, None)
+                .await
+                .expect("add liquidity failed")
+                .return_value()
+                .expect("add liquidity returned an error");
+            assert_eq!(liquidity, 200_000 - MINIMUM_LIQUIDITY);
+
+            let quote = build_message::<PairRef>(pair.clone())
+                .call(|pair| pair.get_amount_out(token_0, 1_000));
+            let quote = client
+                .call_dry_run(&ink_e2e::alice(), &quote, 0, None)
+                .await
+                .return_value()
+                .expect("quote failed");
+
+            let greedy_swap = build_message::<PairRef>(pair.clone())
+                .call(|pair| pair.swap(token_0, 1_000, quote + 1));
+            let result = client
+                .call_dry_run(&ink_e2e::alice(), &greedy_swap, 0, None)
+                .await;
+            assert_eq!(result.return_value(), Err(PairError::SlippageExceeded));
+
+            let swap = build_message::<PairRef>(pair.clone())
+                .call(|pair| pair.swap(token_0, 1_000, quote));
+            client
+                .call(&ink_e2e::alice(), swap, 0, None)
+                .await
+                .expect("swap failed");
+
+            let reserves = build_message::<PairRef>(pair.clone()).call(|pair| pair.reserves());
+            let reserves = client
+                .call_dry_run(&ink_e2e::alice(), &reserves, 0, None)
+                .await
+                .return_value();
+            assert_eq!(reserves, (101_000, 400_000 - quote));
+
+            let remove = build_message::<PairRef>(pair.clone())
+                .call(|pair| pair.remove_liquidity(liquidity, 0, 0));
+            client
+                .call(&ink_e2e::alice(), remove, 0, None)
+                .await
+                .expect("remove liquidity failed");
+
+            let alice = address_of(&ink_e2e::alice());
+            let balance_of_alice =
+                build_message::<YTokenRef>(token_0.clone()).call(|token| token.balance_of(alice));
+            let balance = client
+                .call_dry_run(&ink_e2e::alice(), &balance_of_alice, 0, None)
+                .await
+                .return_value();
+            // the locked minimum liquidity keeps its share of the reserves in the pair
+            assert!(balance > 1_000_000 - 1_000);
+            assert!(balance < 1_000_000);
+
+            Ok(())
+        }
+
+        #[ink_e2e::test(
+            additional_contracts = "../contract/Cargo.toml e2e/reentrant_token/Cargo.toml"
+        )]
+        async fn tokens_calling_back_find_the_pair_locked(
+            mut client: ink_e2e::Client<C, E>,
+        ) -> E2EResult<()> {
+            let token_0 = client
+                .instantiate(
+                    "y_psp22_token",
+                    &ink_e2e::alice(),
+                    YTokenRef::new(1_000_000),
+                    0,
+                    None,
+                )
+                .await
+                .expect("instantiate token failed")
+                .account_id;
+            let token_1 = client
+                .instantiate(
+                    "reentrant_token",
+                    &ink_e2e::alice(),
+                    ReentrantTokenRef::new(1_000_000),
+                    0,
+                    None,
+                )
+                .await
+                .expect("instantiate reentrant token failed")
+                .account_id;
+            let pair = client
+                .instantiate(
+                    "amm_pair",
+                    &ink_e2e::alice(),
+                    PairRef::new(token_0, token_1),
+                    0,
+                    None,
+                )
+                .await
+                .expect("instantiate pair failed")
+                .account_id;
+            let approve = build_message::<YTokenRef>(token_0.clone())
+                .call(|token| token.approve(pair, Balance::MAX));
+            client
+                .call(&ink_e2e::alice(), approve, 0, None)
+                .await
+                .expect("approve failed");
+            let approve = build_message::<ReentrantTokenRef>(token_1.clone())
+                .call(|token| token.approve(pair, Balance::MAX));
+            client
+                .call(&ink_e2e::alice(), approve, 0, None)
+                .await
+                .expect("approve failed");
+            let add = build_message::<PairRef>(pair.clone())
+                .call(|pair| pair.add_liquidity(100_000, 100_000, 0));
+            client
+                .call(&ink_e2e::alice(), add, 0, None)
+                .await
+                .expect("add liquidity failed");
+
+            let reenter = build_message::<ReentrantTokenRef>(token_1.clone())
+                .call(|token| token.reenter(pair));
+            client
+                .call(&ink_e2e::alice(), reenter, 0, None)
+                .await
+                .expect("reenter failed");
+            let swap =
+                build_message::<PairRef>(pair.clone()).call(|pair| pair.swap(token_1, 1_000, 0));
+            let amount_out = client
+                .call(&ink_e2e::alice(), swap, 0, None)
+                .await
+                .expect("swap failed")
+                .return_value()
+                .expect("swap returned an error");
+
+            let output = build_message::<ReentrantTokenRef>(token_1.clone())
+                .call(|token| token.reentry_output());
+            let output = client
+                .call_dry_run(&ink_e2e::alice(), &output, 0, None)
+                .await
+                .return_value();
+            let reentry = <Result<Balance, PairError> as scale::Decode>::decode(&mut &output[..])
+                .expect("the swap called back was not answered");
+            assert_eq!(reentry, Err(PairError::Locked));
+
+            let reserves = build_message::<PairRef>(pair.clone()).call(|pair| pair.reserves());
+            let reserves = client
+                .call_dry_run(&ink_e2e::alice(), &reserves, 0, None)
+                .await
+                .return_value();
+            assert_eq!(reserves, (100_000 - amount_out, 101_000));
+
+            Ok(())
+        }
+    }
+}
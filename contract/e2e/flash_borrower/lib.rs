@@ -0,0 +1,59 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+/// borrower used by the flash loan e2e tests of `YToken`.
+///
+/// It repays the loan plus fee by approving the lender, unless it was instantiated with `repay: false`.
+/// The fee has to be transferred to the borrower before the loan, so it accepts every transfer.
+#[openbrush::contract]
+pub mod flash_borrower {
+    use ink::prelude::vec::Vec;
+    use openbrush::contracts::psp22::PSP22Ref;
+    use openbrush::contracts::traits::flashloan::{FlashBorrower, FlashBorrowerError};
+    use openbrush::traits::String;
+    use y_psp22_receiver::{PSP22Receiver, PSP22ReceiverError};
+
+    #[ink(storage)]
+    pub struct FlashBorrowerContract {
+        repay: bool,
+    }
+
+    impl FlashBorrowerContract {
+        #[ink(constructor)]
+        pub fn new(repay: bool) -> Self {
+            Self { repay }
+        }
+    }
+
+    /// the borrower accepts tokens from everyone
+    impl PSP22Receiver for FlashBorrowerContract {
+        #[ink(message)]
+        fn before_received(
+            &mut self,
+            _operator: AccountId,
+            _from: AccountId,
+            _value: Balance,
+            _data: Vec<u8>,
+        ) -> Result<(), PSP22ReceiverError> {
+            Ok(())
+        }
+    }
+
+    impl FlashBorrower for FlashBorrowerContract {
+        #[ink(message)]
+        fn on_flashloan(
+            &mut self,
+            _initiator: AccountId,
+            token: AccountId,
+            amount: Balance,
+            fee: Balance,
+            _data: Vec<u8>,
+        ) -> Result<(), FlashBorrowerError> {
+            if !self.repay {
+                return Ok(());
+            }
+            // the token contract is the lender and takes the repayment with `transfer_from`
+            PSP22Ref::approve(&token, Self::env().caller(), amount + fee)
+                .map_err(|_| FlashBorrowerError::FlashloanRejected(String::from("ApproveFailed")))
+        }
+    }
+}
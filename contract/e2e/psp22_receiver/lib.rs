@@ -0,0 +1,42 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+/// receiver used by the safe transfer e2e tests of `YToken`.
+///
+/// It accepts every transfer, unless it was instantiated with `accept: false`.
+#[ink::contract]
+pub mod psp22_receiver {
+    use ink::prelude::string::String;
+    use ink::prelude::vec::Vec;
+    use y_psp22_receiver::{PSP22Receiver, PSP22ReceiverError};
+
+    #[ink(storage)]
+    pub struct PSP22ReceiverContract {
+        accept: bool,
+    }
+
+    impl PSP22ReceiverContract {
+        #[ink(constructor)]
+        pub fn new(accept: bool) -> Self {
+            Self { accept }
+        }
+    }
+
+    impl PSP22Receiver for PSP22ReceiverContract {
+        #[ink(message)]
+        fn before_received(
+            &mut self,
+            _operator: AccountId,
+            _from: AccountId,
+            _value: Balance,
+            _data: Vec<u8>,
+        ) -> Result<(), PSP22ReceiverError> {
+            if self.accept {
+                Ok(())
+            } else {
+                Err(PSP22ReceiverError::TransferRejected(String::from(
+                    "ReceiverRejects",
+                )))
+            }
+        }
+    }
+}
@@ -0,0 +1,51 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+/// `YToken` as deployed with storage version 1, used by the upgrade e2e tests of `YToken`.
+///
+/// It keeps the packed fields of the root cell of version 1, the mappings of version 1 are left out, since they
+/// don't take space in the root cell.
+#[openbrush::implementation(PSP22, Ownable)]
+#[openbrush::contract]
+pub mod y_psp22_token_v1 {
+    use openbrush::modifiers;
+    use openbrush::traits::{Storage, String};
+
+    #[ink(storage)]
+    #[derive(Default, Storage)]
+    pub struct YToken {
+        #[storage_field]
+        psp22: psp22::Data,
+        #[storage_field]
+        ownable: ownable::Data,
+        flash_fee_bps: u16,
+        storage_version: u32,
+    }
+
+    impl YToken {
+        #[ink(constructor)]
+        pub fn new(total_supply: Balance) -> Self {
+            let mut instance = Self::default();
+
+            psp22::Internal::_mint_to(&mut instance, Self::env().caller(), total_supply)
+                .expect("Should mint");
+            ownable::Internal::_init_with_owner(&mut instance, Self::env().caller());
+            instance.storage_version = 1;
+
+            instance
+        }
+
+        #[ink(message)]
+        #[modifiers(only_owner)]
+        pub fn set_flash_fee_bps(&mut self, fee_bps: u16) -> Result<(), PSP22Error> {
+            self.flash_fee_bps = fee_bps;
+            Ok(())
+        }
+
+        #[ink(message)]
+        #[modifiers(only_owner)]
+        pub fn upgrade(&mut self, code_hash: Hash) -> Result<(), PSP22Error> {
+            ink::env::set_code_hash(&<[u8; 32]>::from(code_hash))
+                .map_err(|_| PSP22Error::Custom(String::from("UpgradeFailed")))
+        }
+    }
+}
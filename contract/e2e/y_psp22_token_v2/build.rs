@@ -0,0 +1,5 @@
+// builds the shared lib.rs with `STORAGE_VERSION` 2. A cfg is used instead of a feature,
+// because cargo-contract builds without default features.
+fn main() {
+    println!("cargo:rustc-cfg=ytoken_v2");
+}
@@ -1,47 +1,1800 @@
 #![cfg_attr(not(feature = "std"), no_std, no_main)]
 
-#[openbrush::implementation(PSP22, PSP22Mintable)]
+#[openbrush::implementation(PSP22, PSP22Mintable, PSP22Metadata, Ownable, Flashmint)]
 #[openbrush::contract]
 pub mod y_psp22_token {
-    use openbrush::traits::Storage;
+    use ink::env::call::{build_call, ExecutionInput, Selector};
+    use ink::env::hash::{Blake2x256, HashOutput};
+    use ink::prelude::vec::Vec;
+    use ink::storage::{Lazy, Mapping};
+    use openbrush::contracts::psp22::PSP22Ref;
+    use openbrush::modifiers;
+    use openbrush::traits::{Storage, String};
+    use scale::Encode;
+    use y_psp22_receiver::{mul_div, PSP22Receiver, PSP22ReceiverError};
 
+    /// version of the storage layout this code works with, `migrate` updates storage of older versions.
+    ///
+    /// The `y_psp22_token_v2` crate in `e2e` builds this file as version 2 to test upgrades.
+    #[cfg(not(ytoken_v2))]
+    pub const STORAGE_VERSION: u32 = 1;
+    #[cfg(ytoken_v2)]
+    pub const STORAGE_VERSION: u32 = 2;
+
+    /// maximum number of recipients of `batch_transfer`, bounds the weight of the call
+    pub const MAX_BATCH_LEN: u32 = 100;
+
+    /// maximum number of accounts `frozen_accounts` returns
+    pub const MAX_PAGE_LEN: u32 = 50;
+
+    /// flash loan fees are given in basis points of the borrowed amount
+    pub const FLASH_FEE_DENOMINATOR: u16 = 10_000;
+
+    /// transfer fees are given in basis points of the transferred amount
+    pub const TRANSFER_FEE_DENOMINATOR: u16 = 10_000;
+
+    /// upper bound of the transfer fee, 10%
+    pub const MAX_TRANSFER_FEE_BPS: u16 = 1_000;
+
+    /// selector of `PSP22Receiver::before_received`, which contracts receiving transfers have to implement
+    pub const BEFORE_RECEIVED_SELECTOR: [u8; 4] = [0xfd, 0xa6, 0xf1, 0xa9];
+
+    /// domain of the payload signed for `permit`, so the signature can't be replayed for other messages
+    pub const PERMIT_DOMAIN: &[u8] = b"YToken::permit";
+
+    /// domain of the payload signed for `delegate_by_signature`
+    pub const DELEGATION_DOMAIN: &[u8] = b"YToken::delegate";
+
+    /// signature of a `permit` payload, made by the owner of the tokens
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum PermitSignature {
+        Sr25519([u8; 64]),
+        /// signature of the blake2_256 hash of the payload, the owner is the blake2_256 hash of the public key
+        Ecdsa([u8; 65]),
+    }
+
+    /// tokens of a beneficiary which unlock linearly between the end of the cliff and `start + duration`
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct VestingSchedule {
+        pub total: Balance,
+        /// amount unlocked with `release`
+        pub released: Balance,
+        pub start: Timestamp,
+        /// nothing vests before `start + cliff`
+        pub cliff: Timestamp,
+        pub duration: Timestamp,
+    }
+
+    impl VestingSchedule {
+        /// amount vested at `now`
+        pub fn vested_at(&self, now: Timestamp) -> Balance {
+            if now < self.start.saturating_add(self.cliff) {
+                return 0;
+            }
+            let elapsed = now - self.start;
+            if elapsed >= self.duration {
+                return self.total;
+            }
+            mul_div(
+                self.total,
+                Balance::from(elapsed),
+                Balance::from(self.duration),
+            )
+        }
+
+        /// amount which can't be transferred yet
+        pub fn locked(&self) -> Balance {
+            self.total - self.released
+        }
+    }
+
+    /// the value a series of checkpoints records
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum CheckpointKey {
+        TotalSupply,
+        Balance(AccountId),
+        Votes(AccountId),
+    }
+
+    /// value of a balance, the total supply or voting power from the end of `block` until the next checkpoint
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct Checkpoint {
+        pub block: BlockNumber,
+        pub value: Balance,
+    }
+
+    #[ink(event)]
+    pub struct Transfer {
+        #[ink(topic)]
+        from: Option<AccountId>,
+        #[ink(topic)]
+        to: Option<AccountId>,
+        value: Balance,
+    }
+
+    #[ink(event)]
+    pub struct Approval {
+        #[ink(topic)]
+        owner: AccountId,
+        #[ink(topic)]
+        spender: AccountId,
+        value: Balance,
+    }
+
+    #[ink(event)]
+    pub struct VestingCreated {
+        #[ink(topic)]
+        beneficiary: AccountId,
+        schedule: VestingSchedule,
+    }
+
+    #[ink(event)]
+    pub struct Released {
+        #[ink(topic)]
+        beneficiary: AccountId,
+        amount: Balance,
+    }
+
+    #[ink(event)]
+    pub struct DelegateChanged {
+        #[ink(topic)]
+        delegator: AccountId,
+        from_delegate: Option<AccountId>,
+        #[ink(topic)]
+        to_delegate: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct DelegateVotesChanged {
+        #[ink(topic)]
+        delegate: AccountId,
+        previous_votes: Balance,
+        new_votes: Balance,
+    }
+
+    #[ink(event)]
+    pub struct AccountFrozen {
+        #[ink(topic)]
+        account: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct AccountUnfrozen {
+        #[ink(topic)]
+        account: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct Rescued {
+        /// `None` for the native balance
+        #[ink(topic)]
+        token: Option<AccountId>,
+        #[ink(topic)]
+        to: AccountId,
+        amount: Balance,
+    }
+
+    /// the packed fields of the root cell have the layout of storage version 1, `flash_fee_bps` and
+    /// `storage_version`. Fields added since live in their own cells, `Lazy` or `Mapping`, so deployed
+    /// contracts read them as unset after an upgrade.
     #[ink(storage)]
     #[derive(Default, Storage)]
     pub struct YToken {
         #[storage_field]
         psp22: psp22::Data,
+        #[storage_field]
+        ownable: ownable::Data,
+        #[storage_field]
+        metadata: metadata::Data,
+        /// number of permits used per owner
+        permit_nonces: Mapping<AccountId, u64>,
+        /// fee of flash loans in basis points
+        flash_fee_bps: u16,
+        /// vesting schedule per beneficiary
+        vesting: Mapping<AccountId, VestingSchedule>,
+        /// checkpoints of every series, indexed by their position
+        checkpoints: Mapping<(CheckpointKey, u32), Checkpoint>,
+        checkpoint_counts: Mapping<CheckpointKey, u32>,
+        /// delegate per delegator, tokens of accounts without delegate don't count as votes
+        delegates: Mapping<AccountId, AccountId>,
+        /// number of signed delegations used per delegator
+        delegation_nonces: Mapping<AccountId, u64>,
+        /// accounts which can't send or receive tokens, with their position in `frozen_accounts`
+        frozen: Mapping<AccountId, u32>,
+        /// the frozen accounts by position, to list them
+        frozen_accounts: Mapping<u32, AccountId>,
+        frozen_count: Lazy<u32>,
+        /// fee of transfers in basis points, paid by the sender on top of the amount
+        transfer_fee_bps: Lazy<u16>,
+        /// receives the transfer fees, no fee is charged without treasury
+        treasury: Lazy<Option<AccountId>>,
+        /// accounts which neither pay nor cause transfer fees
+        fee_exempt: Mapping<AccountId, ()>,
+        /// tokens minted per faucet claim, 0 disables the faucet
+        faucet_amount: Lazy<Balance>,
+        /// milliseconds an account waits between two faucet claims
+        faucet_cooldown: Lazy<Timestamp>,
+        /// timestamp of the last faucet claim per account
+        faucet_claims: Mapping<AccountId, Timestamp>,
+        /// the total supply never exceeds the cap, `None` for no cap
+        supply_cap: Lazy<Option<Balance>>,
+        /// receives the emissions, `None` turns them off
+        emission_recipient: Lazy<Option<AccountId>>,
+        /// tokens emitted per block
+        emission_rate: Lazy<Balance>,
+        /// block up to which the emissions are minted
+        emission_block: Lazy<BlockNumber>,
+        /// version of the storage layout, lags behind `STORAGE_VERSION` between `upgrade` and `migrate`
+        storage_version: u32,
+    }
+
+    /// frozen accounts can't send or receive tokens, locked tokens of vesting schedules can't be transferred or burned
+    /// and mints can't exceed the supply cap.
+    #[overrider(psp22::Internal)]
+    fn _before_token_transfer(
+        &mut self,
+        from: Option<&AccountId>,
+        to: Option<&AccountId>,
+        amount: &Balance,
+    ) -> Result<(), PSP22Error> {
+        if [from, to]
+            .into_iter()
+            .flatten()
+            .any(|account| self.frozen.contains(account))
+        {
+            return Err(PSP22Error::Custom(String::from("AccountFrozen")));
+        }
+        if let (None, Some(cap)) = (from, self.supply_cap.get_or_default()) {
+            let total_supply = psp22::Internal::_total_supply(self);
+            if total_supply
+                .checked_add(*amount)
+                .map_or(true, |supply| supply > cap)
+            {
+                return Err(PSP22Error::Custom(String::from("CapExceeded")));
+            }
+        }
+        if let Some(from) = from {
+            let locked = self.locked_balance(*from);
+            let remaining = psp22::Internal::_balance_of(self, from).checked_sub(*amount);
+            if matches!(remaining, Some(remaining) if remaining < locked) {
+                return Err(PSP22Error::Custom(String::from("TokensLocked")));
+            }
+        }
+        Ok(())
+    }
+
+    #[overrider(psp22::Internal)]
+    fn _emit_transfer_event(
+        &self,
+        from: Option<AccountId>,
+        to: Option<AccountId>,
+        amount: Balance,
+    ) {
+        self.env().emit_event(Transfer {
+            from,
+            to,
+            value: amount,
+        });
+    }
+
+    #[overrider(psp22::Internal)]
+    fn _emit_approval_event(&self, owner: AccountId, spender: AccountId, amount: Balance) {
+        self.env().emit_event(Approval {
+            owner,
+            spender,
+            value: amount,
+        });
+    }
+
+    /// records checkpoints of the changed balances and, when minting or burning, of the total supply,
+    /// and moves the votes between the delegates of the accounts.
+    #[overrider(psp22::Internal)]
+    fn _after_token_transfer(
+        &mut self,
+        from: Option<&AccountId>,
+        to: Option<&AccountId>,
+        amount: &Balance,
+    ) -> Result<(), PSP22Error> {
+        for account in [from, to].into_iter().flatten() {
+            let balance = psp22::Internal::_balance_of(self, account);
+            self.write_checkpoint(CheckpointKey::Balance(*account), balance);
+        }
+        if from.is_none() || to.is_none() {
+            let total_supply = psp22::Internal::_total_supply(self);
+            self.write_checkpoint(CheckpointKey::TotalSupply, total_supply);
+        }
+        let from_delegate = from.and_then(|from| self.delegates.get(from));
+        let to_delegate = to.and_then(|to| self.delegates.get(to));
+        self.move_votes(from_delegate, to_delegate, *amount);
+        Ok(())
+    }
+
+    /// the sender pays the transfer fee on top of `value`
+    #[overrider(PSP22)]
+    fn transfer(&mut self, to: AccountId, value: Balance, data: Vec<u8>) -> Result<(), PSP22Error> {
+        let from = self.env().caller();
+        self.safe_transfer(from, to, value, data)?;
+        self.charge_transfer_fee(from, to, value)
+    }
+
+    /// the allowance has to cover `value` and the transfer fee, both are deducted from it
+    #[overrider(PSP22)]
+    fn transfer_from(
+        &mut self,
+        from: AccountId,
+        to: AccountId,
+        value: Balance,
+        data: Vec<u8>,
+    ) -> Result<(), PSP22Error> {
+        let caller = self.env().caller();
+        let allowance = psp22::Internal::_allowance(self, &from, &caller);
+        let remaining = value
+            .checked_add(self.transfer_fee(from, to, value))
+            .and_then(|total| allowance.checked_sub(total))
+            .ok_or(PSP22Error::InsufficientAllowance)?;
+        psp22::Internal::_approve_from_to(self, from, caller, remaining)?;
+        self.safe_transfer(from, to, value, data)?;
+        self.charge_transfer_fee(from, to, value)
+    }
+
+    /// the fee is burned together with the borrowed amount when the loan is repaid
+    #[overrider(flashmint::Internal)]
+    fn _get_fee(&self, amount: Balance) -> Balance {
+        amount.saturating_mul(self.flash_fee_bps.into()) / Balance::from(FLASH_FEE_DENOMINATOR)
+    }
+
+    /// only the owner mints, everybody else gets tokens from the faucet
+    #[overrider(PSP22Mintable)]
+    #[modifiers(only_owner)]
+    fn mint(&mut self, account: AccountId, amount: Balance) -> Result<(), PSP22Error> {
+        psp22::Internal::_mint_to(self, account, amount)
+    }
+
+    impl YToken {
+        #[ink(constructor)]
+        pub fn new(total_supply: Balance) -> Self {
+            let mut instance = Self::default();
+
+            psp22::Internal::_mint_to(&mut instance, Self::env().caller(), total_supply)
+                .expect("Should mint");
+            ownable::Internal::_init_with_owner(&mut instance, Self::env().caller());
+            instance.storage_version = STORAGE_VERSION;
+
+            instance
+        }
+
+        /// token with name, symbol and decimals, the initial supply is minted to `owner`.
+        ///
+        /// Used by `token_factory`, which instantiates tokens on behalf of their owners.
+        #[ink(constructor)]
+        pub fn with_metadata(
+            name: Option<String>,
+            symbol: Option<String>,
+            decimals: u8,
+            total_supply: Balance,
+            owner: AccountId,
+        ) -> Self {
+            let mut instance = Self::default();
+
+            instance.metadata.name.set(&name);
+            instance.metadata.symbol.set(&symbol);
+            instance.metadata.decimals.set(&decimals);
+            psp22::Internal::_mint_to(&mut instance, owner, total_supply).expect("Should mint");
+            ownable::Internal::_init_with_owner(&mut instance, owner);
+            instance.storage_version = STORAGE_VERSION;
+
+            instance
+        }
+
+        /// approves `spender` to spend `value` tokens of `owner` with a signature of `owner`,
+        /// so a relayer can submit the approval.
+        ///
+        /// The signature is over `permit_message`, which includes the nonce of the owner.
+        #[ink(message)]
+        pub fn permit(
+            &mut self,
+            owner: AccountId,
+            spender: AccountId,
+            value: Balance,
+            deadline: Timestamp,
+            signature: PermitSignature,
+        ) -> Result<(), PSP22Error> {
+            if self.env().block_timestamp() > deadline {
+                return Err(PSP22Error::Custom(String::from("PermitExpired")));
+            }
+            let nonce = self.permit_nonce(owner);
+            let message = self.permit_message(owner, spender, value, nonce, deadline);
+            if !Self::verify_signature(&owner, &message, &signature) {
+                return Err(PSP22Error::Custom(String::from("InvalidSignature")));
+            }
+            self.permit_nonces.insert(owner, &(nonce + 1));
+            psp22::Internal::_approve_from_to(self, owner, spender, value)
+        }
+
+        /// transfers tokens of the caller to every recipient, `data` is passed to each transfer.
+        ///
+        /// The batch is all-or-nothing: it fails before the first transfer if the caller can't pay the total.
+        #[ink(message)]
+        pub fn batch_transfer(
+            &mut self,
+            transfers: Vec<(AccountId, Balance)>,
+            data: Vec<u8>,
+        ) -> Result<(), PSP22Error> {
+            if transfers.len() > MAX_BATCH_LEN as usize {
+                return Err(PSP22Error::Custom(String::from("BatchTooLong")));
+            }
+            let from = self.env().caller();
+            let total = transfers
+                .iter()
+                .try_fold(0 as Balance, |total, (to, value)| {
+                    total
+                        .checked_add(*value)?
+                        .checked_add(self.transfer_fee(from, *to, *value))
+                })
+                .ok_or(PSP22Error::InsufficientBalance)?;
+            let available =
+                psp22::Internal::_balance_of(self, &from).saturating_sub(self.locked_balance(from));
+            if total > available {
+                return Err(PSP22Error::InsufficientBalance);
+            }
+            for (to, value) in transfers {
+                self.safe_transfer(from, to, value, data.clone())?;
+                self.charge_transfer_fee(from, to, value)?;
+            }
+            Ok(())
+        }
+
+        /// blocks all transfers from and to `account`
+        #[ink(message)]
+        #[modifiers(only_owner)]
+        pub fn freeze(&mut self, account: AccountId) -> Result<(), PSP22Error> {
+            if self.frozen.contains(account) {
+                return Err(PSP22Error::Custom(String::from("AlreadyFrozen")));
+            }
+            let index = self.frozen_count.get_or_default();
+            self.frozen.insert(account, &index);
+            self.frozen_accounts.insert(index, &account);
+            self.frozen_count.set(&(index + 1));
+            self.env().emit_event(AccountFrozen { account });
+            Ok(())
+        }
+
+        /// the last frozen account takes the position of `account` in `frozen_accounts`
+        #[ink(message)]
+        #[modifiers(only_owner)]
+        pub fn unfreeze(&mut self, account: AccountId) -> Result<(), PSP22Error> {
+            let index = self
+                .frozen
+                .get(account)
+                .ok_or(PSP22Error::Custom(String::from("NotFrozen")))?;
+            let last = self.frozen_count.get_or_default() - 1;
+            if let Some(moved) = self.frozen_accounts.get(last).filter(|_| index != last) {
+                self.frozen_accounts.insert(index, &moved);
+                self.frozen.insert(moved, &index);
+            }
+            self.frozen.remove(account);
+            self.frozen_accounts.remove(last);
+            self.frozen_count.set(&last);
+            self.env().emit_event(AccountUnfrozen { account });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn is_frozen(&self, account: AccountId) -> bool {
+            self.frozen.contains(account)
+        }
+
+        /// frozen accounts from position `start` on, at most `MAX_PAGE_LEN`
+        #[ink(message)]
+        pub fn frozen_accounts(&self, start: u32, limit: u32) -> Vec<AccountId> {
+            let end = start
+                .saturating_add(limit.min(MAX_PAGE_LEN))
+                .min(self.frozen_count.get_or_default());
+            (start..end)
+                .filter_map(|index| self.frozen_accounts.get(index))
+                .collect()
+        }
+
+        #[ink(message)]
+        pub fn frozen_count(&self) -> u32 {
+            self.frozen_count.get_or_default()
+        }
+
+        /// withdraws tokens held by the contract: PSP22 tokens of `token`, including its own, or the native
+        /// balance if `token` is `None`
+        #[ink(message)]
+        #[modifiers(only_owner)]
+        pub fn rescue(
+            &mut self,
+            token: Option<AccountId>,
+            to: AccountId,
+            amount: Balance,
+        ) -> Result<(), PSP22Error> {
+            let contract = self.env().account_id();
+            match token {
+                None => self
+                    .env()
+                    .transfer(to, amount)
+                    .map_err(|_| PSP22Error::Custom(String::from("NativeTransferFailed")))?,
+                Some(token) if token == contract => {
+                    self.safe_transfer(contract, to, amount, Vec::new())?
+                }
+                Some(token) => PSP22Ref::transfer(&token, to, amount, Vec::new())?,
+            }
+            self.env().emit_event(Rescued { token, to, amount });
+            Ok(())
+        }
+
+        /// replaces the code of the contract, the storage is kept.
+        ///
+        /// The new code must be able to read the current storage layout, `migrate` has to be called after the upgrade.
+        #[ink(message)]
+        #[modifiers(only_owner)]
+        pub fn upgrade(&mut self, code_hash: Hash) -> Result<(), PSP22Error> {
+            ink::env::set_code_hash(&<[u8; 32]>::from(code_hash))
+                .map_err(|_| PSP22Error::Custom(String::from("UpgradeFailed")))
+        }
+
+        /// migrates the storage to `STORAGE_VERSION` after an upgrade
+        #[ink(message)]
+        #[modifiers(only_owner)]
+        pub fn migrate(&mut self) -> Result<(), PSP22Error> {
+            if self.storage_version >= STORAGE_VERSION {
+                return Err(PSP22Error::Custom(String::from("AlreadyMigrated")));
+            }
+            // version 2 keeps the layout of version 1, migrations of changed fields go here
+            self.storage_version = STORAGE_VERSION;
+            Ok(())
+        }
+
+        /// version of the storage layout
+        #[ink(message)]
+        pub fn storage_version(&self) -> u32 {
+            self.storage_version
+        }
+
+        /// version of the storage layout of the deployed code
+        #[ink(message)]
+        pub fn code_version(&self) -> u32 {
+            STORAGE_VERSION
+        }
+
+        /// mints the emissions accrued since the last call to the emission recipient, anyone can call it
+        #[ink(message)]
+        pub fn mint_emissions(&mut self) -> Result<(), PSP22Error> {
+            let recipient = self
+                .emission_recipient
+                .get_or_default()
+                .ok_or(PSP22Error::Custom(String::from("NoEmission")))?;
+            let amount = self.pending_emissions();
+            if amount == 0 {
+                return Err(PSP22Error::Custom(String::from("NothingToMint")));
+            }
+            self.emission_block.set(&self.env().block_number());
+            psp22::Internal::_mint_to(self, recipient, amount)
+        }
+
+        /// emissions accrued since the last `mint_emissions`, bounded by the supply cap.
+        ///
+        /// Emissions above the cap are dropped when minting.
+        #[ink(message)]
+        pub fn pending_emissions(&self) -> Balance {
+            if self.emission_recipient.get_or_default().is_none() {
+                return 0;
+            }
+            let blocks = self
+                .env()
+                .block_number()
+                .saturating_sub(self.emission_block.get_or_default());
+            let pending = self
+                .emission_rate
+                .get_or_default()
+                .saturating_mul(blocks.into());
+            match self.supply_cap.get_or_default() {
+                Some(cap) => pending.min(cap.saturating_sub(psp22::Internal::_total_supply(self))),
+                None => pending,
+            }
+        }
+
+        /// recipient, tokens per block and the block up to which the emissions are minted
+        #[ink(message)]
+        pub fn emission(&self) -> (Option<AccountId>, Balance, BlockNumber) {
+            (
+                self.emission_recipient.get_or_default(),
+                self.emission_rate.get_or_default(),
+                self.emission_block.get_or_default(),
+            )
+        }
+
+        /// changes the emission schedule from the current block on, the pending emissions are minted first
+        #[ink(message)]
+        #[modifiers(only_owner)]
+        pub fn set_emission(
+            &mut self,
+            recipient: Option<AccountId>,
+            rate_per_block: Balance,
+        ) -> Result<(), PSP22Error> {
+            if self.pending_emissions() > 0 {
+                self.mint_emissions()?;
+            }
+            self.emission_recipient.set(&recipient);
+            self.emission_rate.set(&rate_per_block);
+            self.emission_block.set(&self.env().block_number());
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn supply_cap(&self) -> Option<Balance> {
+            self.supply_cap.get_or_default()
+        }
+
+        /// sets the maximum total supply, it can't be below the current total supply
+        #[ink(message)]
+        #[modifiers(only_owner)]
+        pub fn set_supply_cap(&mut self, cap: Option<Balance>) -> Result<(), PSP22Error> {
+            if matches!(cap, Some(cap) if cap < psp22::Internal::_total_supply(self)) {
+                return Err(PSP22Error::Custom(String::from("CapBelowSupply")));
+            }
+            self.supply_cap.set(&cap);
+            Ok(())
+        }
+
+        /// mints the faucet amount to the caller, once per cooldown period.
+        ///
+        /// Meant for dev chains, the owner enables the faucet with `set_faucet`.
+        #[ink(message)]
+        pub fn claim_faucet(&mut self) -> Result<(), PSP22Error> {
+            let amount = self.faucet_amount.get_or_default();
+            if amount == 0 {
+                return Err(PSP22Error::Custom(String::from("FaucetDisabled")));
+            }
+            let caller = self.env().caller();
+            let now = self.env().block_timestamp();
+            if now < self.next_faucet_claim(caller) {
+                return Err(PSP22Error::Custom(String::from("FaucetCooldown")));
+            }
+            self.faucet_claims.insert(caller, &now);
+            psp22::Internal::_mint_to(self, caller, amount)
+        }
+
+        /// amount per claim and cooldown of the faucet in milliseconds
+        #[ink(message)]
+        pub fn faucet(&self) -> (Balance, Timestamp) {
+            (
+                self.faucet_amount.get_or_default(),
+                self.faucet_cooldown.get_or_default(),
+            )
+        }
+
+        /// earliest timestamp `account` can claim from the faucet again
+        #[ink(message)]
+        pub fn next_faucet_claim(&self, account: AccountId) -> Timestamp {
+            self.faucet_claims
+                .get(account)
+                .map(|last_claim| last_claim.saturating_add(self.faucet_cooldown.get_or_default()))
+                .unwrap_or_default()
+        }
+
+        /// sets the amount per claim and the cooldown of the faucet, an amount of 0 disables it
+        #[ink(message)]
+        #[modifiers(only_owner)]
+        pub fn set_faucet(
+            &mut self,
+            amount: Balance,
+            cooldown: Timestamp,
+        ) -> Result<(), PSP22Error> {
+            self.faucet_amount.set(&amount);
+            self.faucet_cooldown.set(&cooldown);
+            Ok(())
+        }
+
+        /// fee of flash loans in basis points of the borrowed amount
+        #[ink(message)]
+        pub fn flash_fee_bps(&self) -> u16 {
+            self.flash_fee_bps
+        }
+
+        /// sets the fee of flash loans, at most `FLASH_FEE_DENOMINATOR` basis points
+        #[ink(message)]
+        #[modifiers(only_owner)]
+        pub fn set_flash_fee_bps(&mut self, fee_bps: u16) -> Result<(), PSP22Error> {
+            if fee_bps > FLASH_FEE_DENOMINATOR {
+                return Err(PSP22Error::Custom(String::from("FlashFeeTooHigh")));
+            }
+            self.flash_fee_bps = fee_bps;
+            Ok(())
+        }
+
+        /// fee of transfers in basis points of the transferred amount
+        #[ink(message)]
+        pub fn transfer_fee_bps(&self) -> u16 {
+            self.transfer_fee_bps.get_or_default()
+        }
+
+        /// sets the fee of transfers, at most `MAX_TRANSFER_FEE_BPS` basis points
+        #[ink(message)]
+        #[modifiers(only_owner)]
+        pub fn set_transfer_fee_bps(&mut self, fee_bps: u16) -> Result<(), PSP22Error> {
+            if fee_bps > MAX_TRANSFER_FEE_BPS {
+                return Err(PSP22Error::Custom(String::from("TransferFeeTooHigh")));
+            }
+            self.transfer_fee_bps.set(&fee_bps);
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn treasury(&self) -> Option<AccountId> {
+            self.treasury.get_or_default()
+        }
+
+        /// sets the account receiving the transfer fees, `None` turns the fees off
+        #[ink(message)]
+        #[modifiers(only_owner)]
+        pub fn set_treasury(&mut self, treasury: Option<AccountId>) -> Result<(), PSP22Error> {
+            self.treasury.set(&treasury);
+            Ok(())
+        }
+
+        /// transfers from and to exempt accounts are free
+        #[ink(message)]
+        #[modifiers(only_owner)]
+        pub fn set_fee_exempt(
+            &mut self,
+            account: AccountId,
+            exempt: bool,
+        ) -> Result<(), PSP22Error> {
+            if exempt {
+                self.fee_exempt.insert(account, &());
+            } else {
+                self.fee_exempt.remove(account);
+            }
+            Ok(())
+        }
+
+        /// the treasury is always exempt
+        #[ink(message)]
+        pub fn is_fee_exempt(&self, account: AccountId) -> bool {
+            self.treasury() == Some(account) || self.fee_exempt.contains(account)
+        }
+
+        /// fee the sender pays for transferring `amount` from `from` to `to`, rounded down
+        #[ink(message)]
+        pub fn transfer_fee(&self, from: AccountId, to: AccountId, amount: Balance) -> Balance {
+            if self.treasury().is_none() || self.is_fee_exempt(from) || self.is_fee_exempt(to) {
+                return 0;
+            }
+            amount.saturating_mul(self.transfer_fee_bps().into())
+                / Balance::from(TRANSFER_FEE_DENOMINATOR)
+        }
+
+        /// transfers `amount` tokens of the owner to `beneficiary`, locked by a vesting schedule.
+        ///
+        /// A beneficiary has one schedule at a time, a new one can be created once the old one is fully released.
+        #[ink(message)]
+        #[modifiers(only_owner)]
+        pub fn create_vesting(
+            &mut self,
+            beneficiary: AccountId,
+            amount: Balance,
+            start: Timestamp,
+            cliff: Timestamp,
+            duration: Timestamp,
+        ) -> Result<(), PSP22Error> {
+            if duration == 0 || cliff > duration {
+                return Err(PSP22Error::Custom(String::from("InvalidSchedule")));
+            }
+            if self.locked_balance(beneficiary) > 0 {
+                return Err(PSP22Error::Custom(String::from("VestingExists")));
+            }
+            let owner = self.env().caller();
+            self.safe_transfer(owner, beneficiary, amount, Vec::new())?;
+            let schedule = VestingSchedule {
+                total: amount,
+                released: 0,
+                start,
+                cliff,
+                duration,
+            };
+            self.vesting.insert(beneficiary, &schedule);
+            self.env().emit_event(VestingCreated {
+                beneficiary,
+                schedule,
+            });
+            Ok(())
+        }
+
+        /// unlocks the vested tokens of the caller
+        #[ink(message)]
+        pub fn release(&mut self) -> Result<(), PSP22Error> {
+            let beneficiary = self.env().caller();
+            let mut schedule = self
+                .vesting
+                .get(beneficiary)
+                .ok_or(PSP22Error::Custom(String::from("NoVesting")))?;
+            let amount = schedule
+                .vested_at(self.env().block_timestamp())
+                .saturating_sub(schedule.released);
+            if amount == 0 {
+                return Err(PSP22Error::Custom(String::from("NothingToRelease")));
+            }
+            schedule.released += amount;
+            self.vesting.insert(beneficiary, &schedule);
+            self.env().emit_event(Released {
+                beneficiary,
+                amount,
+            });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn vesting_schedule(&self, beneficiary: AccountId) -> Option<VestingSchedule> {
+            self.vesting.get(beneficiary)
+        }
+
+        /// amount of the schedule of `beneficiary` vested by now, including the released amount
+        #[ink(message)]
+        pub fn vested_amount(&self, beneficiary: AccountId) -> Balance {
+            self.vesting
+                .get(beneficiary)
+                .map(|schedule| schedule.vested_at(self.env().block_timestamp()))
+                .unwrap_or_default()
+        }
+
+        /// amount of tokens of `account` which can't be transferred
+        #[ink(message)]
+        pub fn locked_balance(&self, account: AccountId) -> Balance {
+            self.vesting
+                .get(account)
+                .map(|schedule| schedule.locked())
+                .unwrap_or_default()
+        }
+
+        /// balance of `account` at the end of `block`
+        #[ink(message)]
+        pub fn balance_of_at(&self, account: AccountId, block: BlockNumber) -> Balance {
+            self.checkpoint_value_at(CheckpointKey::Balance(account), block)
+        }
+
+        /// total supply at the end of `block`
+        #[ink(message)]
+        pub fn total_supply_at(&self, block: BlockNumber) -> Balance {
+            self.checkpoint_value_at(CheckpointKey::TotalSupply, block)
+        }
+
+        /// delegates the votes of the caller's tokens to `delegatee`, the caller can delegate to itself
+        #[ink(message)]
+        pub fn delegate(&mut self, delegatee: AccountId) -> Result<(), PSP22Error> {
+            let delegator = self.env().caller();
+            self.delegate_votes(delegator, delegatee);
+            Ok(())
+        }
+
+        /// delegates the votes of `delegator` with its signature over `delegation_message`,
+        /// so a relayer can submit the delegation
+        #[ink(message)]
+        pub fn delegate_by_signature(
+            &mut self,
+            delegator: AccountId,
+            delegatee: AccountId,
+            expiry: Timestamp,
+            signature: PermitSignature,
+        ) -> Result<(), PSP22Error> {
+            if self.env().block_timestamp() > expiry {
+                return Err(PSP22Error::Custom(String::from("SignatureExpired")));
+            }
+            let nonce = self.delegation_nonce(delegator);
+            let message = self.delegation_message(delegator, delegatee, nonce, expiry);
+            if !Self::verify_signature(&delegator, &message, &signature) {
+                return Err(PSP22Error::Custom(String::from("InvalidSignature")));
+            }
+            self.delegation_nonces.insert(delegator, &(nonce + 1));
+            self.delegate_votes(delegator, delegatee);
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn delegates(&self, account: AccountId) -> Option<AccountId> {
+            self.delegates.get(account)
+        }
+
+        /// current voting power of `account`
+        #[ink(message)]
+        pub fn get_votes(&self, account: AccountId) -> Balance {
+            self.checkpoint_value_at(CheckpointKey::Votes(account), self.env().block_number())
+        }
+
+        /// voting power of `account` at the end of `block`, which must be finished
+        #[ink(message)]
+        pub fn get_past_votes(
+            &self,
+            account: AccountId,
+            block: BlockNumber,
+        ) -> Result<Balance, PSP22Error> {
+            if block >= self.env().block_number() {
+                return Err(PSP22Error::Custom(String::from("BlockNotYetMined")));
+            }
+            Ok(self.checkpoint_value_at(CheckpointKey::Votes(account), block))
+        }
+
+        /// the nonce the next signed delegation of `delegator` has to be signed with
+        #[ink(message)]
+        pub fn delegation_nonce(&self, delegator: AccountId) -> u64 {
+            self.delegation_nonces.get(delegator).unwrap_or_default()
+        }
+
+        /// the payload signed for `delegate_by_signature`, wrapped in `<Bytes>` like `permit_message`
+        pub fn delegation_message(
+            &self,
+            delegator: AccountId,
+            delegatee: AccountId,
+            nonce: u64,
+            expiry: Timestamp,
+        ) -> Vec<u8> {
+            let payload = (
+                DELEGATION_DOMAIN,
+                self.env().account_id(),
+                delegator,
+                delegatee,
+                nonce,
+                expiry,
+            )
+                .encode();
+            [b"<Bytes>".as_slice(), &payload, b"</Bytes>".as_slice()].concat()
+        }
+
+        /// the nonce the next permit of `owner` has to be signed with
+        #[ink(message)]
+        pub fn permit_nonce(&self, owner: AccountId) -> u64 {
+            self.permit_nonces.get(owner).unwrap_or_default()
+        }
+
+        /// the payload signed for `permit`, wrapped in `<Bytes>` like the `signRaw` of browser extensions does
+        pub fn permit_message(
+            &self,
+            owner: AccountId,
+            spender: AccountId,
+            value: Balance,
+            nonce: u64,
+            deadline: Timestamp,
+        ) -> Vec<u8> {
+            let payload = (
+                PERMIT_DOMAIN,
+                self.env().account_id(),
+                owner,
+                spender,
+                value,
+                nonce,
+                deadline,
+            )
+                .encode();
+            [b"<Bytes>".as_slice(), &payload, b"</Bytes>".as_slice()].concat()
+        }
+
+        /// moves the fee of transferring `amount` from `from` to `to` to the treasury.
+        ///
+        /// Only the transfer messages charge it, internal moves like `create_vesting` or `rescue` are free. The
+        /// treasury is not asked with `before_received`.
+        fn charge_transfer_fee(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            amount: Balance,
+        ) -> Result<(), PSP22Error> {
+            let fee = self.transfer_fee(from, to, amount);
+            if let (Some(treasury), true) = (self.treasury(), fee > 0) {
+                psp22::Internal::_transfer_from_to(self, from, treasury, fee, Vec::new())?;
+            }
+            Ok(())
+        }
+
+        /// transfers `value` from `from` to `to` once `to` accepted it with `before_received`
+        fn safe_transfer(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            value: Balance,
+            data: Vec<u8>,
+        ) -> Result<(), PSP22Error> {
+            self.check_receiver(from, to, value, &data)?;
+            psp22::Internal::_transfer_from_to(self, from, to, value, data)
+        }
+
+        /// calls `before_received` of `to` if it is a contract, contracts which don't implement it can't receive tokens
+        fn check_receiver(
+            &self,
+            from: AccountId,
+            to: AccountId,
+            value: Balance,
+            data: &[u8],
+        ) -> Result<(), PSP22Error> {
+            if to == self.env().account_id() || !self.env().is_contract(&to) {
+                return Ok(());
+            }
+            let result = build_call::<ink::env::DefaultEnvironment>()
+                .call(to)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(BEFORE_RECEIVED_SELECTOR))
+                        .push_arg(self.env().caller())
+                        .push_arg(from)
+                        .push_arg(value)
+                        .push_arg(data),
+                )
+                .returns::<Result<(), PSP22ReceiverError>>()
+                .try_invoke();
+            match result {
+                Ok(Ok(Ok(()))) => Ok(()),
+                Ok(Ok(Err(PSP22ReceiverError::TransferRejected(reason)))) => {
+                    Err(PSP22Error::SafeTransferCheckFailed(reason))
+                }
+                // the selector is unknown to the receiver
+                Ok(Err(ink::LangError::CouldNotReadInput)) => Err(
+                    PSP22Error::SafeTransferCheckFailed(String::from("ReceiverNotImplemented")),
+                ),
+                Ok(Err(_)) | Err(_) => Err(PSP22Error::SafeTransferCheckFailed(String::from(
+                    "ReceiverFailed",
+                ))),
+            }
+        }
+
+        fn delegate_votes(&mut self, delegator: AccountId, delegatee: AccountId) {
+            let from_delegate = self.delegates.get(delegator);
+            self.delegates.insert(delegator, &delegatee);
+            self.env().emit_event(DelegateChanged {
+                delegator,
+                from_delegate,
+                to_delegate: delegatee,
+            });
+            let balance = psp22::Internal::_balance_of(self, &delegator);
+            self.move_votes(from_delegate, Some(delegatee), balance);
+        }
+
+        fn move_votes(&mut self, from: Option<AccountId>, to: Option<AccountId>, amount: Balance) {
+            if from == to || amount == 0 {
+                return;
+            }
+            if let Some(delegate) = from {
+                let previous_votes = self.get_votes(delegate);
+                self.write_votes(
+                    delegate,
+                    previous_votes,
+                    previous_votes.saturating_sub(amount),
+                );
+            }
+            if let Some(delegate) = to {
+                let previous_votes = self.get_votes(delegate);
+                self.write_votes(
+                    delegate,
+                    previous_votes,
+                    previous_votes.saturating_add(amount),
+                );
+            }
+        }
+
+        fn write_votes(
+            &mut self,
+            delegate: AccountId,
+            previous_votes: Balance,
+            new_votes: Balance,
+        ) {
+            self.write_checkpoint(CheckpointKey::Votes(delegate), new_votes);
+            self.env().emit_event(DelegateVotesChanged {
+                delegate,
+                previous_votes,
+                new_votes,
+            });
+        }
+
+        /// records `value` for the current block, several changes within a block share one checkpoint
+        fn write_checkpoint(&mut self, key: CheckpointKey, value: Balance) {
+            let block = self.env().block_number();
+            let count = self.checkpoint_counts.get(key).unwrap_or_default();
+            let last = count
+                .checked_sub(1)
+                .and_then(|last| Some((last, self.checkpoints.get((key, last))?)));
+            let index = match last {
+                Some((last, checkpoint)) if checkpoint.block == block => last,
+                _ => {
+                    self.checkpoint_counts.insert(key, &(count + 1));
+                    count
+                }
+            };
+            self.checkpoints
+                .insert((key, index), &Checkpoint { block, value });
+        }
+
+        /// value of the last checkpoint at or before `block`, 0 if there is none
+        fn checkpoint_value_at(&self, key: CheckpointKey, block: BlockNumber) -> Balance {
+            // binary search for the number of checkpoints at or before `block`
+            let (mut low, mut high) = (0, self.checkpoint_counts.get(key).unwrap_or_default());
+            while low < high {
+                let mid = low + (high - low) / 2;
+                match self.checkpoints.get((key, mid)) {
+                    Some(checkpoint) if checkpoint.block <= block => low = mid + 1,
+                    _ => high = mid,
+                }
+            }
+            low.checked_sub(1)
+                .and_then(|index| self.checkpoints.get((key, index)))
+                .map(|checkpoint| checkpoint.value)
+                .unwrap_or_default()
+        }
+
+        fn verify_signature(
+            owner: &AccountId,
+            message: &[u8],
+            signature: &PermitSignature,
+        ) -> bool {
+            match signature {
+                PermitSignature::Sr25519(signature) => {
+                    let public_key: &[u8; 32] = owner.as_ref();
+                    ink::env::sr25519_verify(signature, message, public_key).is_ok()
+                }
+                PermitSignature::Ecdsa(signature) => {
+                    let mut message_hash = <Blake2x256 as HashOutput>::Type::default();
+                    ink::env::hash_bytes::<Blake2x256>(message, &mut message_hash);
+                    let mut public_key = [0; 33];
+                    if ink::env::ecdsa_recover(signature, &message_hash, &mut public_key).is_err() {
+                        return false;
+                    }
+                    let mut account = <Blake2x256 as HashOutput>::Type::default();
+                    ink::env::hash_bytes::<Blake2x256>(&public_key, &mut account);
+                    AccountId::from(account) == *owner
+                }
+            }
+        }
+    }
+
+    /// the token accepts other PSP22 tokens, the owner can `rescue` them
+    impl PSP22Receiver for YToken {
+        #[ink(message)]
+        fn before_received(
+            &mut self,
+            _operator: AccountId,
+            _from: AccountId,
+            _value: Balance,
+            _data: Vec<u8>,
+        ) -> Result<(), PSP22ReceiverError> {
+            Ok(())
+        }
     }
 
-    impl YToken {
-        #[ink(constructor)]
-        pub fn new(total_supply: Balance) -> Self {
-            let mut instance = Self::default();
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use openbrush::contracts::psp22::extensions::mintable::PSP22Mintable;
+        use openbrush::contracts::psp22::PSP22;
+        #[ink::test]
+        fn total_supply_works() {
+            let token = YToken::new(100);
+            assert_eq!(PSP22Impl::total_supply(&token), 100);
+        }
+
+        #[ink::test]
+        fn transfer_emits_event() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut token = YToken::new(100);
+            assert!(PSP22Impl::transfer(&mut token, accounts.bob, 10, vec![]).is_ok());
+
+            // one event for the mint in the constructor, one for the transfer
+            let emitted_events = ink::env::test::recorded_events().collect::<Vec<_>>();
+            assert_eq!(emitted_events.len(), 2);
+        }
+
+        #[ink::test]
+        fn permit_approves_with_sr25519_signature() {
+            use sp_core::Pair;
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let pair = sp_core::sr25519::Pair::from_string("//Alice", None).unwrap();
+            let owner = AccountId::from(pair.public().0);
+            let mut token = YToken::new(100);
+
+            let message = token.permit_message(owner, accounts.bob, 10, 0, 1000);
+            let signature = PermitSignature::Sr25519(pair.sign(&message).0);
+            assert!(token
+                .permit(owner, accounts.bob, 10, 1000, signature.clone())
+                .is_ok());
+            assert_eq!(PSP22Impl::allowance(&token, owner, accounts.bob), 10);
+            assert_eq!(token.permit_nonce(owner), 1);
+
+            // the nonce changed, so the signature can't be replayed
+            assert_eq!(
+                token.permit(owner, accounts.bob, 10, 1000, signature),
+                Err(PSP22Error::Custom(String::from("InvalidSignature")))
+            );
+        }
+
+        #[ink::test]
+        fn permit_approves_with_ecdsa_signature() {
+            use sp_core::Pair;
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let pair = sp_core::ecdsa::Pair::from_string("//Alice", None).unwrap();
+            let owner = AccountId::from(sp_core::blake2_256(&pair.public().0));
+            let mut token = YToken::new(100);
+
+            let message = token.permit_message(owner, accounts.bob, 10, 0, 1000);
+            let signature = PermitSignature::Ecdsa(pair.sign(&message).0);
+            assert!(token
+                .permit(owner, accounts.bob, 10, 1000, signature)
+                .is_ok());
+            assert_eq!(PSP22Impl::allowance(&token, owner, accounts.bob), 10);
+        }
+
+        #[ink::test]
+        fn flash_fee_is_configurable_by_owner() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut token = YToken::new(100);
+            let address = ink::env::account_id::<ink::env::DefaultEnvironment>();
+            assert_eq!(FlashLenderImpl::flash_fee(&token, address, 1000), Ok(0));
+
+            assert!(token.set_flash_fee_bps(30).is_ok());
+            assert_eq!(FlashLenderImpl::flash_fee(&token, address, 1000), Ok(3));
+            assert_eq!(
+                token.set_flash_fee_bps(FLASH_FEE_DENOMINATOR + 1),
+                Err(PSP22Error::Custom(String::from("FlashFeeTooHigh")))
+            );
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert!(token.set_flash_fee_bps(0).is_err());
+            assert_eq!(token.flash_fee_bps(), 30);
+        }
+
+        #[ink::test]
+        fn vested_tokens_are_locked_until_released() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut token = YToken::new(1000);
+            assert!(token
+                .create_vesting(accounts.bob, 100, 0, 100, 1000)
+                .is_ok());
+            assert_eq!(PSP22Impl::balance_of(&token, accounts.bob), 100);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(
+                PSP22Impl::transfer(&mut token, accounts.charlie, 1, vec![]),
+                Err(PSP22Error::Custom(String::from("TokensLocked")))
+            );
+            assert_eq!(
+                token.release(),
+                Err(PSP22Error::Custom(String::from("NothingToRelease")))
+            );
+
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(250);
+            assert_eq!(token.vested_amount(accounts.bob), 25);
+            assert!(token.release().is_ok());
+            assert_eq!(token.locked_balance(accounts.bob), 75);
+            assert!(PSP22Impl::transfer(&mut token, accounts.charlie, 25, vec![]).is_ok());
+            assert!(PSP22Impl::transfer(&mut token, accounts.charlie, 1, vec![]).is_err());
+
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(1000);
+            assert!(token.release().is_ok());
+            assert_eq!(token.locked_balance(accounts.bob), 0);
+            assert!(PSP22Impl::transfer(&mut token, accounts.charlie, 75, vec![]).is_ok());
+        }
+
+        #[ink::test]
+        fn only_owner_creates_vesting() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut token = YToken::new(1000);
+            assert_eq!(
+                token.create_vesting(accounts.bob, 100, 0, 200, 100),
+                Err(PSP22Error::Custom(String::from("InvalidSchedule")))
+            );
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert!(token.create_vesting(accounts.bob, 100, 0, 0, 100).is_err());
+            assert_eq!(token.vesting_schedule(accounts.bob), None);
+        }
+
+        #[ink::test]
+        fn large_schedules_vest_monotonically() {
+            let schedule = VestingSchedule {
+                total: 1_856_910_061_563_319_565_735_765_138,
+                released: 0,
+                start: 0,
+                cliff: 0,
+                duration: u64::MAX,
+            };
+
+            // `total * 183_251_937_703` overflows
+            assert_eq!(
+                schedule.vested_at(183_251_937_702),
+                18_446_744_073_648_834_036
+            );
+            assert_eq!(
+                schedule.vested_at(183_251_937_703),
+                18_446_744_073_749_497_332
+            );
+            assert_eq!(schedule.vested_at(u64::MAX), schedule.total);
+        }
+
+        #[ink::test]
+        fn transfers_within_a_block_share_a_checkpoint() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut token = YToken::new(100);
+            assert!(PSP22Impl::transfer(&mut token, accounts.bob, 10, vec![]).is_ok());
+            assert!(PSP22Impl::transfer(&mut token, accounts.bob, 20, vec![]).is_ok());
+
+            assert_eq!(token.balance_of_at(accounts.alice, 0), 70);
+            assert_eq!(token.balance_of_at(accounts.bob, 0), 30);
+            assert_eq!(
+                token
+                    .checkpoint_counts
+                    .get(CheckpointKey::Balance(accounts.bob)),
+                Some(1)
+            );
+            assert_eq!(token.total_supply_at(0), 100);
+        }
+
+        #[ink::test]
+        fn checkpoints_keep_balances_of_earlier_blocks() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut token = YToken::new(100);
+            ink::env::test::advance_block::<ink::env::DefaultEnvironment>();
+            assert!(PSP22Impl::transfer(&mut token, accounts.bob, 10, vec![]).is_ok());
+            ink::env::test::advance_block::<ink::env::DefaultEnvironment>();
+            ink::env::test::advance_block::<ink::env::DefaultEnvironment>();
+            assert!(PSP22Impl::transfer(&mut token, accounts.bob, 20, vec![]).is_ok());
+            assert!(PSP22MintableImpl::mint(&mut token, accounts.charlie, 50).is_ok());
+
+            assert_eq!(token.balance_of_at(accounts.bob, 0), 0);
+            assert_eq!(token.balance_of_at(accounts.bob, 1), 10);
+            assert_eq!(token.balance_of_at(accounts.bob, 2), 10);
+            assert_eq!(token.balance_of_at(accounts.bob, 3), 30);
+            assert_eq!(token.balance_of_at(accounts.alice, 0), 100);
+            assert_eq!(token.balance_of_at(accounts.alice, 3), 70);
+            assert_eq!(token.total_supply_at(2), 100);
+            assert_eq!(token.total_supply_at(3), 150);
+        }
+
+        #[ink::test]
+        fn delegation_moves_votes_with_transfers() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut token = YToken::new(100);
+            assert_eq!(token.get_votes(accounts.alice), 0);
+
+            assert!(token.delegate(accounts.alice).is_ok());
+            assert_eq!(token.get_votes(accounts.alice), 100);
+            assert_eq!(token.delegates(accounts.alice), Some(accounts.alice));
+
+            ink::env::test::advance_block::<ink::env::DefaultEnvironment>();
+            assert!(token.delegate(accounts.charlie).is_ok());
+            assert!(PSP22Impl::transfer(&mut token, accounts.bob, 30, vec![]).is_ok());
+            assert_eq!(token.get_votes(accounts.alice), 0);
+            // bob has no delegate, so the transferred tokens don't count as votes
+            assert_eq!(token.get_votes(accounts.charlie), 70);
+
+            ink::env::test::advance_block::<ink::env::DefaultEnvironment>();
+            assert_eq!(token.get_past_votes(accounts.alice, 0), Ok(100));
+            assert_eq!(token.get_past_votes(accounts.charlie, 1), Ok(70));
+            assert_eq!(
+                token.get_past_votes(accounts.charlie, 2),
+                Err(PSP22Error::Custom(String::from("BlockNotYetMined")))
+            );
+        }
+
+        #[ink::test]
+        fn delegate_by_signature_works_once() {
+            use sp_core::Pair;
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let pair = sp_core::sr25519::Pair::from_string("//Alice", None).unwrap();
+            let delegator = AccountId::from(pair.public().0);
+            let mut token = YToken::new(100);
+            assert!(PSP22Impl::transfer(&mut token, delegator, 40, vec![]).is_ok());
+
+            let message = token.delegation_message(delegator, accounts.bob, 0, 1000);
+            let signature = PermitSignature::Sr25519(pair.sign(&message).0);
+            assert!(token
+                .delegate_by_signature(delegator, accounts.bob, 1000, signature.clone())
+                .is_ok());
+            assert_eq!(token.delegates(delegator), Some(accounts.bob));
+            assert_eq!(token.get_votes(accounts.bob), 40);
+            assert_eq!(
+                token.delegate_by_signature(delegator, accounts.bob, 1000, signature),
+                Err(PSP22Error::Custom(String::from("InvalidSignature")))
+            );
+        }
+
+        #[ink::test]
+        fn migrate_only_runs_for_older_versions() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut token = YToken::new(100);
+            assert_eq!(token.storage_version(), STORAGE_VERSION);
+            assert_eq!(
+                token.migrate(),
+                Err(PSP22Error::Custom(String::from("AlreadyMigrated")))
+            );
+
+            token.storage_version = 0;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert!(token.migrate().is_err());
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert!(token.migrate().is_ok());
+            assert_eq!(token.storage_version(), STORAGE_VERSION);
+        }
+
+        #[ink::test]
+        fn with_metadata_mints_to_the_owner() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let token = YToken::with_metadata(
+                Some(String::from("Brand")),
+                Some(String::from("BRD")),
+                12,
+                100,
+                accounts.bob,
+            );
+
+            assert_eq!(
+                PSP22MetadataImpl::token_name(&token),
+                Some(String::from("Brand"))
+            );
+            assert_eq!(
+                PSP22MetadataImpl::token_symbol(&token),
+                Some(String::from("BRD"))
+            );
+            assert_eq!(PSP22MetadataImpl::token_decimals(&token), 12);
+            assert_eq!(PSP22Impl::balance_of(&token, accounts.bob), 100);
+            assert_eq!(PSP22Impl::balance_of(&token, accounts.alice), 0);
+            assert_eq!(OwnableImpl::owner(&token), Some(accounts.bob));
+        }
+
+        #[ink::test]
+        fn batch_transfer_emits_one_event_per_recipient() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut token = YToken::new(100);
+            assert!(token
+                .batch_transfer(vec![(accounts.bob, 10), (accounts.charlie, 20)], vec![])
+                .is_ok());
+
+            assert_eq!(PSP22Impl::balance_of(&token, accounts.alice), 70);
+            assert_eq!(PSP22Impl::balance_of(&token, accounts.bob), 10);
+            assert_eq!(PSP22Impl::balance_of(&token, accounts.charlie), 20);
+            // one event for the mint in the constructor, one per recipient
+            assert_eq!(ink::env::test::recorded_events().count(), 3);
+        }
+
+        #[ink::test]
+        fn batch_transfer_is_all_or_nothing() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut token = YToken::new(100);
+            // the first transfer could be paid, the second one can't
+            assert_eq!(
+                token.batch_transfer(vec![(accounts.bob, 60), (accounts.charlie, 60)], vec![]),
+                Err(PSP22Error::InsufficientBalance)
+            );
+
+            assert_eq!(PSP22Impl::balance_of(&token, accounts.alice), 100);
+            assert_eq!(PSP22Impl::balance_of(&token, accounts.bob), 0);
+            assert_eq!(ink::env::test::recorded_events().count(), 1);
+        }
+
+        #[ink::test]
+        fn batch_transfer_checks_locked_tokens_and_length() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut token = YToken::new(100);
+            assert!(token.create_vesting(accounts.bob, 50, 0, 0, 1000).is_ok());
+            assert!(PSP22Impl::transfer(&mut token, accounts.bob, 10, vec![]).is_ok());
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(
+                token.batch_transfer(vec![(accounts.charlie, 5), (accounts.django, 6)], vec![]),
+                Err(PSP22Error::InsufficientBalance)
+            );
+            assert!(token
+                .batch_transfer(vec![(accounts.charlie, 5), (accounts.django, 5)], vec![])
+                .is_ok());
+
+            let transfers = vec![(accounts.charlie, 0); MAX_BATCH_LEN as usize + 1];
+            assert_eq!(
+                token.batch_transfer(transfers, vec![]),
+                Err(PSP22Error::Custom(String::from("BatchTooLong")))
+            );
+        }
+
+        #[ink::test]
+        fn frozen_accounts_cannot_send_or_receive() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut token = YToken::new(100);
+            assert!(PSP22Impl::transfer(&mut token, accounts.bob, 10, vec![]).is_ok());
+            assert!(token.freeze(accounts.bob).is_ok());
+            assert_eq!(
+                token.freeze(accounts.bob),
+                Err(PSP22Error::Custom(String::from("AlreadyFrozen")))
+            );
+            assert!(token.is_frozen(accounts.bob));
+            assert_eq!(token.frozen_accounts(0, 10), vec![accounts.bob]);
+
+            let frozen = Err(PSP22Error::Custom(String::from("AccountFrozen")));
+            assert_eq!(
+                PSP22Impl::transfer(&mut token, accounts.bob, 1, vec![]),
+                frozen
+            );
+            assert_eq!(PSP22MintableImpl::mint(&mut token, accounts.bob, 1), frozen);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(
+                PSP22Impl::transfer(&mut token, accounts.charlie, 1, vec![]),
+                frozen
+            );
+            assert!(token.unfreeze(accounts.bob).is_err());
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert!(token.unfreeze(accounts.bob).is_ok());
+            assert_eq!(token.frozen_accounts(0, 10), vec![]);
+            assert!(PSP22Impl::transfer(&mut token, accounts.bob, 1, vec![]).is_ok());
+            // transfer, frozen, unfrozen and transfer events after the mint
+            assert_eq!(ink::env::test::recorded_events().count(), 5);
+        }
+
+        #[ink::test]
+        fn unfreezing_moves_the_last_frozen_account() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut token = YToken::new(100);
+            for account in [accounts.bob, accounts.charlie, accounts.django] {
+                assert!(token.freeze(account).is_ok());
+            }
+            assert_eq!(token.frozen_accounts(1, 1), vec![accounts.charlie]);
+
+            assert!(token.unfreeze(accounts.bob).is_ok());
+            assert_eq!(token.frozen_count(), 2);
+            assert_eq!(
+                token.frozen_accounts(0, 10),
+                vec![accounts.django, accounts.charlie]
+            );
+            assert!(token.unfreeze(accounts.charlie).is_ok());
+            assert!(token.unfreeze(accounts.django).is_ok());
+            assert_eq!(token.frozen_count(), 0);
+            assert_eq!(token.frozen_accounts(0, 10), vec![]);
+            assert!(token.freeze(accounts.bob).is_ok());
+            assert_eq!(token.frozen_accounts(0, 10), vec![accounts.bob]);
+        }
 
-            psp22::Internal::_mint_to(&mut instance, Self::env().caller(), total_supply)
-                .expect("Should mint");
+        #[ink::test]
+        fn transfer_fee_goes_to_treasury_and_rounds_down() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut token = YToken::new(10_000);
+            assert!(token.set_transfer_fee_bps(100).is_ok());
+            // no treasury, no fee
+            assert_eq!(token.transfer_fee(accounts.alice, accounts.bob, 1000), 0);
+            assert!(token.set_treasury(Some(accounts.charlie)).is_ok());
 
-            instance
+            // 1% of 99 rounds down to nothing
+            assert!(PSP22::transfer(&mut token, accounts.bob, 99, vec![]).is_ok());
+            assert_eq!(PSP22Impl::balance_of(&token, accounts.charlie), 0);
+            assert!(PSP22::transfer(&mut token, accounts.bob, 100, vec![]).is_ok());
+            assert_eq!(PSP22Impl::balance_of(&token, accounts.charlie), 1);
+            assert!(PSP22::transfer(&mut token, accounts.bob, 299, vec![]).is_ok());
+            assert_eq!(PSP22Impl::balance_of(&token, accounts.charlie), 3);
+
+            // the recipient gets the full amount, the sender pays the fee on top
+            assert_eq!(PSP22Impl::balance_of(&token, accounts.bob), 498);
+            assert_eq!(
+                PSP22Impl::balance_of(&token, accounts.alice),
+                10_000 - 498 - 3
+            );
+            assert_eq!(PSP22Impl::total_supply(&token), 10_000);
         }
-    }
 
-    #[cfg(test)]
-    mod tests {
-        use super::*;
         #[ink::test]
-        fn total_supply_works() {
-            let token = YToken::new(100);
-            assert_eq!(PSP22Impl::total_supply(&token), 100);
+        fn exempt_accounts_pay_no_transfer_fee() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut token = YToken::new(10_000);
+            assert!(token.set_transfer_fee_bps(MAX_TRANSFER_FEE_BPS).is_ok());
+            assert_eq!(
+                token.set_transfer_fee_bps(MAX_TRANSFER_FEE_BPS + 1),
+                Err(PSP22Error::Custom(String::from("TransferFeeTooHigh")))
+            );
+            assert!(token.set_treasury(Some(accounts.charlie)).is_ok());
+            assert!(token.set_fee_exempt(accounts.bob, true).is_ok());
+
+            assert!(PSP22::transfer(&mut token, accounts.bob, 1000, vec![]).is_ok());
+            assert!(PSP22::transfer(&mut token, accounts.charlie, 1000, vec![]).is_ok());
+            assert_eq!(PSP22Impl::balance_of(&token, accounts.alice), 8000);
+            assert!(token.set_fee_exempt(accounts.bob, false).is_ok());
+            assert!(PSP22::transfer(&mut token, accounts.bob, 1000, vec![]).is_ok());
+            assert_eq!(PSP22Impl::balance_of(&token, accounts.charlie), 1100);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert!(token.set_fee_exempt(accounts.bob, true).is_err());
+            // the fee comes on top of the amount, bob can't send all of his tokens
+            assert_eq!(
+                PSP22::transfer(&mut token, accounts.django, 2000, vec![]),
+                Err(PSP22Error::InsufficientBalance)
+            );
+        }
+
+        #[ink::test]
+        fn allowance_covers_the_transfer_fee() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut token = YToken::new(10_000);
+            assert!(token.set_transfer_fee_bps(100).is_ok());
+            assert!(token.set_treasury(Some(accounts.charlie)).is_ok());
+            assert!(PSP22Impl::approve(&mut token, accounts.bob, 1000).is_ok());
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            // 1000 and the fee of 10 exceed the allowance
+            assert_eq!(
+                PSP22::transfer_from(&mut token, accounts.alice, accounts.django, 1000, vec![]),
+                Err(PSP22Error::InsufficientAllowance)
+            );
+            assert!(
+                PSP22::transfer_from(&mut token, accounts.alice, accounts.django, 990, vec![])
+                    .is_ok()
+            );
+            assert_eq!(
+                PSP22Impl::allowance(&token, accounts.alice, accounts.bob),
+                1
+            );
+            assert_eq!(PSP22Impl::balance_of(&token, accounts.django), 990);
+            assert_eq!(PSP22Impl::balance_of(&token, accounts.charlie), 9);
+            assert_eq!(PSP22Impl::balance_of(&token, accounts.alice), 10_000 - 999);
+        }
+
+        #[ink::test]
+        fn vesting_and_rescue_pay_no_transfer_fee() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut token = YToken::new(10_000);
+            let contract = ink::env::account_id::<ink::env::DefaultEnvironment>();
+            assert!(PSP22::transfer(&mut token, contract, 1000, vec![]).is_ok());
+            assert!(token.set_transfer_fee_bps(100).is_ok());
+            assert!(token.set_treasury(Some(accounts.charlie)).is_ok());
+
+            assert!(token.create_vesting(accounts.bob, 1000, 0, 0, 100).is_ok());
+            assert!(token.rescue(Some(contract), accounts.django, 1000).is_ok());
+            assert_eq!(PSP22Impl::balance_of(&token, accounts.charlie), 0);
+            assert_eq!(PSP22Impl::balance_of(&token, accounts.alice), 8000);
+        }
+
+        #[ink::test]
+        fn faucet_dispenses_once_per_cooldown() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut token = YToken::new(100);
+            assert_eq!(
+                token.claim_faucet(),
+                Err(PSP22Error::Custom(String::from("FaucetDisabled")))
+            );
+            assert!(token.set_faucet(10, 1000).is_ok());
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert!(token.set_faucet(1000, 0).is_err());
+            assert!(token.claim_faucet().is_ok());
+            assert_eq!(
+                token.claim_faucet(),
+                Err(PSP22Error::Custom(String::from("FaucetCooldown")))
+            );
+            let next_claim = token.next_faucet_claim(accounts.bob);
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(next_claim);
+            assert!(token.claim_faucet().is_ok());
+            assert_eq!(PSP22Impl::balance_of(&token, accounts.bob), 20);
+            assert_eq!(PSP22Impl::total_supply(&token), 120);
+        }
+
+        #[ink::test]
+        fn only_the_owner_mints_others_claim_the_faucet() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut token = YToken::new(100);
+            assert!(token.set_faucet(10, 1000).is_ok());
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert!(PSP22Mintable::mint(&mut token, accounts.bob, 1000).is_err());
+            assert!(token.claim_faucet().is_ok());
+            assert_eq!(PSP22Impl::balance_of(&token, accounts.bob), 10);
+            assert_eq!(PSP22Impl::total_supply(&token), 110);
+        }
+
+        #[ink::test]
+        fn owner_rescues_native_balance() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut token = YToken::new(100);
+            let contract = ink::env::account_id::<ink::env::DefaultEnvironment>();
+            ink::env::test::set_account_balance::<ink::env::DefaultEnvironment>(contract, 1000);
+            let bob_balance =
+                ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.bob)
+                    .unwrap();
+
+            assert!(token.rescue(None, accounts.bob, 400).is_ok());
+            assert_eq!(
+                ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.bob),
+                Ok(bob_balance + 400)
+            );
+            assert_eq!(
+                token.rescue(None, accounts.bob, 1000),
+                Err(PSP22Error::Custom(String::from("NativeTransferFailed")))
+            );
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert!(token.rescue(None, accounts.bob, 100).is_err());
+        }
+
+        #[ink::test]
+        fn owner_rescues_own_tokens() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut token = YToken::new(100);
+            let contract = ink::env::account_id::<ink::env::DefaultEnvironment>();
+            assert!(PSP22Impl::transfer(&mut token, contract, 10, vec![]).is_ok());
+
+            assert!(token.rescue(Some(contract), accounts.bob, 10).is_ok());
+            assert_eq!(PSP22Impl::balance_of(&token, contract), 0);
+            assert_eq!(PSP22Impl::balance_of(&token, accounts.bob), 10);
+            // mint, two transfers and the rescue
+            assert_eq!(ink::env::test::recorded_events().count(), 4);
+        }
+
+        #[ink::test]
+        fn emissions_accrue_per_block_up_to_the_cap() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut token = YToken::new(100);
+            assert_eq!(
+                token.set_supply_cap(Some(99)),
+                Err(PSP22Error::Custom(String::from("CapBelowSupply")))
+            );
+            assert!(token.set_supply_cap(Some(150)).is_ok());
+            assert!(token.set_emission(Some(accounts.bob), 10).is_ok());
+            assert_eq!(
+                token.mint_emissions(),
+                Err(PSP22Error::Custom(String::from("NothingToMint")))
+            );
+
+            ink::env::test::advance_block::<ink::env::DefaultEnvironment>();
+            ink::env::test::advance_block::<ink::env::DefaultEnvironment>();
+            assert_eq!(token.pending_emissions(), 20);
+            assert!(token.mint_emissions().is_ok());
+            assert_eq!(PSP22Impl::balance_of(&token, accounts.bob), 20);
+
+            // 40 tokens accrue, but only 30 fit under the cap
+            for _ in 0..4 {
+                ink::env::test::advance_block::<ink::env::DefaultEnvironment>();
+            }
+            assert_eq!(token.pending_emissions(), 30);
+            assert!(token.mint_emissions().is_ok());
+            assert_eq!(PSP22Impl::total_supply(&token), 150);
+            assert_eq!(token.pending_emissions(), 0);
+            assert_eq!(
+                PSP22MintableImpl::mint(&mut token, accounts.alice, 1),
+                Err(PSP22Error::Custom(String::from("CapExceeded")))
+            );
+        }
+
+        #[ink::test]
+        fn permit_fails_after_deadline() {
+            use sp_core::Pair;
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let pair = sp_core::sr25519::Pair::from_string("//Alice", None).unwrap();
+            let owner = AccountId::from(pair.public().0);
+            let mut token = YToken::new(100);
+
+            let message = token.permit_message(owner, accounts.bob, 10, 0, 1000);
+            let signature = PermitSignature::Sr25519(pair.sign(&message).0);
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(1001);
+            assert_eq!(
+                token.permit(owner, accounts.bob, 10, 1000, signature),
+                Err(PSP22Error::Custom(String::from("PermitExpired")))
+            );
+            assert_eq!(token.permit_nonce(owner), 0);
         }
     }
 
     #[cfg(all(test, feature = "e2e-tests"))]
     pub mod e2e_tests {
         use super::*;
+        use flash_borrower::flash_borrower::FlashBorrowerContractRef;
         use ink::primitives::AccountId;
         use ink_e2e::subxt::tx::Signer;
         use ink_e2e::subxt::utils::AccountId32;
         use ink_e2e::{build_message, Keypair, PolkadotConfig};
+        use openbrush::contracts::psp22::extensions::mintable::psp22mintable_external::PSP22Mintable;
         use openbrush::contracts::psp22::psp22_external::PSP22;
+        use openbrush::contracts::traits::flashloan::flashlender_external::FlashLender;
+        use openbrush::contracts::traits::flashloan::FlashLenderError;
+        use psp22_receiver::psp22_receiver::PSP22ReceiverContractRef;
+        use y_psp22_token_v1::y_psp22_token_v1::YTokenRef as YTokenV1Ref;
 
         type ContractRef = YTokenRef;
 
@@ -66,6 +1819,19 @@ pub mod y_psp22_token {
             result
         }
 
+        async fn votes_of(
+            client: &mut ink_e2e::Client<PolkadotConfig, ink_env::DefaultEnvironment>,
+            address: AccountId,
+            account: AccountId,
+        ) -> Balance {
+            let _msg = build_message::<ContractRef>(address.clone())
+                .call(|contract| contract.get_votes(account));
+            client
+                .call_dry_run(&ink_e2e::alice(), &_msg, 0, None)
+                .await
+                .return_value()
+        }
+
         #[ink_e2e::test]
         async fn assigns_initial_balance(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
             let constructor = ContractRef::new(100);
@@ -144,5 +1910,516 @@ pub mod y_psp22_token {
 
             Ok(())
         }
+
+        /// deploys the token with a flash fee of 1% and a borrower which holds enough tokens for the fee
+        async fn deploy_flash_loan(
+            client: &mut ink_e2e::Client<PolkadotConfig, ink_env::DefaultEnvironment>,
+            repay: bool,
+        ) -> E2EResult<(AccountId, AccountId)> {
+            let address = client
+                .instantiate(
+                    "y_psp22_token",
+                    &ink_e2e::alice(),
+                    ContractRef::new(1000),
+                    0,
+                    None,
+                )
+                .await
+                .expect("instantiate failed")
+                .account_id;
+            let borrower = client
+                .instantiate(
+                    "flash_borrower",
+                    &ink_e2e::alice(),
+                    FlashBorrowerContractRef::new(repay),
+                    0,
+                    None,
+                )
+                .await
+                .expect("instantiate borrower failed")
+                .account_id;
+
+            let set_fee = build_message::<ContractRef>(address.clone())
+                .call(|contract| contract.set_flash_fee_bps(100));
+            client
+                .call(&ink_e2e::alice(), set_fee, 0, None)
+                .await
+                .expect("set_flash_fee_bps failed");
+            let fund_fee = build_message::<ContractRef>(address.clone())
+                .call(|contract| contract.transfer(borrower, 10, vec![]));
+            client
+                .call(&ink_e2e::alice(), fund_fee, 0, None)
+                .await
+                .expect("transfer failed");
+
+            Ok((address, borrower))
+        }
+
+        #[ink_e2e::test(additional_contracts = "e2e/flash_borrower/Cargo.toml")]
+        async fn flash_loan_is_repaid_with_fee(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            let (address, borrower) = deploy_flash_loan(&mut client, true).await?;
+
+            let result = {
+                let _msg = build_message::<ContractRef>(address.clone())
+                    .call(|contract| contract.flashloan(borrower, address, 500, vec![]));
+                client
+                    .call(&ink_e2e::alice(), _msg, 0, None)
+                    .await
+                    .expect("flashloan failed")
+            };
+
+            assert!(matches!(result.return_value(), Ok(())));
+            // the borrowed amount and the fee of 5 tokens are burned
+            assert_eq!(balance_of(&mut client, address, borrower).await, 5);
+            let total_supply = {
+                let _msg = build_message::<ContractRef>(address.clone())
+                    .call(|contract| contract.total_supply());
+                client
+                    .call_dry_run(&ink_e2e::alice(), &_msg, 0, None)
+                    .await
+                    .return_value()
+            };
+            assert_eq!(total_supply, 995);
+
+            Ok(())
+        }
+
+        #[ink_e2e::test(additional_contracts = "e2e/flash_borrower/Cargo.toml")]
+        async fn flash_loan_is_reverted_without_repayment(
+            mut client: ink_e2e::Client<C, E>,
+        ) -> E2EResult<()> {
+            let (address, borrower) = deploy_flash_loan(&mut client, false).await?;
+
+            let result = {
+                let _msg = build_message::<ContractRef>(address.clone())
+                    .call(|contract| contract.flashloan(borrower, address, 500, vec![]));
+                client.call_dry_run(&ink_e2e::alice(), &_msg, 0, None).await
+            };
+
+            assert!(matches!(
+                result.return_value(),
+                Err(FlashLenderError::AllowanceDoesNotAllowRefund)
+            ));
+
+            let _msg = build_message::<ContractRef>(address.clone())
+                .call(|contract| contract.flashloan(borrower, address, 500, vec![]));
+            assert!(client.call(&ink_e2e::alice(), _msg, 0, None).await.is_err());
+            // nothing was minted, the borrower only holds the tokens for the fee
+            assert_eq!(balance_of(&mut client, address, borrower).await, 10);
+
+            Ok(())
+        }
+
+        /// deploys the token and a receiver contract which accepts or rejects transfers
+        async fn deploy_receiver(
+            client: &mut ink_e2e::Client<PolkadotConfig, ink_env::DefaultEnvironment>,
+            accept: bool,
+        ) -> E2EResult<(AccountId, AccountId)> {
+            let address = client
+                .instantiate(
+                    "y_psp22_token",
+                    &ink_e2e::alice(),
+                    ContractRef::new(100),
+                    0,
+                    None,
+                )
+                .await
+                .expect("instantiate failed")
+                .account_id;
+            let receiver = client
+                .instantiate(
+                    "psp22_receiver",
+                    &ink_e2e::alice(),
+                    PSP22ReceiverContractRef::new(accept),
+                    0,
+                    None,
+                )
+                .await
+                .expect("instantiate receiver failed")
+                .account_id;
+            Ok((address, receiver))
+        }
+
+        #[ink_e2e::test(additional_contracts = "e2e/psp22_receiver/Cargo.toml")]
+        async fn accepting_receiver_gets_tokens(
+            mut client: ink_e2e::Client<C, E>,
+        ) -> E2EResult<()> {
+            let (address, receiver) = deploy_receiver(&mut client, true).await?;
+
+            let transfer = build_message::<ContractRef>(address.clone())
+                .call(|contract| contract.transfer(receiver, 10, vec![]));
+            client
+                .call(&ink_e2e::alice(), transfer, 0, None)
+                .await
+                .expect("transfer failed");
+
+            assert_eq!(balance_of(&mut client, address, receiver).await, 10);
+
+            Ok(())
+        }
+
+        #[ink_e2e::test(additional_contracts = "e2e/psp22_receiver/Cargo.toml")]
+        async fn rejecting_receiver_reverts_transfer(
+            mut client: ink_e2e::Client<C, E>,
+        ) -> E2EResult<()> {
+            let (address, receiver) = deploy_receiver(&mut client, false).await?;
+
+            let result = {
+                let _msg = build_message::<ContractRef>(address.clone())
+                    .call(|contract| contract.transfer(receiver, 10, vec![]));
+                client.call_dry_run(&ink_e2e::alice(), &_msg, 0, None).await
+            };
+            assert_eq!(
+                result.return_value(),
+                Err(PSP22Error::SafeTransferCheckFailed(String::from(
+                    "ReceiverRejects"
+                )))
+            );
+
+            let transfer = build_message::<ContractRef>(address.clone())
+                .call(|contract| contract.transfer(receiver, 10, vec![]));
+            assert!(client
+                .call(&ink_e2e::alice(), transfer, 0, None)
+                .await
+                .is_err());
+            assert_eq!(balance_of(&mut client, address, receiver).await, 0);
+
+            Ok(())
+        }
+
+        #[ink_e2e::test]
+        async fn owner_rescues_tokens_sent_to_the_contract(
+            mut client: ink_e2e::Client<C, E>,
+        ) -> E2EResult<()> {
+            let address = client
+                .instantiate(
+                    "y_psp22_token",
+                    &ink_e2e::alice(),
+                    ContractRef::new(100),
+                    0,
+                    None,
+                )
+                .await
+                .expect("instantiate failed")
+                .account_id;
+            let other_token = client
+                .instantiate(
+                    "y_psp22_token",
+                    &ink_e2e::alice(),
+                    ContractRef::new(100),
+                    0,
+                    None,
+                )
+                .await
+                .expect("instantiate other token failed")
+                .account_id;
+            let bob = address_of(&ink_e2e::bob());
+
+            let send_own = build_message::<ContractRef>(address.clone())
+                .call(|contract| contract.transfer(address, 5, vec![]));
+            client
+                .call(&ink_e2e::alice(), send_own, 0, None)
+                .await
+                .expect("transfer failed");
+            let send_other = build_message::<ContractRef>(other_token.clone())
+                .call(|contract| contract.transfer(address, 7, vec![]));
+            client
+                .call(&ink_e2e::alice(), send_other, 0, None)
+                .await
+                .expect("transfer of other token failed");
+
+            let rescue_by_bob = build_message::<ContractRef>(address.clone())
+                .call(|contract| contract.rescue(Some(address), bob, 5));
+            assert!(client
+                .call(&ink_e2e::bob(), rescue_by_bob, 0, None)
+                .await
+                .is_err());
+
+            let rescue_own = build_message::<ContractRef>(address.clone())
+                .call(|contract| contract.rescue(Some(address), bob, 5));
+            client
+                .call(&ink_e2e::alice(), rescue_own, 0, None)
+                .await
+                .expect("rescue failed");
+            let rescue_other = build_message::<ContractRef>(address.clone())
+                .call(|contract| contract.rescue(Some(other_token), bob, 7));
+            client
+                .call(&ink_e2e::alice(), rescue_other, 0, None)
+                .await
+                .expect("rescue of other token failed");
+
+            assert_eq!(balance_of(&mut client, address, address).await, 0);
+            assert_eq!(balance_of(&mut client, address, bob).await, 5);
+            assert_eq!(balance_of(&mut client, other_token, address).await, 0);
+            assert_eq!(balance_of(&mut client, other_token, bob).await, 7);
+
+            Ok(())
+        }
+
+        #[ink_e2e::test]
+        async fn delegated_votes_follow_transfers(
+            mut client: ink_e2e::Client<C, E>,
+        ) -> E2EResult<()> {
+            let constructor = ContractRef::new(100);
+            let address = client
+                .instantiate("y_psp22_token", &ink_e2e::alice(), constructor, 0, None)
+                .await
+                .expect("instantiate failed")
+                .account_id;
+            let (alice, bob, charlie) = (
+                address_of(&ink_e2e::alice()),
+                address_of(&ink_e2e::bob()),
+                address_of(&ink_e2e::charlie()),
+            );
+
+            let transfer = build_message::<ContractRef>(address.clone())
+                .call(|contract| contract.transfer(bob, 30, vec![]));
+            client
+                .call(&ink_e2e::alice(), transfer, 0, None)
+                .await
+                .expect("transfer failed");
+            let delegate = build_message::<ContractRef>(address.clone())
+                .call(|contract| contract.delegate(charlie));
+            client
+                .call(&ink_e2e::bob(), delegate, 0, None)
+                .await
+                .expect("delegate failed");
+            let delegate = build_message::<ContractRef>(address.clone())
+                .call(|contract| contract.delegate(alice));
+            client
+                .call(&ink_e2e::alice(), delegate, 0, None)
+                .await
+                .expect("delegate failed");
+
+            assert_eq!(votes_of(&mut client, address, charlie).await, 30);
+            assert_eq!(votes_of(&mut client, address, alice).await, 70);
+
+            let transfer = build_message::<ContractRef>(address.clone())
+                .call(|contract| contract.transfer(alice, 10, vec![]));
+            client
+                .call(&ink_e2e::bob(), transfer, 0, None)
+                .await
+                .expect("transfer failed");
+
+            assert_eq!(votes_of(&mut client, address, charlie).await, 20);
+            assert_eq!(votes_of(&mut client, address, alice).await, 80);
+            let delegates = {
+                let _msg = build_message::<ContractRef>(address.clone())
+                    .call(|contract| contract.delegates(bob));
+                client
+                    .call_dry_run(&ink_e2e::alice(), &_msg, 0, None)
+                    .await
+                    .return_value()
+            };
+            assert_eq!(delegates, Some(charlie));
+
+            Ok(())
+        }
+
+        #[ink_e2e::test]
+        async fn relayer_submits_signed_delegation(
+            mut client: ink_e2e::Client<C, E>,
+        ) -> E2EResult<()> {
+            let constructor = ContractRef::new(100);
+            let address = client
+                .instantiate("y_psp22_token", &ink_e2e::alice(), constructor, 0, None)
+                .await
+                .expect("instantiate failed")
+                .account_id;
+            let (alice, bob) = (address_of(&ink_e2e::alice()), address_of(&ink_e2e::bob()));
+
+            // alice signs the delegation to bob, charlie submits it
+            let expiry = u64::MAX;
+            let payload = (DELEGATION_DOMAIN, address, alice, bob, 0u64, expiry).encode();
+            let message = [b"<Bytes>".as_slice(), &payload, b"</Bytes>".as_slice()].concat();
+            let signature = PermitSignature::Sr25519(ink_e2e::alice().sign(&message).0);
+
+            let delegate = build_message::<ContractRef>(address.clone()).call(|contract| {
+                contract.delegate_by_signature(alice, bob, expiry, signature.clone())
+            });
+            client
+                .call(&ink_e2e::charlie(), delegate, 0, None)
+                .await
+                .expect("delegate_by_signature failed");
+
+            assert_eq!(votes_of(&mut client, address, bob).await, 100);
+
+            Ok(())
+        }
+
+        #[ink_e2e::test(additional_contracts = "e2e/y_psp22_token_v2/Cargo.toml")]
+        async fn upgrade_keeps_balances_and_allowances(
+            mut client: ink_e2e::Client<C, E>,
+        ) -> E2EResult<()> {
+            let constructor = ContractRef::new(100);
+            let address = client
+                .instantiate("y_psp22_token", &ink_e2e::alice(), constructor, 0, None)
+                .await
+                .expect("instantiate failed")
+                .account_id;
+            let (alice, bob, charlie) = (
+                address_of(&ink_e2e::alice()),
+                address_of(&ink_e2e::bob()),
+                address_of(&ink_e2e::charlie()),
+            );
+            let mint = build_message::<ContractRef>(address.clone())
+                .call(|contract| contract.mint(bob, 30));
+            client
+                .call(&ink_e2e::alice(), mint, 0, None)
+                .await
+                .expect("mint failed");
+            let approve = build_message::<ContractRef>(address.clone())
+                .call(|contract| contract.approve(charlie, 20));
+            client
+                .call(&ink_e2e::alice(), approve, 0, None)
+                .await
+                .expect("approve failed");
+
+            let code_hash = client
+                .upload("y_psp22_token_v2", &ink_e2e::alice(), None)
+                .await
+                .expect("upload failed")
+                .code_hash;
+            let upgrade = build_message::<ContractRef>(address.clone())
+                .call(|contract| contract.upgrade(code_hash));
+            // only the owner can upgrade
+            let result = client
+                .call_dry_run(&ink_e2e::bob(), &upgrade, 0, None)
+                .await;
+            assert!(result.return_value().is_err());
+            client
+                .call(&ink_e2e::alice(), upgrade, 0, None)
+                .await
+                .expect("upgrade failed");
+            let migrate =
+                build_message::<ContractRef>(address.clone()).call(|contract| contract.migrate());
+            client
+                .call(&ink_e2e::alice(), migrate, 0, None)
+                .await
+                .expect("migrate failed");
+
+            let storage_version = {
+                let _msg = build_message::<ContractRef>(address.clone())
+                    .call(|contract| contract.storage_version());
+                client
+                    .call_dry_run(&ink_e2e::alice(), &_msg, 0, None)
+                    .await
+                    .return_value()
+            };
+            assert_eq!(storage_version, 2);
+            assert_eq!(balance_of(&mut client, address, alice).await, 100);
+            assert_eq!(balance_of(&mut client, address, bob).await, 30);
+            let allowance = {
+                let _msg = build_message::<ContractRef>(address.clone())
+                    .call(|contract| contract.allowance(alice, charlie));
+                client
+                    .call_dry_run(&ink_e2e::alice(), &_msg, 0, None)
+                    .await
+                    .return_value()
+            };
+            assert_eq!(allowance, 20);
+
+            Ok(())
+        }
+
+        #[ink_e2e::test(additional_contracts = "e2e/y_psp22_token_v1/Cargo.toml")]
+        async fn upgrade_from_version_1_reads_later_settings_as_unset(
+            mut client: ink_e2e::Client<C, E>,
+        ) -> E2EResult<()> {
+            let address = client
+                .instantiate(
+                    "y_psp22_token_v1",
+                    &ink_e2e::alice(),
+                    YTokenV1Ref::new(100),
+                    0,
+                    None,
+                )
+                .await
+                .expect("instantiate version 1 failed")
+                .account_id;
+            let (bob, charlie) = (address_of(&ink_e2e::bob()), address_of(&ink_e2e::charlie()));
+            let set_fee = build_message::<YTokenV1Ref>(address.clone())
+                .call(|contract| contract.set_flash_fee_bps(25));
+            client
+                .call(&ink_e2e::alice(), set_fee, 0, None)
+                .await
+                .expect("set_flash_fee_bps failed");
+            let transfer = build_message::<ContractRef>(address.clone())
+                .call(|contract| contract.transfer(bob, 30, vec![]));
+            client
+                .call(&ink_e2e::alice(), transfer, 0, None)
+                .await
+                .expect("transfer failed");
+
+            let code_hash = client
+                .upload("y_psp22_token", &ink_e2e::alice(), None)
+                .await
+                .expect("upload failed")
+                .code_hash;
+            let upgrade = build_message::<YTokenV1Ref>(address.clone())
+                .call(|contract| contract.upgrade(code_hash));
+            client
+                .call(&ink_e2e::alice(), upgrade, 0, None)
+                .await
+                .expect("upgrade failed");
+
+            // the packed fields of version 1 keep their values
+            let (flash_fee_bps, storage_version) = {
+                let fee = build_message::<ContractRef>(address.clone())
+                    .call(|contract| contract.flash_fee_bps());
+                let version = build_message::<ContractRef>(address.clone())
+                    .call(|contract| contract.storage_version());
+                (
+                    client
+                        .call_dry_run(&ink_e2e::alice(), &fee, 0, None)
+                        .await
+                        .return_value(),
+                    client
+                        .call_dry_run(&ink_e2e::alice(), &version, 0, None)
+                        .await
+                        .return_value(),
+                )
+            };
+            assert_eq!(flash_fee_bps, 25);
+            assert_eq!(storage_version, 1);
+            assert_eq!(balance_of(&mut client, address, bob).await, 30);
+
+            // settings added after version 1 read as unset and can be changed
+            let (transfer_fee_bps, supply_cap) = {
+                let fee = build_message::<ContractRef>(address.clone())
+                    .call(|contract| contract.transfer_fee_bps());
+                let cap = build_message::<ContractRef>(address.clone())
+                    .call(|contract| contract.supply_cap());
+                (
+                    client
+                        .call_dry_run(&ink_e2e::alice(), &fee, 0, None)
+                        .await
+                        .return_value(),
+                    client
+                        .call_dry_run(&ink_e2e::alice(), &cap, 0, None)
+                        .await
+                        .return_value(),
+                )
+            };
+            assert_eq!(transfer_fee_bps, 0);
+            assert_eq!(supply_cap, None);
+            let freeze = build_message::<ContractRef>(address.clone())
+                .call(|contract| contract.freeze(charlie));
+            client
+                .call(&ink_e2e::alice(), freeze, 0, None)
+                .await
+                .expect("freeze failed");
+            let frozen = {
+                let _msg = build_message::<ContractRef>(address.clone())
+                    .call(|contract| contract.frozen_accounts(0, 10));
+                client
+                    .call_dry_run(&ink_e2e::alice(), &_msg, 0, None)
+                    .await
+                    .return_value()
+            };
+            assert_eq!(frozen, vec![charlie]);
+
+            Ok(())
+        }
     }
 }
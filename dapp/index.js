@@ -96,6 +96,19 @@ async function signPayload(payloadAsStr, source, address) {
   }
 }
 
+async function signRaw(data, source, address) {
+  const extensionMod = await getPolkadotJsExtensionMod();
+  const injector = await extensionMod.web3FromSource(source);
+  const signRaw = injector?.signer?.signRaw;
+  if (!!signRaw) {
+    // the extension wraps the data in <Bytes>...</Bytes> before signing
+    const { signature } = await signRaw({ address, data, type: "bytes" });
+    return signature;
+  } else {
+    throw "The extension's injector does not have a `signRaw` function on its `signer`";
+  }
+}
+
 // Load the contract metadata from a JSON file
 async function loadContractMetadata() {
   const response = await fetch("y_psp22_token.json");
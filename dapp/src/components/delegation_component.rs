@@ -0,0 +1,107 @@
+use wasm_bindgen::JsCast;
+use web_sys::EventTarget;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use crate::services::votes::VotingPower;
+
+/// shows the delegate and the voting power of the account and delegates its votes
+pub struct DelegationComponent {
+    delegatee: String,
+}
+
+pub enum Msg {
+    UpdateDelegatee(String),
+    Delegate,
+}
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct Props {
+    pub voting_power: Option<VotingPower>,
+    /// emits the address of the new delegate
+    pub ondelegate: Callback<String>,
+}
+
+impl Component for DelegationComponent {
+    type Message = Msg;
+    type Properties = Props;
+
+    fn create(_: &Context<Self>) -> Self {
+        DelegationComponent {
+            delegatee: String::new(),
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::UpdateDelegatee(delegatee) => {
+                self.delegatee = delegatee;
+            }
+            Msg::Delegate => {
+                ctx.props().ondelegate.emit(self.delegatee.clone());
+            }
+        }
+        true
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let update_delegatee = ctx.link().callback(|e: Event| {
+            let target: EventTarget = e
+                .target()
+                .expect("Event should have a target when dispatched");
+            Msg::UpdateDelegatee(target.unchecked_into::<HtmlInputElement>().value())
+        });
+        let delegate = ctx.link().callback(|_| Msg::Delegate);
+
+        let power_html = match &ctx.props().voting_power {
+            Some(power) => html! {
+                <table class="mb">
+                    <tr>
+                        <td>{"Delegate"}</td>
+                        <td><small>{power.delegate.as_ref().map(|delegate| delegate.to_string()).unwrap_or("none, the tokens don't count as votes".to_string())}</small></td>
+                    </tr>
+                    <tr><td>{"Voting power"}</td><td>{power.votes}</td></tr>
+                </table>
+            },
+            None => html!(<div class="mb loading">{"Loading voting power..."}</div>),
+        };
+
+        html! {
+            <div class="mb">
+                <div class="mb"><b>{"Delegation"}</b></div>
+                {power_html}
+                <div>
+                    <input
+                        value={self.delegatee.clone()}
+                        onchange={update_delegatee}
+                        placeholder="Delegate address, your own to vote yourself"
+                    />
+                </div>
+                <button onclick={delegate}>{"Preview delegation"}</button>
+            </div>
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use subxt::utils::AccountId32;
+
+    #[wasm_bindgen_test::wasm_bindgen_test]
+    async fn shows_delegate_and_votes() {
+        let delegate = AccountId32([2; 32]);
+        let rendered = yew::LocalServerRenderer::<DelegationComponent>::with_props(Props {
+            voting_power: Some(VotingPower {
+                delegate: Some(delegate.clone()),
+                votes: 1234,
+            }),
+            ondelegate: Callback::default(),
+        })
+        .render()
+        .await;
+
+        assert!(rendered.contains(&delegate.to_string()));
+        assert!(rendered.contains("1234"));
+    }
+}
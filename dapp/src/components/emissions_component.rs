@@ -0,0 +1,148 @@
+use yew::prelude::*;
+
+use crate::services::emissions::{EmissionState, SupplyPoint};
+
+const CHART_WIDTH: u32 = 400;
+const CHART_HEIGHT: u32 = 150;
+
+/// shows the emission schedule and a chart of the projected vs. the actual total supply
+pub struct EmissionsComponent;
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct Props {
+    /// `None` while loading
+    pub state: Option<EmissionState>,
+    pub chart: Vec<SupplyPoint>,
+    pub onmint: Callback<()>,
+}
+
+impl Component for EmissionsComponent {
+    type Message = ();
+    type Properties = Props;
+
+    fn create(_: &Context<Self>) -> Self {
+        EmissionsComponent
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let props = ctx.props();
+        let Some(state) = &props.state else {
+            return html!(<div class="mb loading">{"Loading emissions..."}</div>);
+        };
+        let Some(recipient) = &state.recipient else {
+            return html!(<></>);
+        };
+        let (projected, actual) = polylines(&props.chart);
+        let mint = props.onmint.reform(|_: MouseEvent| ());
+
+        html! {
+            <div class="mb">
+                <div class="mb"><b>{"Emissions"}</b></div>
+                <div class="mb">
+                    {format!("{} tokens per block to {recipient}", state.rate)}
+                </div>
+                if let Some(cap) = state.cap {
+                    <div class="mb"><small>{format!("Supply cap: {cap}")}</small></div>
+                }
+                <svg class="mb" width={CHART_WIDTH.to_string()} height={CHART_HEIGHT.to_string()}>
+                    <polyline points={projected} fill="none" stroke="gray" stroke-dasharray="4"/>
+                    <polyline points={actual} fill="none" stroke="green"/>
+                </svg>
+                <div class="mb">
+                    <small>{format!("Blocks {} to {}, dashed: projected supply", first_block(&props.chart), last_block(&props.chart))}</small>
+                </div>
+                <div class="mb">{format!("Pending emissions: {}", state.pending)}</div>
+                <button onclick={mint} disabled={state.pending == 0}>{"Mint emissions"}</button>
+            </div>
+        }
+    }
+}
+
+fn first_block(chart: &[SupplyPoint]) -> u32 {
+    chart.first().map(|point| point.block).unwrap_or_default()
+}
+
+fn last_block(chart: &[SupplyPoint]) -> u32 {
+    chart.last().map(|point| point.block).unwrap_or_default()
+}
+
+/// svg points of the projected and the actual supply, scaled to the chart
+fn polylines(chart: &[SupplyPoint]) -> (String, String) {
+    let (first, last) = (first_block(chart), last_block(chart));
+    let blocks = last.saturating_sub(first).max(1) as f64;
+    let values = chart
+        .iter()
+        .flat_map(|point| point.actual.into_iter().chain([point.projected]));
+    let min = values.clone().min().unwrap_or_default();
+    let range = values.max().unwrap_or_default().saturating_sub(min).max(1) as f64;
+
+    let to_point = |block: u32, value: u128| {
+        let x = (block - first) as f64 / blocks * CHART_WIDTH as f64;
+        // a margin of 5 pixels keeps the lines at the edges visible
+        let y = 5.0 + (1.0 - (value - min) as f64 / range) * (CHART_HEIGHT - 10) as f64;
+        format!("{x:.1},{y:.1}")
+    };
+    let projected = chart
+        .iter()
+        .map(|point| to_point(point.block, point.projected))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let actual = chart
+        .iter()
+        .filter_map(|point| point.actual.map(|actual| to_point(point.block, actual)))
+        .collect::<Vec<_>>()
+        .join(" ");
+    (projected, actual)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use subxt::utils::AccountId32;
+
+    #[wasm_bindgen_test::wasm_bindgen_test]
+    fn actual_supply_ends_at_the_latest_block() {
+        let chart = vec![
+            SupplyPoint {
+                block: 0,
+                actual: Some(100),
+                projected: 100,
+            },
+            SupplyPoint {
+                block: 10,
+                actual: Some(100),
+                projected: 200,
+            },
+            SupplyPoint {
+                block: 20,
+                actual: None,
+                projected: 300,
+            },
+        ];
+        let (projected, actual) = polylines(&chart);
+
+        assert_eq!(projected, "0.0,145.0 200.0,75.0 400.0,5.0");
+        assert_eq!(actual, "0.0,145.0 200.0,145.0");
+    }
+
+    #[wasm_bindgen_test::wasm_bindgen_test]
+    async fn shows_pending_emissions() {
+        let rendered = yew::LocalServerRenderer::<EmissionsComponent>::with_props(Props {
+            state: Some(EmissionState {
+                recipient: Some(AccountId32([1; 32])),
+                rate: 10,
+                last_block: 0,
+                cap: Some(1_000),
+                pending: 100,
+                block: 10,
+            }),
+            chart: Vec::new(),
+            onmint: Callback::default(),
+        })
+        .render()
+        .await;
+
+        assert!(rendered.contains("Pending emissions: 100"));
+        assert!(rendered.contains("Supply cap: 1000"));
+    }
+}
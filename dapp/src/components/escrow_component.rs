@@ -0,0 +1,115 @@
+use subxt::utils::AccountId32;
+use wasm_bindgen::JsValue;
+use yew::prelude::*;
+
+use crate::services::escrow::{Deal, EscrowCall};
+
+/// the open deals of an account with the calls its roles allow
+pub struct EscrowComponent;
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct Props {
+    /// `None` while loading
+    pub deals: Option<Vec<(u32, Deal)>>,
+    /// the selected account
+    pub account: AccountId32,
+    /// current timestamp in milliseconds, the buyer refunds after the deadline
+    pub now: u64,
+    pub oncall: Callback<EscrowCall>,
+}
+
+impl Component for EscrowComponent {
+    type Message = ();
+    type Properties = Props;
+
+    fn create(_: &Context<Self>) -> Self {
+        EscrowComponent
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let props = ctx.props();
+        let Some(deals) = &props.deals else {
+            return html!(<div class="mb loading">{"Loading deals..."}</div>);
+        };
+        if deals.is_empty() {
+            return html!(<div class="mb">{"This account has no open deals."}</div>);
+        }
+
+        html! {
+            <table class="mb">
+                <tr>
+                    <th>{"Deal"}</th>
+                    <th>{"Role"}</th>
+                    <th>{"Amount"}</th>
+                    <th>{"Deadline"}</th>
+                    <th>{"Status"}</th>
+                    <th></th>
+                </tr>
+                { for deals.iter().map(|(id, deal)| {
+                    let status = if deal.disputed {
+                        "disputed"
+                    } else if props.now > deal.deadline {
+                        "expired"
+                    } else {
+                        "open"
+                    };
+                    html! {
+                        <tr>
+                            <td>{format!("#{id}")}</td>
+                            <td>{deal.roles_of(&props.account)}</td>
+                            <td>{deal.amount}</td>
+                            <td><small>{format_timestamp(deal.deadline)}</small></td>
+                            <td>{status}</td>
+                            <td>
+                                { for deal.calls_of(*id, &props.account, props.now).into_iter().map(|call| {
+                                    let label = call.label();
+                                    let onclick = props.oncall.reform(move |_: MouseEvent| call.clone());
+                                    html!(<button {onclick}>{label}</button>)
+                                }) }
+                            </td>
+                        </tr>
+                    }
+                }) }
+            </table>
+        }
+    }
+}
+
+fn format_timestamp(timestamp: u64) -> String {
+    js_sys::Date::new(&JsValue::from_f64(timestamp as f64))
+        .to_iso_string()
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[wasm_bindgen_test::wasm_bindgen_test]
+    async fn shows_the_calls_of_the_seller() {
+        let seller = AccountId32([2; 32]);
+        let rendered = yew::LocalServerRenderer::<EscrowComponent>::with_props(Props {
+            deals: Some(vec![(
+                3,
+                Deal {
+                    buyer: AccountId32([1; 32]),
+                    seller: seller.clone(),
+                    arbiter: AccountId32([3; 32]),
+                    amount: 1_000,
+                    deadline: 100,
+                    disputed: false,
+                },
+            )]),
+            account: seller,
+            now: 50,
+            oncall: Callback::default(),
+        })
+        .render()
+        .await;
+
+        assert!(rendered.contains("#3"));
+        assert!(rendered.contains("seller"));
+        assert!(rendered.contains("Dispute"));
+        assert!(!rendered.contains("Release"));
+    }
+}
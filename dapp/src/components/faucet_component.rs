@@ -0,0 +1,85 @@
+use yew::prelude::*;
+
+use crate::services::faucet::FaucetState;
+
+/// claims test tokens from the faucet and shows the time until the next claim
+pub struct FaucetComponent;
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct Props {
+    /// `None` while loading
+    pub state: Option<FaucetState>,
+    /// current timestamp in milliseconds
+    pub now: u64,
+    pub onclaim: Callback<()>,
+}
+
+impl Component for FaucetComponent {
+    type Message = ();
+    type Properties = Props;
+
+    fn create(_: &Context<Self>) -> Self {
+        FaucetComponent
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let props = ctx.props();
+        let Some(state) = &props.state else {
+            return html!(<div class="mb loading">{"Loading faucet..."}</div>);
+        };
+        if !state.is_enabled() {
+            return html!(<></>);
+        }
+        let wait = state.wait_at(props.now);
+        let claim = props.onclaim.reform(|_: MouseEvent| ());
+
+        html! {
+            <div class="mb">
+                <div class="mb"><b>{"Faucet"}</b></div>
+                <div class="mb">
+                    {format!("{} tokens per claim, one claim every {}", state.amount, format_duration(state.cooldown))}
+                </div>
+                if wait > 0 {
+                    <div class="mb"><small>{"Next claim in "}{format_duration(wait)}</small></div>
+                }
+                <button onclick={claim} disabled={wait > 0}>{"Claim test tokens"}</button>
+            </div>
+        }
+    }
+}
+
+/// milliseconds as hours, minutes and seconds, rounded up to full seconds
+fn format_duration(millis: u64) -> String {
+    let seconds = (millis + 999) / 1000;
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    if hours > 0 {
+        format!("{hours}h {minutes}m {seconds}s")
+    } else if minutes > 0 {
+        format!("{minutes}m {seconds}s")
+    } else {
+        format!("{seconds}s")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[wasm_bindgen_test::wasm_bindgen_test]
+    async fn shows_time_until_next_claim() {
+        let rendered = yew::LocalServerRenderer::<FaucetComponent>::with_props(Props {
+            state: Some(FaucetState {
+                amount: 100,
+                cooldown: 3_600_000,
+                next_claim: 100_000,
+            }),
+            now: 10_000,
+            onclaim: Callback::default(),
+        })
+        .render()
+        .await;
+
+        assert!(rendered.contains("Next claim in 1m 30s"));
+        assert!(rendered.contains("one claim every 1h 0m 0s"));
+    }
+}
@@ -0,0 +1,97 @@
+use yew::prelude::*;
+
+use crate::services::contracts_api::StorageDeposit;
+use crate::services::fees::FeeEstimate;
+
+/// shows the estimated fees of a prepared extrinsic and asks for confirmation before the extension prompt
+pub struct FeeConfirmationComponent;
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct Props {
+    pub title: AttrValue,
+    pub fees: FeeEstimate,
+    pub onconfirm: Callback<()>,
+    pub oncancel: Callback<()>,
+}
+
+impl Component for FeeConfirmationComponent {
+    type Message = ();
+    type Properties = Props;
+
+    fn create(_: &Context<Self>) -> Self {
+        FeeConfirmationComponent
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let props = ctx.props();
+        let fees = &props.fees;
+        let confirm = props.onconfirm.reform(|_: MouseEvent| ());
+        let cancel = props.oncancel.reform(|_: MouseEvent| ());
+
+        let storage_deposit_html = match &fees.storage_deposit {
+            Some(StorageDeposit::Charge(amount)) => {
+                html!(<tr><td>{"Storage deposit (charged)"}</td><td>{amount}</td></tr>)
+            }
+            Some(StorageDeposit::Refund(amount)) => {
+                html!(<tr><td>{"Storage deposit (refunded)"}</td><td>{amount}</td></tr>)
+            }
+            None => html!(<></>),
+        };
+
+        html! {
+            <div class="mb">
+                <div class="mb"><b>{&props.title}</b></div>
+                <table class="mb">
+                    <tr><td>{"Base fee"}</td><td>{fees.base_fee}</td></tr>
+                    <tr><td>{"Length fee"}</td><td>{fees.len_fee}</td></tr>
+                    <tr><td>{"Weight fee"}</td><td>{fees.adjusted_weight_fee}</td></tr>
+                    <tr><td>{"Tip"}</td><td>{fees.tip}</td></tr>
+                    {storage_deposit_html}
+                    <tr><td><b>{"Total"}</b></td><td><b>{fees.total()}</b></td></tr>
+                    <tr>
+                        <td>{"Weight"}</td>
+                        <td><small>{format!("ref_time: {}, proof_size: {}", fees.weight_ref_time, fees.weight_proof_size)}</small></td>
+                    </tr>
+                </table>
+                <button onclick={confirm}>{"=> Confirm and sign with extension"}</button>
+                <button onclick={cancel}>{"Cancel"}</button>
+            </div>
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[wasm_bindgen_test::wasm_bindgen_test]
+    async fn shows_fee_components() {
+        let rendered = yew::LocalServerRenderer::<FeeConfirmationComponent>::with_props(Props {
+            title: "Transfer".into(),
+            fees: FeeEstimate {
+                base_fee: 11,
+                len_fee: 22,
+                adjusted_weight_fee: 33,
+                partial_fee: 66,
+                storage_deposit: Some(StorageDeposit::Charge(4)),
+                ..Default::default()
+            },
+            onconfirm: Callback::default(),
+            oncancel: Callback::default(),
+        })
+        .render()
+        .await;
+
+        for row in [
+            "<td>Base fee</td><td>11</td>",
+            "<td>Length fee</td><td>22</td>",
+            "<td>Weight fee</td><td>33</td>",
+            "<td>Tip</td><td>0</td>",
+            "<td>Storage deposit (charged)</td><td>4</td>",
+            // partial fee and charged storage deposit
+            "<td><b>Total</b></td><td><b>70</b></td>",
+        ] {
+            assert!(rendered.contains(row), "{row} missing in {rendered}");
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use subxt::utils::AccountId32;
+use wasm_bindgen::JsCast;
+use web_sys::EventTarget;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+/// lists the frozen accounts of the token, the owner can freeze and unfreeze accounts
+pub struct FrozenAccountsComponent {
+    account: String,
+}
+
+pub enum Msg {
+    UpdateAccount(String),
+    Freeze,
+}
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct Props {
+    /// `None` while loading
+    pub frozen_accounts: Option<Vec<AccountId32>>,
+    /// the selected account owns the token, only the owner can freeze accounts
+    pub is_owner: bool,
+    /// emits the address of the account to freeze
+    pub onfreeze: Callback<String>,
+    pub onunfreeze: Callback<AccountId32>,
+}
+
+impl Component for FrozenAccountsComponent {
+    type Message = Msg;
+    type Properties = Props;
+
+    fn create(_: &Context<Self>) -> Self {
+        FrozenAccountsComponent {
+            account: String::new(),
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::UpdateAccount(account) => {
+                self.account = account;
+            }
+            Msg::Freeze => {
+                ctx.props().onfreeze.emit(self.account.clone());
+            }
+        }
+        true
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let props = ctx.props();
+        let update_account = ctx.link().callback(|e: Event| {
+            let target: EventTarget = e
+                .target()
+                .expect("Event should have a target when dispatched");
+            Msg::UpdateAccount(target.unchecked_into::<HtmlInputElement>().value())
+        });
+        let freeze = ctx.link().callback(|_| Msg::Freeze);
+
+        let frozen_html = match &props.frozen_accounts {
+            None => html!(<div class="mb loading">{"Loading frozen accounts..."}</div>),
+            Some(accounts) if accounts.is_empty() => {
+                html!(<div class="mb"><small>{"No account is frozen"}</small></div>)
+            }
+            Some(accounts) => html! {
+                <table class="mb">
+                    <tr><th>{"Frozen account"}</th><th></th></tr>
+                    { for accounts.iter().map(|account| {
+                        let unfreeze = {
+                            let account = account.clone();
+                            props.onunfreeze.reform(move |_: MouseEvent| account.clone())
+                        };
+                        html! {
+                            <tr>
+                                <td><small>{account.to_string()}</small></td>
+                                <td>
+                                    if props.is_owner {
+                                        <button onclick={unfreeze}>{"Preview unfreeze"}</button>
+                                    }
+                                </td>
+                            </tr>
+                        }
+                    }) }
+                </table>
+            },
+        };
+
+        html! {
+            <div class="mb">
+                <div class="mb"><b>{"Frozen accounts"}</b></div>
+                {frozen_html}
+                if props.is_owner {
+                    <div>
+                        <input
+                            value={self.account.clone()}
+                            onchange={update_account}
+                            placeholder="Account to freeze"
+                        />
+                    </div>
+                    <button onclick={freeze}>{"Preview freeze"}</button>
+                } else {
+                    <div class="mb"><small>{"Only the owner of the token can freeze accounts"}</small></div>
+                }
+            </div>
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[wasm_bindgen_test::wasm_bindgen_test]
+    async fn hides_controls_from_non_owners() {
+        let frozen = AccountId32([2; 32]);
+        let rendered = yew::LocalServerRenderer::<FrozenAccountsComponent>::with_props(Props {
+            frozen_accounts: Some(vec![frozen.clone()]),
+            is_owner: false,
+            onfreeze: Callback::default(),
+            onunfreeze: Callback::default(),
+        })
+        .render()
+        .await;
+
+        assert!(rendered.contains(&frozen.to_string()));
+        assert!(!rendered.contains("Preview unfreeze"));
+        assert!(!rendered.contains("Preview freeze"));
+    }
+}
@@ -1,2 +1,30 @@
+mod delegation_component;
+mod emissions_component;
+mod escrow_component;
+mod faucet_component;
+mod fee_confirmation_component;
+mod frozen_accounts_component;
+mod payouts_component;
+mod permit_component;
+mod proposals_component;
 mod send_token_component;
-pub use send_token_component::SendTokenComponent;
+mod simulation_component;
+mod streams_component;
+mod vesting_component;
+mod watchlist_component;
+mod wrap_component;
+pub use delegation_component::DelegationComponent;
+pub use emissions_component::EmissionsComponent;
+pub use escrow_component::EscrowComponent;
+pub use faucet_component::FaucetComponent;
+pub use fee_confirmation_component::FeeConfirmationComponent;
+pub use frozen_accounts_component::FrozenAccountsComponent;
+pub use payouts_component::PayoutsComponent;
+pub use permit_component::PermitComponent;
+pub use proposals_component::ProposalsComponent;
+pub use send_token_component::{SendTokenComponent, TokenAction};
+pub use simulation_component::SimulationComponent;
+pub use streams_component::StreamsComponent;
+pub use vesting_component::VestingComponent;
+pub use watchlist_component::WatchlistComponent;
+pub use wrap_component::WrapComponent;
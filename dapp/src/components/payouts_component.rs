@@ -0,0 +1,182 @@
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::EventTarget;
+use web_sys::HtmlInputElement;
+use web_sys::HtmlTextAreaElement;
+use yew::prelude::*;
+
+use crate::services::payouts::{PayoutBatch, RowResult};
+
+pub struct PayoutsComponent {
+    csv: String,
+    batch: PayoutBatch,
+}
+
+pub enum Msg {
+    UpdateCsv(String),
+    LoadFile(web_sys::File),
+    SubmitPayouts,
+}
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct Props {
+    /// balance of the sender, used to preview the total
+    pub balance: Option<u128>,
+    /// transfer fee in basis points the sender pays on each row
+    #[prop_or_default]
+    pub transfer_fee_bps: u16,
+    /// per-row results of the last submitted batch
+    #[prop_or_default]
+    pub results: Option<Vec<RowResult>>,
+    #[prop_or_default]
+    pub submitting: bool,
+    pub onsubmit: Callback<PayoutBatch>,
+}
+
+impl Component for PayoutsComponent {
+    type Message = Msg;
+    type Properties = Props;
+
+    fn create(_: &Context<Self>) -> Self {
+        PayoutsComponent {
+            csv: String::new(),
+            batch: PayoutBatch::default(),
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::UpdateCsv(csv) => {
+                self.batch = PayoutBatch::parse(&csv);
+                self.csv = csv;
+            }
+            Msg::LoadFile(file) => {
+                ctx.link().send_future(async move {
+                    let text = JsFuture::from(file.text())
+                        .await
+                        .ok()
+                        .and_then(|text| text.as_string())
+                        .unwrap_or_default();
+                    Msg::UpdateCsv(text)
+                });
+                return false;
+            }
+            Msg::SubmitPayouts => {
+                ctx.props().onsubmit.emit(self.batch.clone());
+            }
+        }
+        true
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let update_csv = ctx.link().callback(|e: InputEvent| {
+            let target: EventTarget = e
+                .target()
+                .expect("Event should have a target when dispatched");
+            let value = target.unchecked_into::<HtmlTextAreaElement>().value();
+            Msg::UpdateCsv(value)
+        });
+
+        let load_file = ctx.link().batch_callback(|e: Event| {
+            let target: EventTarget = e
+                .target()
+                .expect("Event should have a target when dispatched");
+            target
+                .unchecked_into::<HtmlInputElement>()
+                .files()
+                .and_then(|files| files.get(0))
+                .map(Msg::LoadFile)
+        });
+
+        let submit_payouts = ctx.link().callback(|_| Msg::SubmitPayouts);
+
+        let props = ctx.props();
+        let total = self.batch.total();
+        let fees = self.batch.transfer_fees(props.transfer_fee_bps);
+        let covered = props
+            .balance
+            .map(|balance| self.batch.is_covered_by(balance, props.transfer_fee_bps))
+            .unwrap_or(false);
+        let can_submit = self.batch.is_valid() && covered && !props.submitting;
+
+        let row_result = |i: usize| -> Html {
+            match props.results.as_ref().and_then(|results| results.get(i)) {
+                Some(RowResult::Completed) => html!(<>{"completed"}</>),
+                Some(RowResult::Failed(reason)) => html!(<span class="error">{reason}</span>),
+                None => html!(<>{"pending"}</>),
+            }
+        };
+
+        html! {
+            <div>
+                <div class="mb"><b>{"Batch payouts (address,amount[,memo]):"}</b></div>
+                <div class="mb">
+                    <input type="file" accept=".csv,text/csv" onchange={load_file}/>
+                </div>
+                <div class="mb">
+                    <textarea
+                        value={self.csv.clone()}
+                        oninput={update_csv}
+                        placeholder="address,amount,memo"
+                        rows="6"
+                    />
+                </div>
+                if !self.batch.rows.is_empty() {
+                    <table class="mb">
+                        <tr><th>{"Line"}</th><th>{"Address"}</th><th>{"Amount"}</th><th>{"Memo"}</th><th>{"Result"}</th></tr>
+                        { for self.batch.rows.iter().enumerate().map(|(i, row)| html! {
+                            <tr>
+                                <td>{row.line}</td>
+                                <td><small>{&row.address}</small></td>
+                                <td>{row.amount}</td>
+                                <td>{row.memo.clone().unwrap_or_default()}</td>
+                                <td>{row_result(i)}</td>
+                            </tr>
+                        }) }
+                    </table>
+                }
+                { for self.batch.invalid.iter().map(|row| html! {
+                    <div class="error mb">{format!("Line {}: {} ({})", row.line, row.reason, row.content)}</div>
+                }) }
+                <div class="mb">
+                    {"Total: "}
+                    {total.map(|total| total.to_string()).unwrap_or_else(|| "overflow".to_string())}
+                    if props.transfer_fee_bps > 0 {
+                        {" + transfer fees: "}
+                        {fees.map(|fees| fees.to_string()).unwrap_or_else(|| "overflow".to_string())}
+                    }
+                    {" / Balance: "}
+                    {props.balance.map(|balance| balance.to_string()).unwrap_or_else(|| "unknown".to_string())}
+                </div>
+                if !self.batch.rows.is_empty() && !covered {
+                    <div class="error mb">{"The total and its transfer fees exceed the balance of the sender."}</div>
+                }
+                <button onclick={submit_payouts} disabled={!can_submit}>
+                    {format!("Send {} payouts with one signature", self.batch.rows.len())}
+                </button>
+            </div>
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[wasm_bindgen_test::wasm_bindgen_test]
+    async fn create_default() {
+        let onsubmit = Callback::default();
+        let rendered = yew::LocalServerRenderer::<PayoutsComponent>::with_props(Props {
+            balance: Some(100),
+            transfer_fee_bps: 0,
+            results: None,
+            submitting: false,
+            onsubmit,
+        })
+        .render()
+        .await;
+
+        assert!(rendered.contains("Batch payouts"));
+        assert!(rendered.contains("100"));
+    }
+}
@@ -0,0 +1,169 @@
+use wasm_bindgen::JsCast;
+use web_sys::EventTarget;
+use web_sys::HtmlInputElement;
+use web_sys::HtmlTextAreaElement;
+use yew::prelude::*;
+
+use crate::services::permit::SignedPermit;
+
+/// signs permits with the selected account and relays permits signed by other accounts
+pub struct PermitComponent {
+    spender: String,
+    value: u128,
+    valid_minutes: u64,
+    relay_input: String,
+    relay_error: Option<String>,
+}
+
+pub enum Msg {
+    UpdateSpender(String),
+    UpdateValue(String),
+    UpdateValidMinutes(String),
+    SignPermit,
+    UpdateRelayInput(String),
+    RelayPermit,
+}
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct Props {
+    /// hex of the permit signed last, to be handed to the relayer
+    #[prop_or_default]
+    pub signed_permit: Option<AttrValue>,
+    /// emits the spender, the value and how many minutes the permit is valid
+    pub onsign: Callback<(String, u128, u64)>,
+    pub onrelay: Callback<SignedPermit>,
+}
+
+impl Component for PermitComponent {
+    type Message = Msg;
+    type Properties = Props;
+
+    fn create(_: &Context<Self>) -> Self {
+        PermitComponent {
+            spender: "5FHneW46xGXgs5mUiveU4sbTyGBzmstUspZC92UhjJM694ty".to_string(),
+            value: 1,
+            valid_minutes: 60,
+            relay_input: String::new(),
+            relay_error: None,
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::UpdateSpender(spender) => {
+                self.spender = spender;
+            }
+            Msg::UpdateValue(value) => {
+                if let Ok(value) = value.parse::<u128>() {
+                    self.value = value;
+                }
+            }
+            Msg::UpdateValidMinutes(minutes) => {
+                if let Ok(minutes) = minutes.parse::<u64>() {
+                    self.valid_minutes = minutes;
+                }
+            }
+            Msg::SignPermit => {
+                ctx.props()
+                    .onsign
+                    .emit((self.spender.clone(), self.value, self.valid_minutes));
+            }
+            Msg::UpdateRelayInput(input) => {
+                self.relay_input = input;
+                self.relay_error = None;
+            }
+            Msg::RelayPermit => match SignedPermit::from_hex(&self.relay_input) {
+                Ok(permit) => ctx.props().onrelay.emit(permit),
+                Err(err) => self.relay_error = Some(format!("Invalid permit: {err}")),
+            },
+        }
+        true
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let input_callback = |msg: fn(String) -> Msg| {
+            ctx.link().callback(move |e: Event| {
+                let target: EventTarget = e
+                    .target()
+                    .expect("Event should have a target when dispatched");
+                msg(target.unchecked_into::<HtmlInputElement>().value())
+            })
+        };
+        let update_relay_input = ctx.link().callback(|e: InputEvent| {
+            let target: EventTarget = e
+                .target()
+                .expect("Event should have a target when dispatched");
+            Msg::UpdateRelayInput(target.unchecked_into::<HtmlTextAreaElement>().value())
+        });
+        let sign_permit = ctx.link().callback(|_| Msg::SignPermit);
+        let relay_permit = ctx.link().callback(|_| Msg::RelayPermit);
+
+        html! {
+            <div class="mb">
+                <div class="mb"><b>{"Sign a permit"}</b></div>
+                <div>
+                    <input
+                        value={self.spender.clone()}
+                        onchange={input_callback(Msg::UpdateSpender)}
+                        placeholder="Spender"
+                    />
+                </div>
+                <div>
+                    <input
+                        value={self.value.to_string()}
+                        onchange={input_callback(Msg::UpdateValue)}
+                        placeholder="Value"
+                        type="number"
+                    />
+                </div>
+                <div>
+                    <input
+                        value={self.valid_minutes.to_string()}
+                        onchange={input_callback(Msg::UpdateValidMinutes)}
+                        placeholder="Valid for minutes"
+                        type="number"
+                    />
+                </div>
+                <div class="mb">
+                    <button onclick={sign_permit}>{"Sign permit with extension"}</button>
+                </div>
+                if let Some(signed_permit) = &ctx.props().signed_permit {
+                    <div class="mb">
+                        <div>{"Signed permit, hand it to the account submitting it:"}</div>
+                        <small>{signed_permit}</small>
+                    </div>
+                }
+                <div class="mb"><b>{"Submit a permit signed by another account"}</b></div>
+                <textarea
+                    value={self.relay_input.clone()}
+                    oninput={update_relay_input}
+                    placeholder="0x..."
+                />
+                if let Some(error) = &self.relay_error {
+                    <div class="error mb">{error}</div>
+                }
+                <div>
+                    <button onclick={relay_permit}>{"Preview permit"}</button>
+                </div>
+            </div>
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[wasm_bindgen_test::wasm_bindgen_test]
+    async fn shows_signed_permit() {
+        let rendered = yew::LocalServerRenderer::<PermitComponent>::with_props(Props {
+            signed_permit: Some("0x0102".into()),
+            onsign: Callback::default(),
+            onrelay: Callback::default(),
+        })
+        .render()
+        .await;
+
+        assert!(rendered.contains("0x0102"));
+    }
+}
@@ -0,0 +1,127 @@
+use subxt::utils::AccountId32;
+use yew::prelude::*;
+
+use crate::services::multisig::{MultisigCall, MultisigState};
+
+/// the pending proposals of the multisig owning the token, its owners approve and execute them
+pub struct ProposalsComponent;
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct Props {
+    pub state: MultisigState,
+    /// the selected account
+    pub account: AccountId32,
+    pub oncall: Callback<MultisigCall>,
+}
+
+impl Component for ProposalsComponent {
+    type Message = ();
+    type Properties = Props;
+
+    fn create(_: &Context<Self>) -> Self {
+        ProposalsComponent
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let props = ctx.props();
+        let state = &props.state;
+        let is_owner = state.is_owner(&props.account);
+
+        let proposals_html = if state.pending.is_empty() {
+            html!(<div class="mb"><small>{"No proposal is pending"}</small></div>)
+        } else {
+            html! {
+                <table class="mb">
+                    <tr>
+                        <th>{"Proposal"}</th>
+                        <th>{"Message"}</th>
+                        <th>{"Input"}</th>
+                        <th>{"Approvals"}</th>
+                        <th></th>
+                    </tr>
+                    { for state.pending.iter().map(|(id, proposal)| {
+                        let id = *id;
+                        let approved = proposal.approvals.contains(&props.account);
+                        let executable = proposal.approvals.len() as u32 >= state.threshold;
+                        html! {
+                            <tr>
+                                <td>{format!("#{id}")}</td>
+                                <td>{proposal.message()}</td>
+                                <td><small>{format!("0x{}", hex::encode(&proposal.input))}</small></td>
+                                <td>{format!("{} / {}", proposal.approvals.len(), state.threshold)}</td>
+                                <td>
+                                    if is_owner && !approved {
+                                        <button onclick={props.oncall.reform(move |_: MouseEvent| MultisigCall::Approve(id))}>
+                                            {"Approve"}
+                                        </button>
+                                    }
+                                    if approved {
+                                        <button onclick={props.oncall.reform(move |_: MouseEvent| MultisigCall::Revoke(id))}>
+                                            {"Revoke"}
+                                        </button>
+                                    }
+                                    if is_owner && executable {
+                                        <button onclick={props.oncall.reform(move |_: MouseEvent| MultisigCall::Execute(id))}>
+                                            {"Execute"}
+                                        </button>
+                                    }
+                                </td>
+                            </tr>
+                        }
+                    }) }
+                </table>
+            }
+        };
+
+        html! {
+            <div class="mb">
+                <div class="mb">
+                    <b>{"Pending proposals"}</b>
+                    {format!(" ({} of {} owners approve a call)", state.threshold, state.owners.len())}
+                </div>
+                {proposals_html}
+                if !is_owner {
+                    <div class="mb"><small>{"Only owners of the multisig can approve proposals"}</small></div>
+                }
+            </div>
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::multisig::Proposal;
+    use crate::services::psp22::FREEZE_SELECTOR;
+
+    #[wasm_bindgen_test::wasm_bindgen_test]
+    async fn offers_execution_once_the_threshold_is_reached() {
+        let (alice, bob) = (AccountId32([1; 32]), AccountId32([2; 32]));
+        let rendered = yew::LocalServerRenderer::<ProposalsComponent>::with_props(Props {
+            state: MultisigState {
+                owners: vec![alice.clone(), bob.clone(), AccountId32([3; 32])],
+                threshold: 2,
+                pending: vec![(
+                    4,
+                    Proposal {
+                        proposer: alice.clone(),
+                        callee: AccountId32([9; 32]),
+                        selector: FREEZE_SELECTOR,
+                        input: vec![7; 32],
+                        approvals: vec![alice.clone(), bob],
+                    },
+                )],
+            },
+            account: alice,
+            oncall: Callback::default(),
+        })
+        .render()
+        .await;
+
+        assert!(rendered.contains("#4"));
+        assert!(rendered.contains("freeze"));
+        assert!(rendered.contains("2 / 2"));
+        assert!(rendered.contains("Execute"));
+        assert!(!rendered.contains("Approve"));
+    }
+}
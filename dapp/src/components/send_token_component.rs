@@ -1,14 +1,41 @@
 use wasm_bindgen::JsCast;
 use web_sys::EventTarget;
 use web_sys::HtmlInputElement;
+use web_sys::HtmlSelectElement;
 use yew::prelude::*;
 
+/// the token message the component sends
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TokenAction {
+    Transfer,
+    Mint,
+    Approve,
+}
+
+impl TokenAction {
+    const ALL: [TokenAction; 3] = [
+        TokenAction::Transfer,
+        TokenAction::Mint,
+        TokenAction::Approve,
+    ];
+
+    fn label(&self) -> &'static str {
+        match self {
+            TokenAction::Transfer => "Transfer",
+            TokenAction::Mint => "Mint",
+            TokenAction::Approve => "Approve",
+        }
+    }
+}
+
 pub struct SendTokenComponent {
+    action: TokenAction,
     to_address: String,
     amount: u128,
 }
 
 pub enum Msg {
+    UpdateAction(usize),
     UpdateAddress(String),
     UpdateAmount(String),
     SendTokens,
@@ -16,7 +43,8 @@ pub enum Msg {
 
 #[derive(Properties, Clone, PartialEq)]
 pub struct Props {
-    pub onsend: Callback<(String, u128)>,
+    /// emits the action, the recipient (or spender) and the amount
+    pub onsend: Callback<(TokenAction, String, u128)>,
 }
 
 impl Component for SendTokenComponent {
@@ -25,6 +53,7 @@ impl Component for SendTokenComponent {
 
     fn create(_: &Context<Self>) -> Self {
         SendTokenComponent {
+            action: TokenAction::Transfer,
             to_address: "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY".to_string(),
             amount: 1,
         }
@@ -32,6 +61,11 @@ impl Component for SendTokenComponent {
 
     fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
         match msg {
+            Msg::UpdateAction(i) => {
+                if let Some(action) = TokenAction::ALL.get(i) {
+                    self.action = *action;
+                }
+            }
             Msg::UpdateAddress(addr) => {
                 self.to_address = addr;
             }
@@ -43,13 +77,23 @@ impl Component for SendTokenComponent {
             Msg::SendTokens => {
                 ctx.props()
                     .onsend
-                    .emit((self.to_address.clone(), self.amount));
+                    .emit((self.action, self.to_address.clone(), self.amount));
             }
         }
         true
     }
 
     fn view(&self, ctx: &Context<Self>) -> Html {
+        let update_action = ctx.link().callback(|e: Event| {
+            let target: EventTarget = e
+                .target()
+                .expect("Event should have a target when dispatched");
+            let index = target
+                .unchecked_into::<HtmlSelectElement>()
+                .selected_index();
+            Msg::UpdateAction(index as usize)
+        });
+
         let update_address = ctx.link().callback(|e: Event| {
             let target: EventTarget = e
                 .target()
@@ -70,6 +114,13 @@ impl Component for SendTokenComponent {
 
         html! {
             <div>
+                <div>
+                    <select onchange={update_action}>
+                        { for TokenAction::ALL.iter().map(|action| html! {
+                            <option selected={*action == self.action}>{action.label()}</option>
+                        }) }
+                    </select>
+                </div>
                 <div>
                     <input
                         value={self.to_address.clone()}
@@ -86,7 +137,7 @@ impl Component for SendTokenComponent {
                     />
                 </div>
                 <div>
-                    <button onclick={send_tokens}>{"Send Tokens"}</button>
+                    <button onclick={send_tokens}>{format!("Preview {}", self.action.label())}</button>
                 </div>
             </div>
         }
@@ -0,0 +1,201 @@
+use yew::prelude::*;
+
+use crate::services::psp22::TokenEvent;
+use crate::services::simulation::Simulation;
+
+/// shows the outcome of a dry-run: the return value, the emitted events and the predicted balance changes
+pub struct SimulationComponent;
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct Props {
+    pub simulation: Simulation,
+}
+
+impl Component for SimulationComponent {
+    type Message = ();
+    type Properties = Props;
+
+    fn create(_: &Context<Self>) -> Self {
+        SimulationComponent
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let simulation = &ctx.props().simulation;
+
+        let result_html = match &simulation.result {
+            Ok(()) => html!(<div class="mb"><small>{"Dry-run succeeded"}</small></div>),
+            Err(err) => {
+                html!(<div class="mb error">{"Dry-run failed, submission blocked: "}{err}</div>)
+            }
+        };
+
+        let events_html = if simulation.events.is_empty() {
+            html!(<></>)
+        } else {
+            html! {
+                <div class="mb">
+                    <div>{"Emitted events:"}</div>
+                    { for simulation.events.iter().map(|event| html!(<div><small>{event_label(event)}</small></div>)) }
+                </div>
+            }
+        };
+
+        let fee_html = match &simulation.transfer_fee {
+            Some(fee) => html! {
+                <table class="mb">
+                    <tr><td>{"Amount"}</td><td>{fee.amount}</td></tr>
+                    <tr><td>{"Transfer fee to "}<small>{fee.treasury.to_string()}</small></td><td>{fee.fee}</td></tr>
+                    <tr><td><b>{"Total debited"}</b></td><td><b>{fee.total()}</b></td></tr>
+                </table>
+            },
+            None => html!(<></>),
+        };
+
+        let balances_html = if simulation.balance_changes.is_empty() {
+            html!(<></>)
+        } else {
+            html! {
+                <table class="mb">
+                    <tr><th>{"Account"}</th><th>{"Balance before"}</th><th>{"Balance after"}</th></tr>
+                    { for simulation.balance_changes.iter().map(|change| html! {
+                        <tr>
+                            <td><small>{change.account.to_string()}</small></td>
+                            <td>{change.before}</td>
+                            <td>{change.after}</td>
+                        </tr>
+                    }) }
+                </table>
+            }
+        };
+
+        let allowances_html = if simulation.allowance_changes.is_empty() {
+            html!(<></>)
+        } else {
+            html! {
+                <table class="mb">
+                    <tr><th>{"Owner"}</th><th>{"Spender"}</th><th>{"Allowance before"}</th><th>{"Allowance after"}</th></tr>
+                    { for simulation.allowance_changes.iter().map(|change| html! {
+                        <tr>
+                            <td><small>{change.owner.to_string()}</small></td>
+                            <td><small>{change.spender.to_string()}</small></td>
+                            <td>{change.before}</td>
+                            <td>{change.after}</td>
+                        </tr>
+                    }) }
+                </table>
+            }
+        };
+
+        html! {
+            <div class="mb">
+                <div class="mb"><b>{"Preview: "}{simulation.call.to_string()}</b></div>
+                {result_html}
+                {events_html}
+                {fee_html}
+                {balances_html}
+                {allowances_html}
+            </div>
+        }
+    }
+}
+
+fn event_label(event: &TokenEvent) -> String {
+    let account = |account: &Option<_>| match account {
+        Some(account) => format!("{account}"),
+        None => "-".to_string(),
+    };
+    match event {
+        TokenEvent::Transfer { from, to, value } => {
+            format!("Transfer {value} from {} to {}", account(from), account(to))
+        }
+        TokenEvent::Approval {
+            owner,
+            spender,
+            value,
+        } => format!("Approval {value} from {owner} to {spender}"),
+        TokenEvent::VestingCreated {
+            beneficiary,
+            schedule,
+        } => format!("VestingCreated {} for {beneficiary}", schedule.total),
+        TokenEvent::Released {
+            beneficiary,
+            amount,
+        } => format!("Released {amount} for {beneficiary}"),
+        TokenEvent::DelegateChanged {
+            delegator,
+            from_delegate,
+            to_delegate,
+        } => format!(
+            "DelegateChanged of {delegator} from {} to {to_delegate}",
+            account(from_delegate)
+        ),
+        TokenEvent::DelegateVotesChanged {
+            delegate,
+            previous_votes,
+            new_votes,
+        } => format!("DelegateVotesChanged of {delegate} from {previous_votes} to {new_votes}"),
+        TokenEvent::AccountFrozen { account } => format!("AccountFrozen {account}"),
+        TokenEvent::AccountUnfrozen { account } => format!("AccountUnfrozen {account}"),
+        TokenEvent::Rescued { token, to, amount } => match token {
+            Some(token) => format!("Rescued {amount} of token {token} to {to}"),
+            None => format!("Rescued {amount} native to {to}"),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::psp22::TokenCall;
+    use crate::services::simulation::BalanceChange;
+    use subxt::utils::AccountId32;
+
+    #[wasm_bindgen_test::wasm_bindgen_test]
+    async fn shows_blocked_call() {
+        let rendered = yew::LocalServerRenderer::<SimulationComponent>::with_props(Props {
+            simulation: Simulation {
+                call: TokenCall::Transfer {
+                    to: AccountId32([2; 32]),
+                    value: 1000,
+                },
+                result: Err("PSP22Error::InsufficientBalance".to_string()),
+                events: Vec::new(),
+                balance_changes: Vec::new(),
+                allowance_changes: Vec::new(),
+                transfer_fee: None,
+            },
+        })
+        .render()
+        .await;
+
+        assert!(rendered.contains("submission blocked"));
+        assert!(rendered.contains("InsufficientBalance"));
+    }
+
+    #[wasm_bindgen_test::wasm_bindgen_test]
+    async fn shows_balance_changes() {
+        let bob = AccountId32([2; 32]);
+        let rendered = yew::LocalServerRenderer::<SimulationComponent>::with_props(Props {
+            simulation: Simulation {
+                call: TokenCall::Transfer {
+                    to: bob.clone(),
+                    value: 10,
+                },
+                result: Ok(()),
+                events: Vec::new(),
+                balance_changes: vec![BalanceChange {
+                    account: bob.clone(),
+                    before: 32,
+                    after: 42,
+                }],
+                allowance_changes: Vec::new(),
+                transfer_fee: None,
+            },
+        })
+        .render()
+        .await;
+
+        assert!(rendered.contains(&bob.to_string()));
+        assert!(rendered.contains("42"));
+    }
+}
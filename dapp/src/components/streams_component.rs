@@ -0,0 +1,110 @@
+use subxt::utils::AccountId32;
+use yew::prelude::*;
+
+use crate::services::streams::{Stream, StreamCall};
+
+/// the active streams of an account with their balances at `now`
+pub struct StreamsComponent;
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct Props {
+    /// `None` while loading
+    pub streams: Option<Vec<(u32, Stream)>>,
+    /// the selected account, it withdraws the streams it receives and cancels the ones it sends
+    pub account: AccountId32,
+    /// current timestamp in milliseconds, the balances tick with it
+    pub now: u64,
+    pub oncall: Callback<StreamCall>,
+}
+
+impl Component for StreamsComponent {
+    type Message = ();
+    type Properties = Props;
+
+    fn create(_: &Context<Self>) -> Self {
+        StreamsComponent
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let props = ctx.props();
+        let Some(streams) = &props.streams else {
+            return html!(<div class="mb loading">{"Loading streams..."}</div>);
+        };
+        if streams.is_empty() {
+            return html!(<div class="mb">{"This account has no active streams."}</div>);
+        }
+
+        html! {
+            <table class="mb">
+                <tr>
+                    <th>{"Stream"}</th>
+                    <th>{"Counterparty"}</th>
+                    <th>{"Streamed"}</th>
+                    <th>{"Withdrawable"}</th>
+                    <th>{"Remaining"}</th>
+                    <th></th>
+                </tr>
+                { for streams.iter().map(|(id, stream)| {
+                    let id = *id;
+                    let receiving = stream.recipient == props.account;
+                    let (label, counterparty) = if receiving {
+                        ("from", &stream.sender)
+                    } else {
+                        ("to", &stream.recipient)
+                    };
+                    let (call, action) = if receiving {
+                        (StreamCall::Withdraw(id), "Withdraw")
+                    } else {
+                        (StreamCall::Cancel(id), "Cancel")
+                    };
+                    let withdrawable = stream.withdrawable_at(props.now);
+                    let onclick = props.oncall.reform(move |_: MouseEvent| call.clone());
+                    html! {
+                        <tr>
+                            <td>{format!("#{id}")}</td>
+                            <td>{label}{" "}<small>{counterparty.to_string()}</small></td>
+                            <td>{format!("{} / {}", stream.streamed_at(props.now), stream.deposit)}</td>
+                            <td>{withdrawable}</td>
+                            <td>{stream.refundable_at(props.now)}</td>
+                            <td>
+                                <button {onclick} disabled={receiving && withdrawable == 0}>{action}</button>
+                            </td>
+                        </tr>
+                    }
+                }) }
+            </table>
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[wasm_bindgen_test::wasm_bindgen_test]
+    async fn shows_the_balances_at_now() {
+        let recipient = AccountId32([2; 32]);
+        let rendered = yew::LocalServerRenderer::<StreamsComponent>::with_props(Props {
+            streams: Some(vec![(
+                7,
+                Stream {
+                    sender: AccountId32([1; 32]),
+                    recipient: recipient.clone(),
+                    deposit: 1_000,
+                    withdrawn: 0,
+                    start: 0,
+                    stop: 1_000,
+                },
+            )]),
+            account: recipient,
+            now: 250,
+            oncall: Callback::default(),
+        })
+        .render()
+        .await;
+
+        assert!(rendered.contains("#7"));
+        assert!(rendered.contains("250 / 1000"));
+        assert!(rendered.contains("Withdraw"));
+    }
+}
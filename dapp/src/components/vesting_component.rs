@@ -0,0 +1,92 @@
+use wasm_bindgen::JsValue;
+use yew::prelude::*;
+
+use crate::services::vesting::VestingSchedule;
+
+/// shows the vesting schedule of the account with its vested and unvested amounts over time
+pub struct VestingComponent;
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct Props {
+    pub schedule: Option<VestingSchedule>,
+    /// current timestamp in milliseconds
+    pub now: u64,
+    pub onrelease: Callback<()>,
+}
+
+impl Component for VestingComponent {
+    type Message = ();
+    type Properties = Props;
+
+    fn create(_: &Context<Self>) -> Self {
+        VestingComponent
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let props = ctx.props();
+        let Some(schedule) = &props.schedule else {
+            return html!(<div class="mb">{"No vesting schedule for this account."}</div>);
+        };
+        let releasable = schedule.releasable_at(props.now);
+        let release = props.onrelease.reform(|_: MouseEvent| ());
+
+        html! {
+            <div class="mb">
+                <div class="mb"><b>{"Vesting"}</b></div>
+                <table class="mb">
+                    <tr><td>{"Total"}</td><td>{schedule.total}</td></tr>
+                    <tr><td>{"Vested now"}</td><td>{schedule.vested_at(props.now)}</td></tr>
+                    <tr><td>{"Released"}</td><td>{schedule.released}</td></tr>
+                    <tr><td>{"Releasable"}</td><td>{releasable}</td></tr>
+                    <tr><td>{"Locked"}</td><td>{schedule.total - schedule.released}</td></tr>
+                </table>
+                <table class="mb">
+                    <tr><th>{"Date"}</th><th>{"Vested"}</th><th>{"Unvested"}</th><th></th></tr>
+                    { for schedule.timeline().iter().map(|point| {
+                        let percent = point.vested.saturating_mul(100) / schedule.total.max(1);
+                        html! {
+                            <tr>
+                                <td><small>{format_timestamp(point.timestamp)}</small></td>
+                                <td>{point.vested}</td>
+                                <td>{point.unvested}</td>
+                                <td><div class="bar" style={format!("width: {percent}%")}></div></td>
+                            </tr>
+                        }
+                    }) }
+                </table>
+                <button onclick={release} disabled={releasable == 0}>{"Release vested tokens"}</button>
+            </div>
+        }
+    }
+}
+
+fn format_timestamp(timestamp: u64) -> String {
+    js_sys::Date::new(&JsValue::from_f64(timestamp as f64))
+        .to_iso_string()
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[wasm_bindgen_test::wasm_bindgen_test]
+    async fn shows_releasable_amount() {
+        let rendered = yew::LocalServerRenderer::<VestingComponent>::with_props(Props {
+            schedule: Some(VestingSchedule {
+                total: 100,
+                released: 10,
+                start: 0,
+                cliff: 100,
+                duration: 1000,
+            }),
+            now: 430,
+            onrelease: Callback::default(),
+        })
+        .render()
+        .await;
+
+        assert!(rendered.contains("Releasable"));
+        assert!(rendered.contains("33"));
+    }
+}
@@ -0,0 +1,75 @@
+use yew::prelude::*;
+
+use crate::services::watchlist::WatchedToken;
+
+/// the watched tokens, selecting one switches the token page to its contract
+pub struct WatchlistComponent;
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct Props {
+    pub tokens: Vec<WatchedToken>,
+    /// address of the current contract
+    pub selected: String,
+    /// emits the address of the selected token
+    pub onselect: Callback<String>,
+    /// emits the address of the token to remove
+    pub onremove: Callback<String>,
+}
+
+impl Component for WatchlistComponent {
+    type Message = ();
+    type Properties = Props;
+
+    fn create(_: &Context<Self>) -> Self {
+        WatchlistComponent
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let props = ctx.props();
+        if props.tokens.is_empty() {
+            return html!(<></>);
+        }
+
+        html! {
+            <div class="mb">
+                <div class="mb"><b>{"Watchlist"}</b></div>
+                { for props.tokens.iter().map(|token| {
+                    let (select_address, remove_address) = (token.address.clone(), token.address.clone());
+                    let onselect = props.onselect.reform(move |_: MouseEvent| select_address.clone());
+                    let onremove = props.onremove.reform(move |_: MouseEvent| remove_address.clone());
+                    html! {
+                        <div class="mb">
+                            <button onclick={onselect} disabled={token.address == props.selected}>
+                                {&token.label}<br/>
+                                <small>{&token.address}</small>
+                            </button>
+                            <button onclick={onremove}>{"Remove"}</button>
+                        </div>
+                    }
+                }) }
+            </div>
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[wasm_bindgen_test::wasm_bindgen_test]
+    async fn lists_watched_tokens() {
+        let rendered = yew::LocalServerRenderer::<WatchlistComponent>::with_props(Props {
+            tokens: vec![WatchedToken {
+                address: "5FbxgE9CZgib7p4oWi34Tx5vqLHsXKNGEWnfMn6pMT7VzwTx".into(),
+                label: "Brand (BRD)".into(),
+            }],
+            selected: String::new(),
+            onselect: Callback::default(),
+            onremove: Callback::default(),
+        })
+        .render()
+        .await;
+
+        assert!(rendered.contains("Brand (BRD)"));
+    }
+}
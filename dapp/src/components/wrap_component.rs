@@ -0,0 +1,104 @@
+use wasm_bindgen::JsCast;
+use web_sys::EventTarget;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use crate::services::wrapped_native::{WrapCall, WrapState};
+
+/// native and wrapped balance of an account with buttons to convert between them
+pub struct WrapComponent {
+    amount: u128,
+}
+
+pub enum Msg {
+    UpdateAmount(String),
+    Wrap,
+    Unwrap,
+}
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct Props {
+    /// `None` while loading
+    pub state: Option<WrapState>,
+    pub oncall: Callback<WrapCall>,
+}
+
+impl Component for WrapComponent {
+    type Message = Msg;
+    type Properties = Props;
+
+    fn create(_: &Context<Self>) -> Self {
+        WrapComponent { amount: 1 }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::UpdateAmount(amt_str) => {
+                if let Ok(amt) = amt_str.parse::<u128>() {
+                    self.amount = amt;
+                }
+            }
+            Msg::Wrap => ctx.props().oncall.emit(WrapCall::Wrap(self.amount)),
+            Msg::Unwrap => ctx.props().oncall.emit(WrapCall::Unwrap(self.amount)),
+        }
+        true
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let Some(state) = &ctx.props().state else {
+            return html!(<div class="mb loading">{"Loading balances..."}</div>);
+        };
+
+        let update_amount = ctx.link().callback(|e: Event| {
+            let target: EventTarget = e
+                .target()
+                .expect("Event should have a target when dispatched");
+            let value = target.unchecked_into::<HtmlInputElement>().value();
+            Msg::UpdateAmount(value)
+        });
+
+        html! {
+            <div class="mb">
+                <table class="mb">
+                    <tr><td>{"Native"}</td><td>{state.native}</td></tr>
+                    <tr><td>{"Wrapped"}</td><td>{state.wrapped}</td></tr>
+                </table>
+                <div>
+                    <input
+                        type="number"
+                        value={self.amount.to_string()}
+                        onchange={update_amount}
+                    />
+                </div>
+                <button onclick={ctx.link().callback(|_| Msg::Wrap)} disabled={self.amount > state.native}>
+                    {"Wrap"}
+                </button>
+                <button onclick={ctx.link().callback(|_| Msg::Unwrap)} disabled={self.amount > state.wrapped}>
+                    {"Unwrap"}
+                </button>
+            </div>
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[wasm_bindgen_test::wasm_bindgen_test]
+    async fn shows_both_balances() {
+        let rendered = yew::LocalServerRenderer::<WrapComponent>::with_props(Props {
+            state: Some(WrapState {
+                native: 5_000,
+                wrapped: 700,
+            }),
+            oncall: Callback::default(),
+        })
+        .render()
+        .await;
+
+        assert!(rendered.contains("5000"));
+        assert!(rendered.contains("700"));
+        assert!(rendered.contains("Unwrap"));
+    }
+}
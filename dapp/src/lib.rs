@@ -2,8 +2,15 @@ use routes::signing::SigningExamplesComponent;
 use yew::prelude::*;
 use yew_router::prelude::*;
 
+use routes::airdrop::AirdropComponent;
+use routes::compliance::ComplianceComponent;
+use routes::escrow::EscrowPageComponent;
+use routes::factory::FactoryComponent;
 use routes::fetching::FetchingExamplesComponent;
+use routes::streams::StreamsPageComponent;
+use routes::swap::SwapComponent;
 use routes::token::TokenComponent;
+use routes::wrap::WrapPageComponent;
 
 mod components;
 mod routes;
@@ -17,6 +24,20 @@ pub enum Route {
     Signing,
     #[at("/token")]
     Token,
+    #[at("/compliance")]
+    Compliance,
+    #[at("/airdrop")]
+    Airdrop,
+    #[at("/factory")]
+    Factory,
+    #[at("/swap")]
+    Swap,
+    #[at("/streams")]
+    Streams,
+    #[at("/wrap")]
+    Wrap,
+    #[at("/escrow")]
+    Escrow,
     #[not_found]
     #[at("/")]
     Home,
@@ -49,6 +70,13 @@ fn switch(routes: Route) -> Html {
         }
         Route::Signing => html! { <SigningExamplesComponent/> },
         Route::Token => html! { <TokenComponent/> },
+        Route::Compliance => html! { <ComplianceComponent/> },
+        Route::Airdrop => html! { <AirdropComponent/> },
+        Route::Factory => html! { <FactoryComponent/> },
+        Route::Swap => html! { <SwapComponent/> },
+        Route::Streams => html! { <StreamsPageComponent/> },
+        Route::Wrap => html! { <WrapPageComponent/> },
+        Route::Escrow => html! { <EscrowPageComponent/> },
         Route::Home => {
             html! {
             <div>
@@ -56,6 +84,13 @@ fn switch(routes: Route) -> Html {
                 <Link<Route> to={Route::Signing}> <button>{"Signing Examples"} </button></Link<Route>>
                 <Link<Route> to={Route::Fetching}> <button>{"Fetching and Subscribing Examples"}</button></Link<Route>>
                 <Link<Route> to={Route::Token}> <button>{"Token Examples"} </button></Link<Route>>
+                <Link<Route> to={Route::Compliance}> <button>{"Compliance"} </button></Link<Route>>
+                <Link<Route> to={Route::Airdrop}> <button>{"Airdrop"} </button></Link<Route>>
+                <Link<Route> to={Route::Factory}> <button>{"Token Factory"} </button></Link<Route>>
+                <Link<Route> to={Route::Swap}> <button>{"Swap"} </button></Link<Route>>
+                <Link<Route> to={Route::Streams}> <button>{"Streams"} </button></Link<Route>>
+                <Link<Route> to={Route::Wrap}> <button>{"Wrap Native"} </button></Link<Route>>
+                <Link<Route> to={Route::Escrow}> <button>{"Escrow"} </button></Link<Route>>
             </div> }
         }
     }
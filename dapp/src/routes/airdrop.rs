@@ -0,0 +1,278 @@
+use anyhow::anyhow;
+use futures::FutureExt;
+use web_sys::{HtmlInputElement, HtmlTextAreaElement};
+use yew::prelude::*;
+use yew_router::prelude::Link;
+
+use crate::components::FeeConfirmationComponent;
+use crate::services::airdrop::{parse_claim, AirdropClaim};
+use crate::services::fees::FeeEstimate;
+use crate::services::{
+    get_accounts, Account, PendingCall, PreparedCall, PreparedExtrinsic, TokenService,
+};
+use crate::Route;
+
+/// claims the tokens of the selected account from an airdrop with the proofs of `airdrop_tool`
+pub struct AirdropComponent {
+    airdrop: String,
+    /// output of `airdrop_tool`
+    claims_json: String,
+    stage: AirdropStage,
+    token_service: Option<TokenService>,
+    account: Option<Account>,
+    /// `Err` with the reason the claims JSON can't be used
+    claim: Result<Option<AirdropClaim>, String>,
+    /// `None` while loading
+    claimed: Option<bool>,
+    pending: PendingCall<()>,
+}
+
+pub enum AirdropStage {
+    Error(String),
+    CreatingTokenService,
+    EnterAccount,
+    RequestingAccounts,
+    SelectAccount(Vec<Account>),
+    Ready,
+}
+
+pub enum Message {
+    Error(anyhow::Error),
+    TokenServiceCreated(TokenService),
+    ChangeAirdrop(String),
+    ChangeClaims(String),
+    RequestAccounts,
+    ReceivedAccounts(Vec<Account>),
+    SelectAccount(usize),
+    RequestClaimed,
+    ReceivedClaimed(bool),
+    Claim,
+    ClaimPrepared(PreparedExtrinsic, FeeEstimate),
+    ConfirmClaim,
+    CancelClaim,
+}
+
+impl AirdropComponent {
+    /// looks up the claim of the selected account in the pasted JSON
+    fn update_claim(&mut self) {
+        self.claim = match &self.account {
+            Some(account) if !self.claims_json.trim().is_empty() => {
+                parse_claim(&self.claims_json, &account.address).map_err(|err| err.to_string())
+            }
+            _ => Ok(None),
+        };
+    }
+}
+
+impl Component for AirdropComponent {
+    type Message = Message;
+
+    type Properties = ();
+
+    fn create(ctx: &Context<Self>) -> Self {
+        ctx.link()
+            .send_future(TokenService::new().map(|res| match res {
+                Ok(service) => Message::TokenServiceCreated(service),
+                Err(err) => Message::Error(anyhow!("Failed to create TokenService: {}", err)),
+            }));
+        AirdropComponent {
+            airdrop: String::new(),
+            claims_json: String::new(),
+            stage: AirdropStage::CreatingTokenService,
+            token_service: None,
+            account: None,
+            claim: Ok(None),
+            claimed: None,
+            pending: PendingCall::default(),
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Message::Error(err) => self.stage = AirdropStage::Error(err.to_string()),
+            Message::TokenServiceCreated(service) => {
+                self.token_service = Some(service);
+                self.stage = AirdropStage::EnterAccount;
+            }
+            Message::ChangeAirdrop(airdrop) => {
+                self.airdrop = airdrop;
+                self.claimed = None;
+                ctx.link().send_message(Message::RequestClaimed);
+            }
+            Message::ChangeClaims(json) => {
+                self.claims_json = json;
+                self.update_claim();
+            }
+            Message::RequestAccounts => {
+                self.stage = AirdropStage::RequestingAccounts;
+                ctx.link().send_future(get_accounts().map(
+                    |accounts_or_err| match accounts_or_err {
+                        Ok(accounts) => Message::ReceivedAccounts(accounts),
+                        Err(err) => Message::Error(err),
+                    },
+                ));
+            }
+            Message::ReceivedAccounts(accounts) => {
+                self.stage = AirdropStage::SelectAccount(accounts);
+            }
+            Message::SelectAccount(i) => {
+                if let AirdropStage::SelectAccount(accounts) = &self.stage {
+                    self.account = accounts.get(i).cloned();
+                    self.stage = AirdropStage::Ready;
+                    self.update_claim();
+                    ctx.link().send_message(Message::RequestClaimed);
+                }
+            }
+            Message::RequestClaimed => {
+                let Some(account) = &self.account else {
+                    return false;
+                };
+                if self.airdrop.is_empty() {
+                    return false;
+                }
+                let Some(token_service) = self.token_service.clone() else {
+                    return false;
+                };
+                let (airdrop, address) = (self.airdrop.clone(), account.address.clone());
+                ctx.link().send_future(async move {
+                    match token_service.get_airdrop_claimed(airdrop, address).await {
+                        Ok(claimed) => Message::ReceivedClaimed(claimed),
+                        Err(err) => Message::Error(err),
+                    }
+                });
+            }
+            Message::ReceivedClaimed(claimed) => {
+                self.claimed = Some(claimed);
+            }
+            Message::Claim => {
+                let (Some(account), Ok(Some(claim))) = (self.account.clone(), self.claim.clone())
+                else {
+                    return false;
+                };
+                let Some(token_service) = self.token_service.clone() else {
+                    return false;
+                };
+                let airdrop = self.airdrop.clone();
+                ctx.link().send_future(async move {
+                    match token_service
+                        .prepare_airdrop_claim(airdrop, account.source, account.address, &claim)
+                        .await
+                    {
+                        Ok((prepared, fees)) => Message::ClaimPrepared(prepared, fees),
+                        Err(err) => Message::Error(err),
+                    }
+                });
+            }
+            Message::ClaimPrepared(prepared, fees) => {
+                self.pending.replace(PreparedCall {
+                    call: (),
+                    prepared,
+                    fees,
+                });
+            }
+            Message::ConfirmClaim => {
+                return self.pending.confirm(
+                    ctx,
+                    self.token_service.as_ref(),
+                    Message::RequestClaimed,
+                    Message::Error,
+                );
+            }
+            Message::CancelClaim => self.pending.cancel(),
+        }
+        true
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let on_airdrop_input = ctx.link().callback(move |event: InputEvent| {
+            let input_element = event.target_dyn_into::<HtmlInputElement>().unwrap();
+            Message::ChangeAirdrop(input_element.value())
+        });
+        let on_claims_input = ctx.link().callback(move |event: InputEvent| {
+            let input_element = event.target_dyn_into::<HtmlTextAreaElement>().unwrap();
+            Message::ChangeClaims(input_element.value())
+        });
+
+        let stage_html: Html = match &self.stage {
+            AirdropStage::Error(error_message) => {
+                html!(<div class="error"> {"Error: "} {error_message} </div>)
+            }
+            AirdropStage::CreatingTokenService => {
+                html!(<div>{"Creating Online Client..."}</div>)
+            }
+            AirdropStage::EnterAccount => {
+                let get_accounts_click = ctx.link().callback(|_| Message::RequestAccounts);
+                html!(
+                    <div>
+                        <button onclick={get_accounts_click}> {"=> Select an Account"} </button>
+                    </div>
+                )
+            }
+            AirdropStage::RequestingAccounts => {
+                html!(<div>{"Querying extensions for accounts..."}</div>)
+            }
+            AirdropStage::SelectAccount(accounts) => {
+                if accounts.is_empty() {
+                    html!(<div>{"No Web3 extension accounts found. Install Talisman or the Polkadot.js extension and add an account."}</div>)
+                } else {
+                    html!(
+                        <>
+                            <div class="mb"><b>{"Select an account you want to use for signing:"}</b></div>
+                            { for accounts.iter().enumerate().map(|(i, account)| {
+                                let select_account = ctx.link().callback(move |_| Message::SelectAccount(i));
+                                html! {
+                                    <button onclick={select_account}>
+                                        {&account.source} {" | "} {&account.name}<br/>
+                                        <small>{&account.address}</small>
+                                    </button>
+                                }
+                            }) }
+                        </>
+                    )
+                }
+            }
+            AirdropStage::Ready => {
+                let claim_html = match (&self.claim, self.claimed) {
+                    (Err(err), _) => html!(<div class="mb error">{"Invalid claims: "}{err}</div>),
+                    (Ok(None), _) => {
+                        html!(<div class="mb">{"The airdrop has no claim for this account."}</div>)
+                    }
+                    (Ok(Some(claim)), Some(true)) => {
+                        html!(<div class="mb">{format!("The {} tokens of this account are claimed.", claim.amount)}</div>)
+                    }
+                    (Ok(Some(claim)), _) => html! {
+                        <>
+                            <div class="mb">{format!("This account can claim {} tokens.", claim.amount)}</div>
+                            <button class="mb" onclick={ctx.link().callback(|_| Message::Claim)}>{"Preview claim"}</button>
+                        </>
+                    },
+                };
+                html!(
+                    <>
+                        {claim_html}
+                        if let Some(pending) = self.pending.get() {
+                            <FeeConfirmationComponent
+                                title="Claim airdrop"
+                                fees={pending.fees.clone()}
+                                onconfirm={ctx.link().callback(|_| Message::ConfirmClaim)}
+                                oncancel={ctx.link().callback(|_| Message::CancelClaim)}
+                            />
+                        }
+                    </>
+                )
+            }
+        };
+
+        html! {
+            <div>
+                <Link<Route> to={Route::Home}> <button>{"<= Back"}</button></Link<Route>>
+                <h1>{"Airdrop"}</h1>
+                <div class="mb"><b>{"Enter Airdrop Contract:"}</b></div>
+                <input oninput={on_airdrop_input} class="mb" value={AttrValue::from(self.airdrop.clone())}/>
+                <div class="mb"><b>{"Paste the claims of airdrop_tool:"}</b></div>
+                <textarea class="mb" oninput={on_claims_input} value={self.claims_json.clone()} placeholder="{\"root\": ...}"/>
+                {stage_html}
+            </div>
+        }
+    }
+}
@@ -0,0 +1,390 @@
+use anyhow::anyhow;
+use futures::FutureExt;
+use std::str::FromStr;
+use subxt::utils::AccountId32;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+use yew_router::prelude::Link;
+
+use crate::components::{
+    FeeConfirmationComponent, FrozenAccountsComponent, ProposalsComponent, SimulationComponent,
+};
+use crate::services::multisig::{MultisigCall, MultisigState};
+use crate::services::psp22::TokenCall;
+use crate::services::simulation::Simulation;
+use crate::services::{
+    get_accounts, Account, PendingCall, PreparedCall, PreviewedCall, TokenService,
+};
+use crate::Route;
+
+/// lets the owner of the token freeze and unfreeze accounts.
+///
+/// If the owner is a multisig, its owners propose freezes and unfreezes and approve and execute the pending
+/// proposals.
+pub struct ComplianceComponent {
+    contract: String,
+    stage: ComplianceStage,
+    token_service: Option<TokenService>,
+    account: Option<Account>,
+    owner: Option<AccountId32>,
+    /// `None` unless the owner is a multisig
+    multisig: Option<MultisigState>,
+    /// `None` while loading
+    frozen_accounts: Option<Vec<AccountId32>>,
+    /// dry-run of a call which would fail, shown instead of the fee confirmation
+    blocked_simulation: Option<Simulation>,
+    pending: PendingCall<OwnerCall>,
+}
+
+pub enum ComplianceStage {
+    Error(String),
+    CreatingTokenService,
+    EnterAccount,
+    RequestingAccounts,
+    SelectAccount(Vec<Account>),
+    Ready,
+}
+
+pub enum Message {
+    Error(anyhow::Error),
+    TokenServiceCreated(TokenService),
+    ChangeContract(String),
+    RequestAccounts,
+    ReceivedAccounts(Vec<Account>),
+    SelectAccount(usize),
+    RequestState,
+    /// frozen accounts and owner of the token
+    ReceivedState(Vec<AccountId32>, Option<AccountId32>),
+    ReceivedMultisigState(Option<MultisigState>),
+    Freeze(String),
+    Unfreeze(AccountId32),
+    MultisigCall(MultisigCall),
+    SimulationBlocked(Simulation),
+    DismissSimulation,
+    CallPrepared(PreparedCall<OwnerCall>),
+    ConfirmCall,
+    CancelCall,
+}
+
+/// a freeze, unfreeze or multisig call waiting for the user to confirm its fees
+pub struct OwnerCall {
+    title: String,
+    /// dry-run of the token call, `None` for multisig calls
+    simulation: Option<Simulation>,
+}
+
+impl ComplianceComponent {
+    /// dry-runs `call` and prepares it for the fee confirmation, unless the dry-run fails
+    fn preview_call(&mut self, ctx: &Context<Self>, call: TokenCall) {
+        let account = self.account.clone().unwrap();
+        let contract = self.contract.clone();
+        let Some(token_service) = self.token_service.clone() else {
+            return;
+        };
+        self.blocked_simulation = None;
+
+        ctx.link().send_future(async move {
+            let title = call.to_string();
+            match token_service
+                .preview_token_call(contract, account.source, account.address, call)
+                .await
+            {
+                Ok(PreviewedCall::Blocked(simulation)) => Message::SimulationBlocked(simulation),
+                Ok(PreviewedCall::Prepared {
+                    simulation,
+                    prepared,
+                    fees,
+                }) => Message::CallPrepared(PreparedCall {
+                    call: OwnerCall {
+                        title,
+                        simulation: Some(simulation),
+                    },
+                    prepared,
+                    fees,
+                }),
+                Err(err) => Message::Error(err),
+            }
+        });
+    }
+
+    /// proposes `call` to the multisig if the owner is one, otherwise previews it
+    fn owner_call(&mut self, ctx: &Context<Self>, call: TokenCall) {
+        let Ok(callee) = AccountId32::from_str(&self.contract) else {
+            ctx.link()
+                .send_message(Message::Error(anyhow!("Invalid contract address: {}", self.contract)));
+            return;
+        };
+        if self.multisig.is_some() {
+            ctx.link()
+                .send_message(Message::MultisigCall(MultisigCall::Propose {
+                    callee,
+                    call,
+                }));
+        } else {
+            self.preview_call(ctx, call);
+        }
+    }
+
+    fn account_id(&self) -> Option<AccountId32> {
+        self.account
+            .as_ref()
+            .and_then(|account| AccountId32::from_str(&account.address).ok())
+    }
+
+    /// the selected account is the owner of the token or an owner of the multisig owning it
+    fn is_owner(&self) -> bool {
+        match (self.account_id(), &self.owner, &self.multisig) {
+            (Some(account), _, Some(multisig)) => multisig.is_owner(&account),
+            (Some(account), Some(owner), None) => &account == owner,
+            _ => false,
+        }
+    }
+}
+
+impl Component for ComplianceComponent {
+    type Message = Message;
+
+    type Properties = ();
+
+    fn create(ctx: &Context<Self>) -> Self {
+        ctx.link()
+            .send_future(TokenService::new().map(|res| match res {
+                Ok(service) => Message::TokenServiceCreated(service),
+                Err(err) => Message::Error(anyhow!("Failed to create TokenService: {}", err)),
+            }));
+        ComplianceComponent {
+            contract: "5FbxgE9CZgib7p4oWi34Tx5vqLHsXKNGEWnfMn6pMT7VzwTx".to_string(),
+            stage: ComplianceStage::CreatingTokenService,
+            token_service: None,
+            account: None,
+            owner: None,
+            multisig: None,
+            frozen_accounts: None,
+            blocked_simulation: None,
+            pending: PendingCall::default(),
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Message::Error(err) => self.stage = ComplianceStage::Error(err.to_string()),
+            Message::TokenServiceCreated(service) => {
+                self.token_service = Some(service);
+                self.stage = ComplianceStage::EnterAccount;
+            }
+            Message::ChangeContract(contract) => {
+                self.contract = contract;
+                self.frozen_accounts = None;
+                ctx.link().send_message(Message::RequestState);
+            }
+            Message::RequestAccounts => {
+                self.stage = ComplianceStage::RequestingAccounts;
+                ctx.link().send_future(get_accounts().map(
+                    |accounts_or_err| match accounts_or_err {
+                        Ok(accounts) => Message::ReceivedAccounts(accounts),
+                        Err(err) => Message::Error(err),
+                    },
+                ));
+            }
+            Message::ReceivedAccounts(accounts) => {
+                self.stage = ComplianceStage::SelectAccount(accounts);
+            }
+            Message::SelectAccount(i) => {
+                if let ComplianceStage::SelectAccount(accounts) = &self.stage {
+                    self.account = accounts.get(i).cloned();
+                    self.stage = ComplianceStage::Ready;
+                    ctx.link().send_message(Message::RequestState);
+                }
+            }
+            Message::RequestState => {
+                let Some(account) = &self.account else {
+                    return false;
+                };
+                let Some(token_service) = self.token_service.clone() else {
+                    return false;
+                };
+                let (contract, address) = (self.contract.clone(), account.address.clone());
+                ctx.link().send_future(async move {
+                    match token_service.get_compliance_state(contract, address).await {
+                        Ok((frozen, owner)) => Message::ReceivedState(frozen, owner),
+                        Err(err) => Message::Error(err),
+                    }
+                });
+            }
+            Message::ReceivedState(frozen, owner) => {
+                self.frozen_accounts = Some(frozen);
+                self.owner = owner.clone();
+                let (Some(owner), Some(account)) = (owner, &self.account) else {
+                    self.multisig = None;
+                    return true;
+                };
+                let Some(token_service) = self.token_service.clone() else {
+                    return false;
+                };
+                let address = account.address.clone();
+                ctx.link().send_future(async move {
+                    // the query fails unless the owner is a multisig
+                    let state = token_service
+                        .get_multisig_state(owner.to_string(), address)
+                        .await
+                        .ok();
+                    Message::ReceivedMultisigState(state)
+                });
+            }
+            Message::ReceivedMultisigState(state) => {
+                self.multisig = state;
+            }
+            Message::Freeze(account) => {
+                let Ok(account) = AccountId32::from_str(&account) else {
+                    ctx.link()
+                        .send_message(Message::Error(anyhow!("Invalid address: {account}")));
+                    return false;
+                };
+                self.owner_call(ctx, TokenCall::Freeze { account });
+            }
+            Message::Unfreeze(account) => {
+                self.owner_call(ctx, TokenCall::Unfreeze { account });
+            }
+            Message::MultisigCall(call) => {
+                let (Some(account), Some(multisig)) = (self.account.clone(), &self.owner) else {
+                    return false;
+                };
+                let Some(token_service) = self.token_service.clone() else {
+                    return false;
+                };
+                let multisig = multisig.to_string();
+                self.blocked_simulation = None;
+                ctx.link().send_future(async move {
+                    match token_service
+                        .prepare_multisig_call(multisig, account.source, account.address, &call)
+                        .await
+                    {
+                        Ok((prepared, fees)) => Message::CallPrepared(PreparedCall {
+                            call: OwnerCall {
+                                title: call.to_string(),
+                                simulation: None,
+                            },
+                            prepared,
+                            fees,
+                        }),
+                        Err(err) => Message::Error(err),
+                    }
+                });
+            }
+            Message::SimulationBlocked(simulation) => {
+                self.blocked_simulation = Some(simulation);
+            }
+            Message::DismissSimulation => {
+                self.blocked_simulation = None;
+            }
+            Message::CallPrepared(pending) => self.pending.replace(pending),
+            Message::ConfirmCall => {
+                return self.pending.confirm(
+                    ctx,
+                    self.token_service.as_ref(),
+                    Message::RequestState,
+                    Message::Error,
+                );
+            }
+            Message::CancelCall => self.pending.cancel(),
+        }
+        true
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let on_input = ctx.link().callback(move |event: InputEvent| {
+            let input_element = event.target_dyn_into::<HtmlInputElement>().unwrap();
+            Message::ChangeContract(input_element.value())
+        });
+
+        let stage_html: Html = match &self.stage {
+            ComplianceStage::Error(error_message) => {
+                html!(<div class="error"> {"Error: "} {error_message} </div>)
+            }
+            ComplianceStage::CreatingTokenService => {
+                html!(<div>{"Creating Online Client..."}</div>)
+            }
+            ComplianceStage::EnterAccount => {
+                let get_accounts_click = ctx.link().callback(|_| Message::RequestAccounts);
+                html!(
+                    <div>
+                        <button onclick={get_accounts_click}> {"=> Select an Account"} </button>
+                    </div>
+                )
+            }
+            ComplianceStage::RequestingAccounts => {
+                html!(<div>{"Querying extensions for accounts..."}</div>)
+            }
+            ComplianceStage::SelectAccount(accounts) => {
+                if accounts.is_empty() {
+                    html!(<div>{"No Web3 extension accounts found. Install Talisman or the Polkadot.js extension and add an account."}</div>)
+                } else {
+                    html!(
+                        <>
+                            <div class="mb"><b>{"Select an account you want to use for signing:"}</b></div>
+                            { for accounts.iter().enumerate().map(|(i, account)| {
+                                let select_account = ctx.link().callback(move |_| Message::SelectAccount(i));
+                                html! {
+                                    <button onclick={select_account}>
+                                        {&account.source} {" | "} {&account.name}<br/>
+                                        <small>{&account.address}</small>
+                                    </button>
+                                }
+                            }) }
+                        </>
+                    )
+                }
+            }
+            ComplianceStage::Ready => {
+                let freeze_callback = ctx.link().callback(Message::Freeze);
+                let unfreeze_callback = ctx.link().callback(Message::Unfreeze);
+                html!(
+                    <>
+                        <div class="mb">
+                            {"Owner: "}
+                            <small>{self.owner.as_ref().map(|owner| owner.to_string()).unwrap_or("none".to_string())}</small>
+                        </div>
+                        if let Some(simulation) = &self.blocked_simulation {
+                            <SimulationComponent simulation={simulation.clone()}/>
+                            <button class="mb" onclick={ctx.link().callback(|_| Message::DismissSimulation)}>{"Dismiss"}</button>
+                        }
+                        if let Some(pending) = self.pending.get() {
+                            if let Some(simulation) = &pending.call.simulation {
+                                <SimulationComponent simulation={simulation.clone()}/>
+                            }
+                            <FeeConfirmationComponent
+                                title={pending.call.title.clone()}
+                                fees={pending.fees.clone()}
+                                onconfirm={ctx.link().callback(|_| Message::ConfirmCall)}
+                                oncancel={ctx.link().callback(|_| Message::CancelCall)}
+                            />
+                        }
+                        <FrozenAccountsComponent
+                            frozen_accounts={self.frozen_accounts.clone()}
+                            is_owner={self.is_owner()}
+                            onfreeze={freeze_callback}
+                            onunfreeze={unfreeze_callback}
+                        />
+                        if let (Some(multisig), Some(account)) = (&self.multisig, self.account_id()) {
+                            <ProposalsComponent
+                                state={multisig.clone()}
+                                {account}
+                                oncall={ctx.link().callback(Message::MultisigCall)}
+                            />
+                        }
+                    </>
+                )
+            }
+        };
+
+        html! {
+            <div>
+                <Link<Route> to={Route::Home}> <button>{"<= Back"}</button></Link<Route>>
+                <h1>{"Compliance"}</h1>
+                <div class="mb"><b>{"Enter Contract:"}</b></div>
+                <input oninput={on_input} class="mb" value={AttrValue::from(self.contract.clone())}/>
+                {stage_html}
+            </div>
+        }
+    }
+}
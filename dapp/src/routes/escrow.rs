@@ -0,0 +1,251 @@
+use anyhow::anyhow;
+use futures::FutureExt;
+use std::str::FromStr;
+use subxt::utils::AccountId32;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+use yew_router::prelude::Link;
+
+use crate::components::{EscrowComponent, FeeConfirmationComponent};
+use crate::services::escrow::{Deal, EscrowCall};
+use crate::services::fees::FeeEstimate;
+use crate::services::{
+    get_accounts, Account, PendingCall, PreparedCall, PreparedExtrinsic, TokenService,
+};
+use crate::Route;
+
+/// the open escrow deals of the selected account
+pub struct EscrowPageComponent {
+    contract: String,
+    stage: EscrowStage,
+    token_service: Option<TokenService>,
+    account: Option<Account>,
+    /// `None` while loading
+    deals: Option<Vec<(u32, Deal)>>,
+    /// timestamp in milliseconds the deals were loaded at
+    now: u64,
+    pending: PendingCall<EscrowCall>,
+}
+
+pub enum EscrowStage {
+    Error(String),
+    CreatingTokenService,
+    EnterAccount,
+    RequestingAccounts,
+    SelectAccount(Vec<Account>),
+    Ready,
+}
+
+pub enum Message {
+    Error(anyhow::Error),
+    TokenServiceCreated(TokenService),
+    ChangeContract(String),
+    RequestAccounts,
+    ReceivedAccounts(Vec<Account>),
+    SelectAccount(usize),
+    RequestDeals,
+    ReceivedDeals(Vec<(u32, Deal)>),
+    Call(EscrowCall),
+    CallPrepared(EscrowCall, PreparedExtrinsic, FeeEstimate),
+    Confirm,
+    Cancel,
+}
+
+impl Component for EscrowPageComponent {
+    type Message = Message;
+
+    type Properties = ();
+
+    fn create(ctx: &Context<Self>) -> Self {
+        ctx.link()
+            .send_future(TokenService::new().map(|res| match res {
+                Ok(service) => Message::TokenServiceCreated(service),
+                Err(err) => Message::Error(anyhow!("Failed to create TokenService: {}", err)),
+            }));
+        EscrowPageComponent {
+            contract: String::new(),
+            stage: EscrowStage::CreatingTokenService,
+            token_service: None,
+            account: None,
+            deals: None,
+            now: js_sys::Date::now() as u64,
+            pending: PendingCall::default(),
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Message::Error(err) => self.stage = EscrowStage::Error(err.to_string()),
+            Message::TokenServiceCreated(service) => {
+                self.token_service = Some(service);
+                self.stage = EscrowStage::EnterAccount;
+            }
+            Message::ChangeContract(contract) => {
+                self.contract = contract;
+                self.deals = None;
+                ctx.link().send_message(Message::RequestDeals);
+            }
+            Message::RequestAccounts => {
+                self.stage = EscrowStage::RequestingAccounts;
+                ctx.link().send_future(get_accounts().map(
+                    |accounts_or_err| match accounts_or_err {
+                        Ok(accounts) => Message::ReceivedAccounts(accounts),
+                        Err(err) => Message::Error(err),
+                    },
+                ));
+            }
+            Message::ReceivedAccounts(accounts) => {
+                self.stage = EscrowStage::SelectAccount(accounts);
+            }
+            Message::SelectAccount(i) => {
+                if let EscrowStage::SelectAccount(accounts) = &self.stage {
+                    self.account = accounts.get(i).cloned();
+                    self.stage = EscrowStage::Ready;
+                    ctx.link().send_message(Message::RequestDeals);
+                }
+            }
+            Message::RequestDeals => {
+                let Some(account) = &self.account else {
+                    return false;
+                };
+                if self.contract.is_empty() {
+                    return false;
+                }
+                let Some(token_service) = self.token_service.clone() else {
+                    return false;
+                };
+                let (contract, address) = (self.contract.clone(), account.address.clone());
+                ctx.link().send_future(async move {
+                    match token_service.get_deals(contract, address).await {
+                        Ok(deals) => Message::ReceivedDeals(deals),
+                        Err(err) => Message::Error(err),
+                    }
+                });
+            }
+            Message::ReceivedDeals(deals) => {
+                self.deals = Some(deals);
+                self.now = js_sys::Date::now() as u64;
+            }
+            Message::Call(call) => {
+                let Some(account) = self.account.clone() else {
+                    return false;
+                };
+                let Some(token_service) = self.token_service.clone() else {
+                    return false;
+                };
+                let contract = self.contract.clone();
+                ctx.link().send_future(async move {
+                    match token_service
+                        .prepare_escrow_call(contract, account.source, account.address, &call)
+                        .await
+                    {
+                        Ok((prepared, fees)) => Message::CallPrepared(call, prepared, fees),
+                        Err(err) => Message::Error(err),
+                    }
+                });
+            }
+            Message::CallPrepared(call, prepared, fees) => {
+                self.pending.replace(PreparedCall {
+                    call,
+                    prepared,
+                    fees,
+                });
+            }
+            Message::Confirm => {
+                return self.pending.confirm(
+                    ctx,
+                    self.token_service.as_ref(),
+                    Message::RequestDeals,
+                    Message::Error,
+                );
+            }
+            Message::Cancel => self.pending.cancel(),
+        }
+        true
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let on_contract_input = ctx.link().callback(move |event: InputEvent| {
+            let input_element = event.target_dyn_into::<HtmlInputElement>().unwrap();
+            Message::ChangeContract(input_element.value())
+        });
+
+        let stage_html: Html = match &self.stage {
+            EscrowStage::Error(error_message) => {
+                html!(<div class="error"> {"Error: "} {error_message} </div>)
+            }
+            EscrowStage::CreatingTokenService => {
+                html!(<div>{"Creating Online Client..."}</div>)
+            }
+            EscrowStage::EnterAccount => {
+                let get_accounts_click = ctx.link().callback(|_| Message::RequestAccounts);
+                html!(
+                    <div>
+                        <button onclick={get_accounts_click}> {"=> Select an Account"} </button>
+                    </div>
+                )
+            }
+            EscrowStage::RequestingAccounts => {
+                html!(<div>{"Querying extensions for accounts..."}</div>)
+            }
+            EscrowStage::SelectAccount(accounts) => {
+                if accounts.is_empty() {
+                    html!(<div>{"No Web3 extension accounts found. Install Talisman or the Polkadot.js extension and add an account."}</div>)
+                } else {
+                    html!(
+                        <>
+                            <div class="mb"><b>{"Select an account you want to use for signing:"}</b></div>
+                            { for accounts.iter().enumerate().map(|(i, account)| {
+                                let select_account = ctx.link().callback(move |_| Message::SelectAccount(i));
+                                html! {
+                                    <button onclick={select_account}>
+                                        {&account.source} {" | "} {&account.name}<br/>
+                                        <small>{&account.address}</small>
+                                    </button>
+                                }
+                            }) }
+                        </>
+                    )
+                }
+            }
+            EscrowStage::Ready => {
+                let account_id = self
+                    .account
+                    .as_ref()
+                    .and_then(|account| AccountId32::from_str(&account.address).ok());
+                match account_id {
+                    Some(_) if self.contract.is_empty() => html!(<></>),
+                    Some(account_id) => html! {
+                        <>
+                            <EscrowComponent
+                                deals={self.deals.clone()}
+                                account={account_id}
+                                now={self.now}
+                                oncall={ctx.link().callback(Message::Call)}
+                            />
+                            if let Some(pending) = self.pending.get() {
+                                <FeeConfirmationComponent
+                                    title={pending.call.to_string()}
+                                    fees={pending.fees.clone()}
+                                    onconfirm={ctx.link().callback(|_| Message::Confirm)}
+                                    oncancel={ctx.link().callback(|_| Message::Cancel)}
+                                />
+                            }
+                        </>
+                    },
+                    None => html!(<div class="error">{"Invalid account address"}</div>),
+                }
+            }
+        };
+
+        html! {
+            <div>
+                <Link<Route> to={Route::Home}> <button>{"<= Back"}</button></Link<Route>>
+                <h1>{"Escrow"}</h1>
+                <div class="mb"><b>{"Enter Escrow Contract:"}</b></div>
+                <input oninput={on_contract_input} class="mb" value={AttrValue::from(self.contract.clone())}/>
+                {stage_html}
+            </div>
+        }
+    }
+}
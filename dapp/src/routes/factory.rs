@@ -0,0 +1,225 @@
+use anyhow::anyhow;
+use futures::FutureExt;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+use yew_router::prelude::Link;
+
+use crate::services::token_factory::TokenInfo;
+use crate::services::watchlist::{load_watchlist, watch, WatchedToken};
+use crate::services::{get_accounts, Account, TokenService};
+use crate::Route;
+
+/// lists the tokens created by a token factory, they can be added to the watchlist of the token page
+pub struct FactoryComponent {
+    factory: String,
+    stage: FactoryStage,
+    token_service: Option<TokenService>,
+    account: Option<Account>,
+    /// `None` while loading
+    tokens: Option<Vec<TokenInfo>>,
+    watchlist: Vec<WatchedToken>,
+}
+
+pub enum FactoryStage {
+    Error(String),
+    CreatingTokenService,
+    EnterAccount,
+    RequestingAccounts,
+    SelectAccount(Vec<Account>),
+    Ready,
+}
+
+pub enum Message {
+    Error(anyhow::Error),
+    TokenServiceCreated(TokenService),
+    ChangeFactory(String),
+    RequestAccounts,
+    ReceivedAccounts(Vec<Account>),
+    SelectAccount(usize),
+    RequestTokens,
+    ReceivedTokens(Vec<TokenInfo>),
+    /// index in the registry
+    Watch(usize),
+}
+
+impl Component for FactoryComponent {
+    type Message = Message;
+
+    type Properties = ();
+
+    fn create(ctx: &Context<Self>) -> Self {
+        ctx.link()
+            .send_future(TokenService::new().map(|res| match res {
+                Ok(service) => Message::TokenServiceCreated(service),
+                Err(err) => Message::Error(anyhow!("Failed to create TokenService: {}", err)),
+            }));
+        FactoryComponent {
+            factory: String::new(),
+            stage: FactoryStage::CreatingTokenService,
+            token_service: None,
+            account: None,
+            tokens: None,
+            watchlist: load_watchlist(),
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Message::Error(err) => self.stage = FactoryStage::Error(err.to_string()),
+            Message::TokenServiceCreated(service) => {
+                self.token_service = Some(service);
+                self.stage = FactoryStage::EnterAccount;
+            }
+            Message::ChangeFactory(factory) => {
+                self.factory = factory;
+                self.tokens = None;
+                ctx.link().send_message(Message::RequestTokens);
+            }
+            Message::RequestAccounts => {
+                self.stage = FactoryStage::RequestingAccounts;
+                ctx.link().send_future(get_accounts().map(
+                    |accounts_or_err| match accounts_or_err {
+                        Ok(accounts) => Message::ReceivedAccounts(accounts),
+                        Err(err) => Message::Error(err),
+                    },
+                ));
+            }
+            Message::ReceivedAccounts(accounts) => {
+                self.stage = FactoryStage::SelectAccount(accounts);
+            }
+            Message::SelectAccount(i) => {
+                if let FactoryStage::SelectAccount(accounts) = &self.stage {
+                    self.account = accounts.get(i).cloned();
+                    self.stage = FactoryStage::Ready;
+                    ctx.link().send_message(Message::RequestTokens);
+                }
+            }
+            Message::RequestTokens => {
+                let Some(account) = &self.account else {
+                    return false;
+                };
+                if self.factory.is_empty() {
+                    return false;
+                }
+                let token_service = self.token_service.clone().unwrap();
+                let (factory, address) = (self.factory.clone(), account.address.clone());
+                ctx.link().send_future(async move {
+                    match token_service.get_token_registry(factory, address).await {
+                        Ok(tokens) => Message::ReceivedTokens(tokens),
+                        Err(err) => Message::Error(err),
+                    }
+                });
+            }
+            Message::ReceivedTokens(tokens) => {
+                self.tokens = Some(tokens);
+            }
+            Message::Watch(i) => {
+                let Some(info) = self.tokens.as_ref().and_then(|tokens| tokens.get(i)) else {
+                    return false;
+                };
+                self.watchlist = watch(WatchedToken {
+                    address: info.token.to_string(),
+                    label: info.label(),
+                });
+            }
+        }
+        true
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let on_factory_input = ctx.link().callback(move |event: InputEvent| {
+            let input_element = event.target_dyn_into::<HtmlInputElement>().unwrap();
+            Message::ChangeFactory(input_element.value())
+        });
+
+        let stage_html: Html = match &self.stage {
+            FactoryStage::Error(error_message) => {
+                html!(<div class="error"> {"Error: "} {error_message} </div>)
+            }
+            FactoryStage::CreatingTokenService => {
+                html!(<div>{"Creating Online Client..."}</div>)
+            }
+            FactoryStage::EnterAccount => {
+                let get_accounts_click = ctx.link().callback(|_| Message::RequestAccounts);
+                html!(
+                    <div>
+                        <button onclick={get_accounts_click}> {"=> Select an Account"} </button>
+                    </div>
+                )
+            }
+            FactoryStage::RequestingAccounts => {
+                html!(<div>{"Querying extensions for accounts..."}</div>)
+            }
+            FactoryStage::SelectAccount(accounts) => {
+                if accounts.is_empty() {
+                    html!(<div>{"No Web3 extension accounts found. Install Talisman or the Polkadot.js extension and add an account."}</div>)
+                } else {
+                    html!(
+                        <>
+                            <div class="mb"><b>{"Select an account you want to use for signing:"}</b></div>
+                            { for accounts.iter().enumerate().map(|(i, account)| {
+                                let select_account = ctx.link().callback(move |_| Message::SelectAccount(i));
+                                html! {
+                                    <button onclick={select_account}>
+                                        {&account.source} {" | "} {&account.name}<br/>
+                                        <small>{&account.address}</small>
+                                    </button>
+                                }
+                            }) }
+                        </>
+                    )
+                }
+            }
+            FactoryStage::Ready => match &self.tokens {
+                None if self.factory.is_empty() => html!(<></>),
+                None => html!(<div class="mb loading">{"Loading tokens..."}</div>),
+                Some(tokens) if tokens.is_empty() => {
+                    html!(<div class="mb">{"The factory has not created any tokens yet."}</div>)
+                }
+                Some(tokens) => {
+                    let address = self.account.as_ref().map(|account| account.address.clone());
+                    html! {
+                        <table class="mb">
+                            <tr>
+                                <th>{"Token"}</th>
+                                <th>{"Decimals"}</th>
+                                <th>{"Creator"}</th>
+                                <th></th>
+                            </tr>
+                            { for tokens.iter().enumerate().map(|(i, info)| {
+                                let token = info.token.to_string();
+                                let watched = self.watchlist.iter().any(|watched| watched.address == token);
+                                let creator = info.creator.to_string();
+                                let by_account = address.as_ref() == Some(&creator);
+                                let onwatch = ctx.link().callback(move |_| Message::Watch(i));
+                                html! {
+                                    <tr>
+                                        <td>{info.label()}<br/><small>{token}</small></td>
+                                        <td>{info.decimals}</td>
+                                        <td>{if by_account { "you".to_string() } else { creator }}</td>
+                                        <td>
+                                            <button onclick={onwatch} disabled={watched}>
+                                                {if watched { "Watched" } else { "Watch" }}
+                                            </button>
+                                        </td>
+                                    </tr>
+                                }
+                            }) }
+                        </table>
+                    }
+                }
+            },
+        };
+
+        html! {
+            <div>
+                <Link<Route> to={Route::Home}> <button>{"<= Back"}</button></Link<Route>>
+                <h1>{"Token Factory"}</h1>
+                <div class="mb"><b>{"Enter Factory Contract:"}</b></div>
+                <input oninput={on_factory_input} class="mb" value={AttrValue::from(self.factory.clone())}/>
+                {stage_html}
+                <Link<Route> to={Route::Token}> <button>{"Open watched tokens"}</button></Link<Route>>
+            </div>
+        }
+    }
+}
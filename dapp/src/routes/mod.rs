@@ -1,3 +1,10 @@
+pub mod airdrop;
+pub mod compliance;
+pub mod escrow;
+pub mod factory;
 pub mod fetching;
 pub mod signing;
+pub mod streams;
+pub mod swap;
 pub mod token;
+pub mod wrap;
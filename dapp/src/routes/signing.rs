@@ -3,12 +3,17 @@ use futures::FutureExt;
 
 use subxt::{OnlineClient, PolkadotConfig};
 
-use subxt::ext::codec::{Decode, Encode};
+use subxt::ext::codec::Encode;
 use subxt::tx::SubmittableExtrinsic;
 use subxt::tx::TxPayload;
 use subxt::utils::{AccountId32, MultiSignature};
 
-use crate::services::{extension_signature_for_partial_extrinsic, get_accounts, polkadot, Account};
+use crate::components::FeeConfirmationComponent;
+use crate::services::fees::{estimate_fees, FeeEstimate};
+use crate::services::{
+    get_accounts, polkadot, prepare_extrinsic, Account, NonceManager, PreparedExtrinsic,
+    SigningOptions,
+};
 use crate::Route;
 use web_sys::HtmlInputElement;
 use yew::prelude::*;
@@ -17,6 +22,9 @@ use yew_router::prelude::Link;
 pub struct SigningExamplesComponent {
     message: String,
     remark_call_bytes: Vec<u8>,
+    signing_options: SigningOptions,
+    /// nonce reserved for the signed but not yet finalized extrinsic
+    reserved_nonce: Option<(AccountId32, u64)>,
     online_client: Option<OnlineClient<PolkadotConfig>>,
     stage: SigningStage,
 }
@@ -33,6 +41,12 @@ impl SigningExamplesComponent {
         self.remark_call_bytes = remark_call_bytes;
         self.message = message;
     }
+
+    fn release_nonce(&mut self) {
+        if let Some((account_id, nonce)) = self.reserved_nonce.take() {
+            NonceManager::shared().release(&account_id, nonce);
+        }
+    }
 }
 
 pub enum SigningStage {
@@ -41,6 +55,12 @@ pub enum SigningStage {
     EnterMessage,
     RequestingAccounts,
     SelectAccount(Vec<Account>),
+    EstimatingFees(Account),
+    ConfirmFees {
+        signer_account: Account,
+        prepared: PreparedExtrinsic,
+        fees: FeeEstimate,
+    },
     Signing(Account),
     SigningSuccess {
         signer_account: Account,
@@ -65,13 +85,20 @@ pub enum Message {
     Error(anyhow::Error),
     OnlineClientCreated(OnlineClient<PolkadotConfig>),
     ChangeMessage(String),
+    /// mortality period in blocks, an empty string makes the transaction immortal
+    ChangeMortalityPeriod(String),
+    ChangeTip(String),
     RequestAccounts,
     ReceivedAccounts(Vec<Account>),
     /// usize represents account index in Vec<Account>
     SignWithAccount(usize),
+    ReceivedFees(PreparedExtrinsic, FeeEstimate),
+    ConfirmSigning,
+    CancelSigning,
     ReceivedSignature(
         MultiSignature,
         SubmittableExtrinsic<PolkadotConfig, OnlineClient<PolkadotConfig>>,
+        u64,
     ),
     SubmitSigned,
     ExtrinsicFinalized {
@@ -97,6 +124,8 @@ impl Component for SigningExamplesComponent {
             stage: SigningStage::CreatingOnlineClient,
             online_client: None,
             remark_call_bytes: vec![],
+            signing_options: SigningOptions::default(),
+            reserved_nonce: None,
         }
     }
 
@@ -110,6 +139,18 @@ impl Component for SigningExamplesComponent {
             Message::ChangeMessage(message) => {
                 self.set_message(message);
             }
+            Message::ChangeMortalityPeriod(period) => {
+                if period.trim().is_empty() {
+                    self.signing_options.mortality_period = None;
+                } else if let Ok(period) = period.trim().parse::<u64>() {
+                    self.signing_options.mortality_period = Some(period);
+                }
+            }
+            Message::ChangeTip(tip) => {
+                if let Ok(tip) = tip.trim().parse::<u128>() {
+                    self.signing_options.tip = tip;
+                }
+            }
             Message::RequestAccounts => {
                 self.stage = SigningStage::RequestingAccounts;
                 ctx.link().send_future(get_accounts().map(
@@ -128,49 +169,92 @@ impl Component for SigningExamplesComponent {
                     let account = accounts.get(i).unwrap();
                     let account_address = account.address.clone();
                     let account_source = account.source.clone();
-                    let account_id: AccountId32 = account_address.parse().unwrap();
 
-                    self.stage = SigningStage::Signing(account.clone());
+                    self.stage = SigningStage::EstimatingFees(account.clone());
 
                     let remark_call = polkadot::tx()
                         .system()
                         .remark(self.message.as_bytes().to_vec());
 
+                    let api = self.online_client.as_ref().unwrap().clone();
+                    let signing_options = self.signing_options.clone();
+
+                    ctx.link().send_future(async move {
+                        let prepared = match prepare_extrinsic(
+                            &api,
+                            &remark_call,
+                            account_source,
+                            account_address,
+                            &signing_options,
+                        )
+                        .await
+                        {
+                            Ok(prepared) => prepared,
+                            Err(err) => {
+                                return Message::Error(anyhow!(
+                                    "could not create partial extrinsic:\n{:?}",
+                                    err
+                                ));
+                            }
+                        };
+
+                        match estimate_fees(&api, &prepared).await {
+                            Ok(fees) => Message::ReceivedFees(prepared, fees),
+                            Err(err) => {
+                                prepared.release_nonce();
+                                Message::Error(anyhow!("could not estimate fees:\n{:?}", err))
+                            }
+                        }
+                    });
+                }
+            }
+            Message::ReceivedFees(prepared, fees) => {
+                if let SigningStage::EstimatingFees(account) = &self.stage {
+                    self.stage = SigningStage::ConfirmFees {
+                        signer_account: account.clone(),
+                        prepared,
+                        fees,
+                    };
+                } else {
+                    prepared.release_nonce();
+                }
+            }
+            Message::CancelSigning => {
+                if let SigningStage::ConfirmFees { prepared, .. } = &self.stage {
+                    prepared.release_nonce();
+                    self.stage = SigningStage::EnterMessage;
+                }
+            }
+            Message::ConfirmSigning => {
+                if let SigningStage::ConfirmFees { signer_account, .. } = &self.stage {
+                    let signing_stage = SigningStage::Signing(signer_account.clone());
+                    let SigningStage::ConfirmFees { prepared, .. } = std::mem::replace(&mut self.stage, signing_stage) else {
+                        panic!("unreachable")
+                    };
+
                     let api = self.online_client.as_ref().unwrap().clone();
 
                     ctx.link()
                         .send_future(
                             async move {
-                                let partial_extrinsic =
-                                    match api.tx().create_partial_signed(&remark_call, &account_id, Default::default()).await {
-                                        Ok(partial_extrinsic) => partial_extrinsic,
-                                        Err(err) => {
-                                            return Message::Error(anyhow!("could not create partial extrinsic:\n{:?}", err));
-                                        }
-                                    };
-
-                                let Ok(signature) = extension_signature_for_partial_extrinsic(&partial_extrinsic, &api, &account_id, account_source, account_address).await else {
+                                let Ok((multi_signature, signed_extrinsic)) = prepared.sign(&api).await else {
                                     return Message::Error(anyhow!("Signing via extension failed"));
                                 };
 
-                                let Ok(multi_signature) = MultiSignature::decode(&mut &signature[..]) else {
-                                    return Message::Error(anyhow!("MultiSignature Decoding"));
-                                };
-
-                                let signed_extrinsic = partial_extrinsic.sign_with_address_and_signature(&account_id.into(), &multi_signature);
-
                                 // do a dry run (to debug in the js console if the extrinsic would work)
                                 let dry_res = signed_extrinsic.dry_run(None).await;
                                 web_sys::console::log_1(&format!("Dry Run Result: {:?}", dry_res).into());
 
-                                // return the signature and signed extrinsic
-                                Message::ReceivedSignature(multi_signature, signed_extrinsic)
+                                // return the signature, signed extrinsic and the nonce reserved for it
+                                Message::ReceivedSignature(multi_signature, signed_extrinsic, prepared.nonce())
                             }
                         );
                 }
             }
-            Message::ReceivedSignature(signature, signed_extrinsic) => {
+            Message::ReceivedSignature(signature, signed_extrinsic, nonce) => {
                 if let SigningStage::Signing(account) = &self.stage {
+                    let account_id: AccountId32 = account.address.parse().unwrap();
+                    self.reserved_nonce = Some((account_id, nonce));
                     let signed_extrinsic_hex =
                         format!("0x{}", hex::encode(signed_extrinsic.encoded()));
                     self.stage = SigningStage::SigningSuccess {
@@ -210,6 +294,7 @@ impl Component for SigningExamplesComponent {
                 {
                     *submitting_stage = SubmittingStage::Success { remark_event }
                 }
+                self.release_nonce();
             }
             Message::ExtrinsicFailed(err) => {
                 if let SigningStage::SigningSuccess {
@@ -218,6 +303,7 @@ impl Component for SigningExamplesComponent {
                 {
                     *submitting_stage = SubmittingStage::Error(err)
                 }
+                self.release_nonce();
             }
         };
         true
@@ -254,7 +340,9 @@ impl Component for SigningExamplesComponent {
         };
 
         let signer_account_html: Html = match &self.stage {
-            SigningStage::Signing(signer_account)
+            SigningStage::EstimatingFees(signer_account)
+            | SigningStage::ConfirmFees { signer_account, .. }
+            | SigningStage::Signing(signer_account)
             | SigningStage::SigningSuccess { signer_account, .. } => {
                 html!(
                     <div class="mb">
@@ -286,12 +374,29 @@ impl Component for SigningExamplesComponent {
                     let value = input_element.value();
                     Message::ChangeMessage(value)
                 });
+                let on_period_input = ctx.link().callback(move |event: InputEvent| {
+                    let input_element = event.target_dyn_into::<HtmlInputElement>().unwrap();
+                    Message::ChangeMortalityPeriod(input_element.value())
+                });
+                let on_tip_input = ctx.link().callback(move |event: InputEvent| {
+                    let input_element = event.target_dyn_into::<HtmlInputElement>().unwrap();
+                    Message::ChangeTip(input_element.value())
+                });
+                let mortality_period = self
+                    .signing_options
+                    .mortality_period
+                    .map(|period| period.to_string())
+                    .unwrap_or_default();
 
                 html!(
                     <>
                         <div class="mb"><b>{"Enter a message for the \"remark\" call in the \"System\" pallet:"}</b></div>
                         <input oninput={on_input} class="mb" value={AttrValue::from(self.message.clone())}/>
                         {message_as_hex_html()}
+                        <div class="mb"><b>{"Mortality period in blocks (empty for an immortal transaction):"}</b></div>
+                        <input oninput={on_period_input} class="mb" type="number" value={mortality_period}/>
+                        <div class="mb"><b>{"Tip:"}</b></div>
+                        <input oninput={on_tip_input} class="mb" type="number" value={self.signing_options.tip.to_string()}/>
                         <button onclick={get_accounts_click}> {"=> Select an Account for Signing"} </button>
                     </>
                 )
@@ -319,6 +424,21 @@ impl Component for SigningExamplesComponent {
                     )
                 }
             }
+            SigningStage::EstimatingFees(_) => {
+                html!(<div>{"Estimating fees..."}</div>)
+            }
+            SigningStage::ConfirmFees { fees, .. } => {
+                let onconfirm = ctx.link().callback(|_| Message::ConfirmSigning);
+                let oncancel = ctx.link().callback(|_| Message::CancelSigning);
+                html!(
+                    <FeeConfirmationComponent
+                        title="Estimated fees of the \"remark\" extrinsic:"
+                        fees={fees.clone()}
+                        {onconfirm}
+                        {oncancel}
+                    />
+                )
+            }
             SigningStage::Signing(_) => {
                 html!(<div>{"Singing message with browser extension..."}</div>)
             }
@@ -0,0 +1,260 @@
+use anyhow::anyhow;
+use futures::{FutureExt, StreamExt};
+use std::str::FromStr;
+use std::time::Duration;
+use subxt::utils::AccountId32;
+use web_sys::HtmlInputElement;
+use yew::platform::time::interval;
+use yew::prelude::*;
+use yew_router::prelude::Link;
+
+use crate::components::{FeeConfirmationComponent, StreamsComponent};
+use crate::services::fees::FeeEstimate;
+use crate::services::streams::{Stream, StreamCall};
+use crate::services::{
+    get_accounts, Account, PendingCall, PreparedCall, PreparedExtrinsic, TokenService,
+};
+use crate::Route;
+
+/// the streams of the selected account, their balances tick every second
+pub struct StreamsPageComponent {
+    contract: String,
+    stage: StreamsStage,
+    token_service: Option<TokenService>,
+    account: Option<Account>,
+    /// `None` while loading
+    streams: Option<Vec<(u32, Stream)>>,
+    /// timestamp in milliseconds the balances are shown at
+    now: u64,
+    pending: PendingCall<StreamCall>,
+}
+
+pub enum StreamsStage {
+    Error(String),
+    CreatingTokenService,
+    EnterAccount,
+    RequestingAccounts,
+    SelectAccount(Vec<Account>),
+    Ready,
+}
+
+pub enum Message {
+    Error(anyhow::Error),
+    TokenServiceCreated(TokenService),
+    ChangeContract(String),
+    RequestAccounts,
+    ReceivedAccounts(Vec<Account>),
+    SelectAccount(usize),
+    RequestStreams,
+    ReceivedStreams(Vec<(u32, Stream)>),
+    Tick,
+    Call(StreamCall),
+    CallPrepared(StreamCall, PreparedExtrinsic, FeeEstimate),
+    Confirm,
+    Cancel,
+}
+
+impl Component for StreamsPageComponent {
+    type Message = Message;
+
+    type Properties = ();
+
+    fn create(ctx: &Context<Self>) -> Self {
+        ctx.link()
+            .send_future(TokenService::new().map(|res| match res {
+                Ok(service) => Message::TokenServiceCreated(service),
+                Err(err) => Message::Error(anyhow!("Failed to create TokenService: {}", err)),
+            }));
+        ctx.link()
+            .send_stream(interval(Duration::from_secs(1)).map(|_| Message::Tick));
+        StreamsPageComponent {
+            contract: String::new(),
+            stage: StreamsStage::CreatingTokenService,
+            token_service: None,
+            account: None,
+            streams: None,
+            now: js_sys::Date::now() as u64,
+            pending: PendingCall::default(),
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Message::Error(err) => self.stage = StreamsStage::Error(err.to_string()),
+            Message::TokenServiceCreated(service) => {
+                self.token_service = Some(service);
+                self.stage = StreamsStage::EnterAccount;
+            }
+            Message::ChangeContract(contract) => {
+                self.contract = contract;
+                self.streams = None;
+                ctx.link().send_message(Message::RequestStreams);
+            }
+            Message::RequestAccounts => {
+                self.stage = StreamsStage::RequestingAccounts;
+                ctx.link().send_future(get_accounts().map(
+                    |accounts_or_err| match accounts_or_err {
+                        Ok(accounts) => Message::ReceivedAccounts(accounts),
+                        Err(err) => Message::Error(err),
+                    },
+                ));
+            }
+            Message::ReceivedAccounts(accounts) => {
+                self.stage = StreamsStage::SelectAccount(accounts);
+            }
+            Message::SelectAccount(i) => {
+                if let StreamsStage::SelectAccount(accounts) = &self.stage {
+                    self.account = accounts.get(i).cloned();
+                    self.stage = StreamsStage::Ready;
+                    ctx.link().send_message(Message::RequestStreams);
+                }
+            }
+            Message::RequestStreams => {
+                let Some(account) = &self.account else {
+                    return false;
+                };
+                if self.contract.is_empty() {
+                    return false;
+                }
+                let Some(token_service) = self.token_service.clone() else {
+                    return false;
+                };
+                let (contract, address) = (self.contract.clone(), account.address.clone());
+                ctx.link().send_future(async move {
+                    match token_service.get_streams(contract, address).await {
+                        Ok(streams) => Message::ReceivedStreams(streams),
+                        Err(err) => Message::Error(err),
+                    }
+                });
+            }
+            Message::ReceivedStreams(streams) => {
+                self.streams = Some(streams);
+            }
+            Message::Tick => {
+                self.now = js_sys::Date::now() as u64;
+                // only the balances of loaded streams change
+                return self.streams.is_some();
+            }
+            Message::Call(call) => {
+                let Some(account) = self.account.clone() else {
+                    return false;
+                };
+                let Some(token_service) = self.token_service.clone() else {
+                    return false;
+                };
+                let contract = self.contract.clone();
+                ctx.link().send_future(async move {
+                    match token_service
+                        .prepare_stream_call(contract, account.source, account.address, &call)
+                        .await
+                    {
+                        Ok((prepared, fees)) => Message::CallPrepared(call, prepared, fees),
+                        Err(err) => Message::Error(err),
+                    }
+                });
+            }
+            Message::CallPrepared(call, prepared, fees) => {
+                self.pending.replace(PreparedCall {
+                    call,
+                    prepared,
+                    fees,
+                });
+            }
+            Message::Confirm => {
+                return self.pending.confirm(
+                    ctx,
+                    self.token_service.as_ref(),
+                    Message::RequestStreams,
+                    Message::Error,
+                );
+            }
+            Message::Cancel => self.pending.cancel(),
+        }
+        true
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let on_contract_input = ctx.link().callback(move |event: InputEvent| {
+            let input_element = event.target_dyn_into::<HtmlInputElement>().unwrap();
+            Message::ChangeContract(input_element.value())
+        });
+
+        let stage_html: Html = match &self.stage {
+            StreamsStage::Error(error_message) => {
+                html!(<div class="error"> {"Error: "} {error_message} </div>)
+            }
+            StreamsStage::CreatingTokenService => {
+                html!(<div>{"Creating Online Client..."}</div>)
+            }
+            StreamsStage::EnterAccount => {
+                let get_accounts_click = ctx.link().callback(|_| Message::RequestAccounts);
+                html!(
+                    <div>
+                        <button onclick={get_accounts_click}> {"=> Select an Account"} </button>
+                    </div>
+                )
+            }
+            StreamsStage::RequestingAccounts => {
+                html!(<div>{"Querying extensions for accounts..."}</div>)
+            }
+            StreamsStage::SelectAccount(accounts) => {
+                if accounts.is_empty() {
+                    html!(<div>{"No Web3 extension accounts found. Install Talisman or the Polkadot.js extension and add an account."}</div>)
+                } else {
+                    html!(
+                        <>
+                            <div class="mb"><b>{"Select an account you want to use for signing:"}</b></div>
+                            { for accounts.iter().enumerate().map(|(i, account)| {
+                                let select_account = ctx.link().callback(move |_| Message::SelectAccount(i));
+                                html! {
+                                    <button onclick={select_account}>
+                                        {&account.source} {" | "} {&account.name}<br/>
+                                        <small>{&account.address}</small>
+                                    </button>
+                                }
+                            }) }
+                        </>
+                    )
+                }
+            }
+            StreamsStage::Ready => {
+                let account_id = self
+                    .account
+                    .as_ref()
+                    .and_then(|account| AccountId32::from_str(&account.address).ok());
+                match account_id {
+                    Some(_) if self.contract.is_empty() => html!(<></>),
+                    Some(account_id) => html! {
+                        <>
+                            <StreamsComponent
+                                streams={self.streams.clone()}
+                                account={account_id}
+                                now={self.now}
+                                oncall={ctx.link().callback(Message::Call)}
+                            />
+                            if let Some(pending) = self.pending.get() {
+                                <FeeConfirmationComponent
+                                    title={pending.call.to_string()}
+                                    fees={pending.fees.clone()}
+                                    onconfirm={ctx.link().callback(|_| Message::Confirm)}
+                                    oncancel={ctx.link().callback(|_| Message::Cancel)}
+                                />
+                            }
+                        </>
+                    },
+                    None => html!(<div class="error">{"Invalid account address"}</div>),
+                }
+            }
+        };
+
+        html! {
+            <div>
+                <Link<Route> to={Route::Home}> <button>{"<= Back"}</button></Link<Route>>
+                <h1>{"Streams"}</h1>
+                <div class="mb"><b>{"Enter Streams Contract:"}</b></div>
+                <input oninput={on_contract_input} class="mb" value={AttrValue::from(self.contract.clone())}/>
+                {stage_html}
+            </div>
+        }
+    }
+}
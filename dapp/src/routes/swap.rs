@@ -0,0 +1,390 @@
+use anyhow::anyhow;
+use futures::FutureExt;
+use std::str::FromStr;
+use subxt::utils::AccountId32;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+use yew_router::prelude::Link;
+
+use crate::components::FeeConfirmationComponent;
+use crate::services::amm::{min_amount_out, PairState, SwapCall};
+use crate::services::fees::FeeEstimate;
+use crate::services::psp22::TokenCall;
+use crate::services::{
+    get_accounts, Account, PendingCall, PreparedCall, PreparedExtrinsic, TokenService,
+};
+use crate::Route;
+
+/// slippage tolerance until the user changes it, 0.5%
+const DEFAULT_SLIPPAGE_BPS: u128 = 50;
+
+/// swaps the tokens of an AMM pair, quoted with a dry-run of the pair
+pub struct SwapComponent {
+    pair: String,
+    stage: SwapStage,
+    token_service: Option<TokenService>,
+    account: Option<Account>,
+    /// `None` while loading
+    pair_state: Option<PairState>,
+    /// swap `token_1` for `token_0` instead of `token_0` for `token_1`
+    reversed: bool,
+    amount_in: u128,
+    slippage_bps: u128,
+    /// `Err` with the reason the pair can't quote the amount
+    quote: Option<Result<u128, String>>,
+    /// title, extrinsic and fees of the approval or swap waiting for confirmation
+    pending: PendingCall<String>,
+}
+
+pub enum SwapStage {
+    Error(String),
+    CreatingTokenService,
+    EnterAccount,
+    RequestingAccounts,
+    SelectAccount(Vec<Account>),
+    Ready,
+}
+
+pub enum Message {
+    Error(anyhow::Error),
+    TokenServiceCreated(TokenService),
+    ChangePair(String),
+    RequestAccounts,
+    ReceivedAccounts(Vec<Account>),
+    SelectAccount(usize),
+    RequestPairState,
+    ReceivedPairState(PairState),
+    ChangeAmount(String),
+    ChangeSlippage(String),
+    Flip,
+    /// the token in, amount in and quote, outdated quotes are dropped
+    ReceivedQuote(AccountId32, u128, Result<u128, String>),
+    Approve,
+    Swap,
+    Prepared(String, PreparedExtrinsic, FeeEstimate),
+    Confirm,
+    Cancel,
+}
+
+impl SwapComponent {
+    fn token_in(&self) -> Option<AccountId32> {
+        let state = self.pair_state.as_ref()?;
+        Some(if self.reversed {
+            state.token_1.clone()
+        } else {
+            state.token_0.clone()
+        })
+    }
+
+    fn request_quote(&mut self, ctx: &Context<Self>) {
+        self.quote = None;
+        let (Some(account), Some(token_in)) = (&self.account, self.token_in()) else {
+            return;
+        };
+        if self.amount_in == 0 {
+            return;
+        }
+        let Some(token_service) = self.token_service.clone() else {
+            return;
+        };
+        let (pair, address, amount_in) =
+            (self.pair.clone(), account.address.clone(), self.amount_in);
+        ctx.link().send_future(async move {
+            let quote = token_service
+                .quote_swap(pair, address, &token_in, amount_in)
+                .await
+                .map_err(|err| err.to_string());
+            Message::ReceivedQuote(token_in, amount_in, quote)
+        });
+    }
+}
+
+impl Component for SwapComponent {
+    type Message = Message;
+
+    type Properties = ();
+
+    fn create(ctx: &Context<Self>) -> Self {
+        ctx.link()
+            .send_future(TokenService::new().map(|res| match res {
+                Ok(service) => Message::TokenServiceCreated(service),
+                Err(err) => Message::Error(anyhow!("Failed to create TokenService: {}", err)),
+            }));
+        SwapComponent {
+            pair: String::new(),
+            stage: SwapStage::CreatingTokenService,
+            token_service: None,
+            account: None,
+            pair_state: None,
+            reversed: false,
+            amount_in: 0,
+            slippage_bps: DEFAULT_SLIPPAGE_BPS,
+            quote: None,
+            pending: PendingCall::default(),
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Message::Error(err) => self.stage = SwapStage::Error(err.to_string()),
+            Message::TokenServiceCreated(service) => {
+                self.token_service = Some(service);
+                self.stage = SwapStage::EnterAccount;
+            }
+            Message::ChangePair(pair) => {
+                self.pair = pair;
+                self.pair_state = None;
+                self.quote = None;
+                ctx.link().send_message(Message::RequestPairState);
+            }
+            Message::RequestAccounts => {
+                self.stage = SwapStage::RequestingAccounts;
+                ctx.link().send_future(get_accounts().map(
+                    |accounts_or_err| match accounts_or_err {
+                        Ok(accounts) => Message::ReceivedAccounts(accounts),
+                        Err(err) => Message::Error(err),
+                    },
+                ));
+            }
+            Message::ReceivedAccounts(accounts) => {
+                self.stage = SwapStage::SelectAccount(accounts);
+            }
+            Message::SelectAccount(i) => {
+                if let SwapStage::SelectAccount(accounts) = &self.stage {
+                    self.account = accounts.get(i).cloned();
+                    self.stage = SwapStage::Ready;
+                    ctx.link().send_message(Message::RequestPairState);
+                }
+            }
+            Message::RequestPairState => {
+                let Some(account) = &self.account else {
+                    return false;
+                };
+                if self.pair.is_empty() {
+                    return false;
+                }
+                let Some(token_service) = self.token_service.clone() else {
+                    return false;
+                };
+                let (pair, address) = (self.pair.clone(), account.address.clone());
+                ctx.link().send_future(async move {
+                    match token_service.get_pair_state(pair, address).await {
+                        Ok(state) => Message::ReceivedPairState(state),
+                        Err(err) => Message::Error(err),
+                    }
+                });
+            }
+            Message::ReceivedPairState(state) => {
+                self.pair_state = Some(state);
+                self.request_quote(ctx);
+            }
+            Message::ChangeAmount(amount) => {
+                self.amount_in = amount.parse().unwrap_or_default();
+                self.request_quote(ctx);
+            }
+            Message::ChangeSlippage(percent) => {
+                // percent with up to two decimals
+                self.slippage_bps = percent
+                    .parse::<f64>()
+                    .map(|percent| (percent * 100.0).round().clamp(0.0, 10_000.0) as u128)
+                    .unwrap_or(DEFAULT_SLIPPAGE_BPS);
+            }
+            Message::Flip => {
+                self.reversed = !self.reversed;
+                self.request_quote(ctx);
+            }
+            Message::ReceivedQuote(token_in, amount_in, quote) => {
+                if self.token_in() != Some(token_in) || self.amount_in != amount_in {
+                    return false;
+                }
+                self.quote = Some(quote);
+            }
+            Message::Approve => {
+                let (Some(account), Some(token_in)) = (self.account.clone(), self.token_in()) else {
+                    return false;
+                };
+                let Ok(spender) = AccountId32::from_str(&self.pair) else {
+                    ctx.link()
+                        .send_message(Message::Error(anyhow!("Invalid pair address: {}", self.pair)));
+                    return false;
+                };
+                let call = TokenCall::Approve {
+                    spender,
+                    value: self.amount_in,
+                };
+                let Some(token_service) = self.token_service.clone() else {
+                    return false;
+                };
+                ctx.link().send_future(async move {
+                    let title = call.to_string();
+                    match token_service
+                        .prepare_token_call(
+                            token_in.to_string(),
+                            account.source,
+                            account.address,
+                            &call,
+                        )
+                        .await
+                    {
+                        Ok((prepared, fees)) => Message::Prepared(title, prepared, fees),
+                        Err(err) => Message::Error(err),
+                    }
+                });
+            }
+            Message::Swap => {
+                let (Some(account), Some(token_in), Some(Ok(quote))) =
+                    (self.account.clone(), self.token_in(), self.quote.clone())
+                else {
+                    return false;
+                };
+                let swap = SwapCall {
+                    token_in,
+                    amount_in: self.amount_in,
+                    min_amount_out: min_amount_out(quote, self.slippage_bps),
+                };
+                let title = format!(
+                    "Swap {} tokens for at least {} tokens",
+                    swap.amount_in, swap.min_amount_out
+                );
+                let Some(token_service) = self.token_service.clone() else {
+                    return false;
+                };
+                let pair = self.pair.clone();
+                ctx.link().send_future(async move {
+                    match token_service
+                        .prepare_swap(pair, account.source, account.address, &swap)
+                        .await
+                    {
+                        Ok((prepared, fees)) => Message::Prepared(title, prepared, fees),
+                        Err(err) => Message::Error(err),
+                    }
+                });
+            }
+            Message::Prepared(title, prepared, fees) => {
+                self.pending.replace(PreparedCall {
+                    call: title,
+                    prepared,
+                    fees,
+                });
+            }
+            Message::Confirm => {
+                return self.pending.confirm(
+                    ctx,
+                    self.token_service.as_ref(),
+                    Message::RequestPairState,
+                    Message::Error,
+                );
+            }
+            Message::Cancel => self.pending.cancel(),
+        }
+        true
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let on_pair_input = ctx.link().callback(move |event: InputEvent| {
+            let input_element = event.target_dyn_into::<HtmlInputElement>().unwrap();
+            Message::ChangePair(input_element.value())
+        });
+
+        let stage_html: Html = match &self.stage {
+            SwapStage::Error(error_message) => {
+                html!(<div class="error"> {"Error: "} {error_message} </div>)
+            }
+            SwapStage::CreatingTokenService => {
+                html!(<div>{"Creating Online Client..."}</div>)
+            }
+            SwapStage::EnterAccount => {
+                let get_accounts_click = ctx.link().callback(|_| Message::RequestAccounts);
+                html!(
+                    <div>
+                        <button onclick={get_accounts_click}> {"=> Select an Account"} </button>
+                    </div>
+                )
+            }
+            SwapStage::RequestingAccounts => {
+                html!(<div>{"Querying extensions for accounts..."}</div>)
+            }
+            SwapStage::SelectAccount(accounts) => {
+                if accounts.is_empty() {
+                    html!(<div>{"No Web3 extension accounts found. Install Talisman or the Polkadot.js extension and add an account."}</div>)
+                } else {
+                    html!(
+                        <>
+                            <div class="mb"><b>{"Select an account you want to use for signing:"}</b></div>
+                            { for accounts.iter().enumerate().map(|(i, account)| {
+                                let select_account = ctx.link().callback(move |_| Message::SelectAccount(i));
+                                html! {
+                                    <button onclick={select_account}>
+                                        {&account.source} {" | "} {&account.name}<br/>
+                                        <small>{&account.address}</small>
+                                    </button>
+                                }
+                            }) }
+                        </>
+                    )
+                }
+            }
+            SwapStage::Ready => match (&self.pair_state, self.token_in()) {
+                (Some(state), Some(token_in)) => {
+                    let on_amount_input = ctx.link().callback(move |event: InputEvent| {
+                        let input_element = event.target_dyn_into::<HtmlInputElement>().unwrap();
+                        Message::ChangeAmount(input_element.value())
+                    });
+                    let on_slippage_input = ctx.link().callback(move |event: InputEvent| {
+                        let input_element = event.target_dyn_into::<HtmlInputElement>().unwrap();
+                        Message::ChangeSlippage(input_element.value())
+                    });
+                    let quote_html = match &self.quote {
+                        None if self.amount_in == 0 => html!(<></>),
+                        None => html!(<div class="mb loading">{"Quoting..."}</div>),
+                        Some(Err(err)) => html!(<div class="mb error">{err}</div>),
+                        Some(Ok(quote)) => html! {
+                            <>
+                                <div class="mb">{format!("You receive about {quote} tokens")}</div>
+                                <div class="mb">
+                                    <small>{format!("at least {} tokens with the slippage tolerance", min_amount_out(*quote, self.slippage_bps))}</small>
+                                </div>
+                            </>
+                        },
+                    };
+                    let can_swap = matches!(self.quote, Some(Ok(_)));
+                    html! {
+                        <>
+                            <div class="mb">{format!("Reserves: {} / {}", state.reserve_0, state.reserve_1)}</div>
+                            <div class="mb"><b>{"Sell "}</b>{token_in.to_string()}</div>
+                            <div class="mb"><b>{"Buy "}</b>{state.other_token(&token_in).to_string()}</div>
+                            <button class="mb" onclick={ctx.link().callback(|_| Message::Flip)}>{"Flip direction"}</button>
+                            <div class="mb"><b>{"Amount:"}</b></div>
+                            <input class="mb" type="number" min="0" oninput={on_amount_input}/>
+                            <div class="mb"><b>{"Slippage tolerance (%):"}</b></div>
+                            <input class="mb" type="number" min="0" step="0.1" value={format!("{}", self.slippage_bps as f64 / 100.0)} oninput={on_slippage_input}/>
+                            {quote_html}
+                            <button class="mb" onclick={ctx.link().callback(|_| Message::Approve)} disabled={self.amount_in == 0}>{"Approve amount"}</button>
+                            <button class="mb" onclick={ctx.link().callback(|_| Message::Swap)} disabled={!can_swap}>{"Preview swap"}</button>
+                            if let Some(pending) = self.pending.get() {
+                                <FeeConfirmationComponent
+                                    title={pending.call.clone()}
+                                    fees={pending.fees.clone()}
+                                    onconfirm={ctx.link().callback(|_| Message::Confirm)}
+                                    oncancel={ctx.link().callback(|_| Message::Cancel)}
+                                />
+                            }
+                        </>
+                    }
+                }
+                _ if self.pair.is_empty() => html!(<></>),
+                _ => html!(<div class="mb loading">{"Loading pair..."}</div>),
+            },
+        };
+
+        html! {
+            <div>
+                <Link<Route> to={Route::Home}> <button>{"<= Back"}</button></Link<Route>>
+                <h1>{"Swap"}</h1>
+                <div class="mb"><b>{"Enter Pair Contract:"}</b></div>
+                <input oninput={on_pair_input} class="mb" value={AttrValue::from(self.pair.clone())}/>
+                {stage_html}
+            </div>
+        }
+    }
+}
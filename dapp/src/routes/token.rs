@@ -7,8 +7,24 @@ use web_sys::HtmlInputElement;
 use yew::prelude::*;
 use yew_router::prelude::Link;
 
-use crate::components::SendTokenComponent;
-use crate::services::{get_accounts, Account, TokenService};
+use crate::components::{
+    DelegationComponent, EmissionsComponent, FaucetComponent, FeeConfirmationComponent,
+    PayoutsComponent, PermitComponent, SendTokenComponent, SimulationComponent, TokenAction,
+    VestingComponent, WatchlistComponent,
+};
+use crate::services::emissions::{EmissionState, SupplyPoint};
+use crate::services::faucet::FaucetState;
+use crate::services::fees::FeeEstimate;
+use crate::services::payouts::{PayoutBatch, RowResult};
+use crate::services::permit::SignedPermit;
+use crate::services::psp22::TokenCall;
+use crate::services::simulation::Simulation;
+use crate::services::vesting::VestingSchedule;
+use crate::services::votes::VotingPower;
+use crate::services::watchlist::{load_watchlist, unwatch, WatchedToken};
+use crate::services::{
+    get_accounts, Account, NonceManager, PreparedExtrinsic, PreviewedCall, TokenService,
+};
 use crate::Route;
 
 pub struct TokenComponent {
@@ -18,12 +34,112 @@ pub struct TokenComponent {
     online_client: Option<OnlineClient<PolkadotConfig>>,
     stage: TokenStage,
     token_service: Option<TokenService>,
+    payout_results: Option<Vec<RowResult>>,
+    submitting_payouts: bool,
+    pending_transactions: Vec<PendingTransaction>,
+    /// hash of the last transaction which was finalized
+    finalized_transaction: Option<String>,
+    /// dry-run of a call which would fail, shown instead of the fee confirmation
+    blocked_simulation: Option<Simulation>,
+    /// hex of the last permit signed with the selected account
+    signed_permit: Option<String>,
+    vesting_schedule: Option<VestingSchedule>,
+    voting_power: Option<VotingPower>,
+    faucet_state: Option<FaucetState>,
+    /// transfer fee in basis points the selected account pays on each payout
+    payout_fee_bps: u16,
+    emission_state: Option<EmissionState>,
+    supply_chart: Vec<SupplyPoint>,
+    /// tokens watched on the factory page, stored in the browser
+    watchlist: Vec<WatchedToken>,
 }
 
 impl TokenComponent {
     fn set_contract(&mut self, contract: String) {
         self.contract = contract;
     }
+
+    /// fetches the vesting schedule, the voting power, the faucet state, the payout fee of the selected account
+    /// and the emissions
+    fn request_account_state(&self, ctx: &Context<Self>) {
+        let Some(account) = &self.account else {
+            return;
+        };
+        let token_service = self.token_service.clone().unwrap();
+        let (contract, address) = (self.contract.clone(), account.address.clone());
+        ctx.link().send_future(async move {
+            match token_service.get_vesting_schedule(contract, address).await {
+                Ok(schedule) => Message::ReceivedVestingSchedule(schedule),
+                Err(err) => Message::Error(err),
+            }
+        });
+
+        let token_service = self.token_service.clone().unwrap();
+        let (contract, address) = (self.contract.clone(), account.address.clone());
+        ctx.link().send_future(async move {
+            match token_service.get_voting_power(contract, address).await {
+                Ok(power) => Message::ReceivedVotingPower(power),
+                Err(err) => Message::Error(err),
+            }
+        });
+
+        let token_service = self.token_service.clone().unwrap();
+        let (contract, address) = (self.contract.clone(), account.address.clone());
+        ctx.link().send_future(async move {
+            match token_service.get_faucet_state(contract, address).await {
+                Ok(state) => Message::ReceivedFaucetState(state),
+                Err(err) => Message::Error(err),
+            }
+        });
+
+        let token_service = self.token_service.clone().unwrap();
+        let (contract, address) = (self.contract.clone(), account.address.clone());
+        ctx.link().send_future(async move {
+            match token_service.get_payout_fee_bps(contract, address).await {
+                Ok(fee_bps) => Message::ReceivedPayoutFee(fee_bps),
+                Err(err) => Message::Error(err),
+            }
+        });
+
+        let token_service = self.token_service.clone().unwrap();
+        let (contract, address) = (self.contract.clone(), account.address.clone());
+        ctx.link().send_future(async move {
+            match token_service.get_emission_state(contract, address).await {
+                Ok((state, chart)) => Message::ReceivedEmissionState(state, chart),
+                Err(err) => Message::Error(err),
+            }
+        });
+    }
+
+    /// dry-runs `call` and prepares it for the fee confirmation, unless the dry-run fails
+    fn preview_call(&mut self, ctx: &Context<Self>, call: TokenCall) {
+        let account = self.account.clone().unwrap();
+        let contract = self.contract.clone();
+        let token_service = self.token_service.clone().unwrap();
+        self.blocked_simulation = None;
+
+        ctx.link().send_future(async move {
+            let title = call.to_string();
+            match token_service
+                .preview_token_call(contract, account.source, account.address, call)
+                .await
+            {
+                Ok(PreviewedCall::Blocked(simulation)) => Message::SimulationBlocked(simulation),
+                Ok(PreviewedCall::Prepared {
+                    simulation,
+                    prepared,
+                    fees,
+                }) => Message::TransactionPrepared(PendingTransaction {
+                    title,
+                    prepared,
+                    fees,
+                    simulation: Some(simulation),
+                    payouts: None,
+                }),
+                Err(err) => Message::Error(err),
+            }
+        });
+    }
 }
 
 pub enum TokenStage {
@@ -42,13 +158,51 @@ pub enum Message {
     Error(anyhow::Error),
     OnlineClientCreated(OnlineClient<PolkadotConfig>),
     ChangeContract(String),
+    Unwatch(String),
     TokenServiceCreated(TokenService),
     RequestAccounts,
     ReceivedAccounts(Vec<Account>),
     SignWithAccount(usize),
     RequestBalance,
     ReceivedBalance(u128),
-    SendToken(String, u128),
+    ReceivedVestingSchedule(Option<VestingSchedule>),
+    ReleaseVesting,
+    ReceivedVotingPower(VotingPower),
+    Delegate(String),
+    ReceivedFaucetState(FaucetState),
+    ReceivedPayoutFee(u16),
+    ClaimFaucet,
+    ReceivedEmissionState(EmissionState, Vec<SupplyPoint>),
+    MintEmissions,
+    SendToken(TokenAction, String, u128),
+    /// the dry-run returned an error, the call is not prepared
+    SimulationBlocked(Simulation),
+    DismissSimulation,
+    /// spender, value and minutes the permit is valid
+    SignPermit(String, u128, u64),
+    PermitSigned(SignedPermit),
+    /// submits a permit signed by another account with the selected account
+    RelayPermit(SignedPermit),
+    SendPayouts(PayoutBatch),
+    /// the transaction has a reserved nonce and waits for the user to confirm its fees
+    TransactionPrepared(PendingTransaction),
+    /// usize represents the index in the pending transactions
+    ConfirmTransaction(usize),
+    CancelTransaction(usize),
+    PayoutsSubmitted(Vec<RowResult>),
+    /// hash of a confirmed transaction once it is finalized
+    TransactionFinalized(String),
+}
+
+/// a prepared transaction waiting for the user to confirm its fees
+pub struct PendingTransaction {
+    title: String,
+    prepared: PreparedExtrinsic,
+    fees: FeeEstimate,
+    /// dry-run of the call, `None` for batch transfers
+    simulation: Option<Simulation>,
+    /// number of payouts if this is a batch transfer
+    payouts: Option<usize>,
 }
 
 impl Component for TokenComponent {
@@ -75,6 +229,19 @@ impl Component for TokenComponent {
             stage: TokenStage::EnterContract,
             online_client: None,
             token_service: None,
+            payout_results: None,
+            submitting_payouts: false,
+            pending_transactions: Vec::new(),
+            finalized_transaction: None,
+            blocked_simulation: None,
+            signed_permit: None,
+            vesting_schedule: None,
+            voting_power: None,
+            faucet_state: None,
+            payout_fee_bps: 0,
+            emission_state: None,
+            supply_chart: Vec::new(),
+            watchlist: load_watchlist(),
         }
     }
 
@@ -87,13 +254,15 @@ impl Component for TokenComponent {
             Message::ChangeContract(contract) => {
                 self.set_contract(contract);
             }
+            Message::Unwatch(address) => {
+                self.watchlist = unwatch(&address);
+            }
             Message::TokenServiceCreated(service) => {
                 self.token_service = Some(service);
             }
             Message::SignWithAccount(i) => {
                 if let TokenStage::SelectAccount(accounts) = &self.stage {
                     let account = accounts.get(i).unwrap();
-                    let account_address = account.address.clone();
                     self.account = Some(account.clone());
                     let contract = self.contract.clone();
                     let token_service = self.token_service.clone().unwrap();
@@ -135,6 +304,40 @@ impl Component for TokenComponent {
             Message::ReceivedBalance(balance) => {
                 self.balance = Some(balance);
                 self.stage = TokenStage::DisplayBalance(balance);
+                self.request_account_state(ctx);
+            }
+            Message::ReceivedVestingSchedule(schedule) => {
+                self.vesting_schedule = schedule;
+            }
+            Message::ReleaseVesting => {
+                self.preview_call(ctx, TokenCall::Release);
+            }
+            Message::ReceivedVotingPower(power) => {
+                self.voting_power = Some(power);
+            }
+            Message::Delegate(delegatee) => {
+                let Ok(delegatee) = AccountId32::from_str(&delegatee) else {
+                    ctx.link()
+                        .send_message(Message::Error(anyhow!("Invalid address: {delegatee}")));
+                    return false;
+                };
+                self.preview_call(ctx, TokenCall::Delegate { delegatee });
+            }
+            Message::ReceivedFaucetState(state) => {
+                self.faucet_state = Some(state);
+            }
+            Message::ClaimFaucet => {
+                self.preview_call(ctx, TokenCall::ClaimFaucet);
+            }
+            Message::ReceivedPayoutFee(fee_bps) => {
+                self.payout_fee_bps = fee_bps;
+            }
+            Message::ReceivedEmissionState(state, chart) => {
+                self.emission_state = Some(state);
+                self.supply_chart = chart;
+            }
+            Message::MintEmissions => {
+                self.preview_call(ctx, TokenCall::MintEmissions);
             }
             Message::Error(err) => self.stage = TokenStage::Error(err.to_string()),
             Message::RequestAccounts => {
@@ -149,32 +352,142 @@ impl Component for TokenComponent {
             Message::ReceivedAccounts(accounts) => {
                 self.stage = TokenStage::SelectAccount(accounts);
             }
-            Message::SendToken(to_address, amount) => {
+            Message::SendToken(action, address, amount) => {
+                let Ok(address) = AccountId32::from_str(&address) else {
+                    ctx.link()
+                        .send_message(Message::Error(anyhow!("Invalid address: {address}")));
+                    return false;
+                };
+                let call = match action {
+                    TokenAction::Transfer => TokenCall::Transfer {
+                        to: address,
+                        value: amount,
+                    },
+                    TokenAction::Mint => TokenCall::Mint {
+                        account: address,
+                        amount,
+                    },
+                    TokenAction::Approve => TokenCall::Approve {
+                        spender: address,
+                        value: amount,
+                    },
+                };
+                self.preview_call(ctx, call);
+            }
+            Message::SignPermit(spender, value, valid_minutes) => {
                 let account = self.account.clone().unwrap();
-                let account_source = account.source.clone();
-                let account_address = account.address.clone();
                 let contract = self.contract.clone();
                 let token_service = self.token_service.clone().unwrap();
+                let deadline = js_sys::Date::now() as u64 + valid_minutes * 60_000;
+                self.signed_permit = None;
 
                 ctx.link().send_future(async move {
                     match token_service
-                        .transfer_tokens(
+                        .sign_permit(contract, account, spender, value, deadline)
+                        .await
+                    {
+                        Ok(permit) => Message::PermitSigned(permit),
+                        Err(err) => Message::Error(err),
+                    }
+                });
+            }
+            Message::PermitSigned(permit) => {
+                self.signed_permit = Some(permit.to_hex());
+            }
+            Message::RelayPermit(permit) => {
+                self.preview_call(ctx, permit.call());
+            }
+            Message::SimulationBlocked(simulation) => {
+                self.blocked_simulation = Some(simulation);
+            }
+            Message::DismissSimulation => {
+                self.blocked_simulation = None;
+            }
+            Message::SendPayouts(batch) => {
+                let account = self.account.clone().unwrap();
+                let contract = self.contract.clone();
+                let token_service = self.token_service.clone().unwrap();
+                let title = format!("Send {} payouts", batch.rows.len());
+                self.payout_results = None;
+                self.submitting_payouts = true;
+
+                ctx.link().send_future(async move {
+                    match token_service
+                        .prepare_batch_transfer(
                             contract,
-                            account_source,
+                            account.source,
                             account.address,
-                            to_address,
-                            amount,
+                            &batch.rows,
                         )
                         .await
                     {
-                        Ok(result) => {
-                            web_sys::console::log_1(&format!("Result: {:?}", result).into());
-                            Message::RequestBalance
-                        }
-                        Err(err) => Message::Error(err),
+                        Ok((prepared, fees)) => Message::TransactionPrepared(PendingTransaction {
+                            title,
+                            prepared,
+                            fees,
+                            simulation: None,
+                            payouts: Some(batch.rows.len()),
+                        }),
+                        Err(err) => Message::PayoutsSubmitted(
+                            batch
+                                .rows
+                                .iter()
+                                .map(|_| RowResult::Failed(err.to_string()))
+                                .collect(),
+                        ),
                     }
                 });
             }
+            Message::TransactionPrepared(pending) => {
+                self.pending_transactions.push(pending);
+            }
+            Message::ConfirmTransaction(i) => {
+                if i >= self.pending_transactions.len() {
+                    return false;
+                }
+                let pending = self.pending_transactions.remove(i);
+                let token_service = self.token_service.clone().unwrap();
+
+                ctx.link().send_future(async move {
+                    match pending.payouts {
+                        Some(rows) => Message::PayoutsSubmitted(
+                            token_service
+                                .submit_batch_transfer(pending.prepared, rows)
+                                .await,
+                        ),
+                        None => match pending
+                            .prepared
+                            .sign_and_submit(&token_service.client)
+                            .await
+                        {
+                            Ok(events) => Message::TransactionFinalized(format!(
+                                "{:?}",
+                                events.extrinsic_hash()
+                            )),
+                            Err(err) => Message::Error(err),
+                        },
+                    }
+                });
+            }
+            Message::CancelTransaction(i) => {
+                if i >= self.pending_transactions.len() {
+                    return false;
+                }
+                let pending = self.pending_transactions.remove(i);
+                pending.prepared.release_nonce();
+                if pending.payouts.is_some() {
+                    self.submitting_payouts = false;
+                }
+            }
+            Message::PayoutsSubmitted(results) => {
+                self.payout_results = Some(results);
+                self.submitting_payouts = false;
+                ctx.link().send_message(Message::RequestBalance);
+            }
+            Message::TransactionFinalized(hash) => {
+                self.finalized_transaction = Some(hash);
+                ctx.link().send_message(Message::RequestBalance);
+            }
         }
         true
     }
@@ -202,12 +515,91 @@ impl Component for TokenComponent {
         };
         let send_token_html: Html = match &self.stage {
             TokenStage::RequestingBalance | TokenStage::DisplayBalance(_) => {
-                let send_callback = ctx
-                    .link()
-                    .callback(|(to_address, amount)| Message::SendToken(to_address, amount));
+                let send_callback = ctx.link().callback(|(action, address, amount)| {
+                    Message::SendToken(action, address, amount)
+                });
+                let payouts_callback = ctx.link().callback(Message::SendPayouts);
+                let sign_permit_callback =
+                    ctx.link().callback(|(spender, value, valid_minutes)| {
+                        Message::SignPermit(spender, value, valid_minutes)
+                    });
+                let relay_permit_callback = ctx.link().callback(Message::RelayPermit);
+                let release_callback = ctx.link().callback(|_| Message::ReleaseVesting);
+                let delegate_callback = ctx.link().callback(Message::Delegate);
+                let claim_faucet_callback = ctx.link().callback(|_| Message::ClaimFaucet);
+                let mint_emissions_callback = ctx.link().callback(|_| Message::MintEmissions);
+                let queued_nonces = self
+                    .account
+                    .as_ref()
+                    .and_then(|account| AccountId32::from_str(&account.address).ok())
+                    .map(|account_id| NonceManager::shared().pending(&account_id))
+                    .unwrap_or_default();
                 html!(
                     <>
                         <SendTokenComponent onsend = {send_callback}/>
+                        if let Some(simulation) = &self.blocked_simulation {
+                            <SimulationComponent simulation={simulation.clone()}/>
+                            <button class="mb" onclick={ctx.link().callback(|_| Message::DismissSimulation)}>{"Dismiss"}</button>
+                        }
+                        { for self.pending_transactions.iter().enumerate().map(|(i, pending)| {
+                            let onconfirm = ctx.link().callback(move |_| Message::ConfirmTransaction(i));
+                            let oncancel = ctx.link().callback(move |_| Message::CancelTransaction(i));
+                            html! {
+                                <>
+                                    if let Some(simulation) = &pending.simulation {
+                                        <SimulationComponent simulation={simulation.clone()}/>
+                                    }
+                                    <FeeConfirmationComponent
+                                        title={pending.title.clone()}
+                                        fees={pending.fees.clone()}
+                                        {onconfirm}
+                                        {oncancel}
+                                    />
+                                </>
+                            }
+                        }) }
+                        if !queued_nonces.is_empty() {
+                            <div class="mb loading">
+                                {"Queued transactions (nonces): "}
+                                {queued_nonces.iter().map(|nonce| nonce.to_string()).collect::<Vec<_>>().join(", ")}
+                            </div>
+                        }
+                        if let Some(hash) = &self.finalized_transaction {
+                            <div class="mb">
+                                {"Transaction finalized: "}<small>{hash}</small>
+                            </div>
+                        }
+                        <FaucetComponent
+                            state={self.faucet_state.clone()}
+                            now={js_sys::Date::now() as u64}
+                            onclaim={claim_faucet_callback}
+                        />
+                        <EmissionsComponent
+                            state={self.emission_state.clone()}
+                            chart={self.supply_chart.clone()}
+                            onmint={mint_emissions_callback}
+                        />
+                        <VestingComponent
+                            schedule={self.vesting_schedule.clone()}
+                            now={js_sys::Date::now() as u64}
+                            onrelease={release_callback}
+                        />
+                        <DelegationComponent
+                            voting_power={self.voting_power.clone()}
+                            ondelegate={delegate_callback}
+                        />
+                        <PermitComponent
+                            signed_permit={self.signed_permit.clone().map(AttrValue::from)}
+                            onsign={sign_permit_callback}
+                            onrelay={relay_permit_callback}
+                        />
+                        <PayoutsComponent
+                            balance={self.balance}
+                            transfer_fee_bps={self.payout_fee_bps}
+                            results={self.payout_results.clone()}
+                            submitting={self.submitting_payouts}
+                            onsubmit={payouts_callback}
+                        />
                     </>
                 )
             }
@@ -280,6 +672,12 @@ impl Component for TokenComponent {
             <div>
                 <Link<Route> to={Route::Home}> <button>{"<= Back"}</button></Link<Route>>
                 <h1>{"Token Management"}</h1>
+                <WatchlistComponent
+                    tokens={self.watchlist.clone()}
+                    selected={self.contract.clone()}
+                    onselect={ctx.link().callback(Message::ChangeContract)}
+                    onremove={ctx.link().callback(Message::Unwatch)}
+                />
                 {contract_html}
                 {stage_html}
                 {send_token_html}
@@ -0,0 +1,239 @@
+use anyhow::anyhow;
+use futures::FutureExt;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+use yew_router::prelude::Link;
+
+use crate::components::{FeeConfirmationComponent, WrapComponent};
+use crate::services::fees::FeeEstimate;
+use crate::services::wrapped_native::{WrapCall, WrapState};
+use crate::services::{
+    get_accounts, Account, PendingCall, PreparedCall, PreparedExtrinsic, TokenService,
+};
+use crate::Route;
+
+/// wraps and unwraps the native currency of the selected account
+pub struct WrapPageComponent {
+    contract: String,
+    stage: WrapStage,
+    token_service: Option<TokenService>,
+    account: Option<Account>,
+    /// `None` while loading
+    state: Option<WrapState>,
+    pending: PendingCall<WrapCall>,
+}
+
+pub enum WrapStage {
+    Error(String),
+    CreatingTokenService,
+    EnterAccount,
+    RequestingAccounts,
+    SelectAccount(Vec<Account>),
+    Ready,
+}
+
+pub enum Message {
+    Error(anyhow::Error),
+    TokenServiceCreated(TokenService),
+    ChangeContract(String),
+    RequestAccounts,
+    ReceivedAccounts(Vec<Account>),
+    SelectAccount(usize),
+    RequestState,
+    ReceivedState(WrapState),
+    Call(WrapCall),
+    CallPrepared(WrapCall, PreparedExtrinsic, FeeEstimate),
+    Confirm,
+    Cancel,
+}
+
+impl Component for WrapPageComponent {
+    type Message = Message;
+
+    type Properties = ();
+
+    fn create(ctx: &Context<Self>) -> Self {
+        ctx.link()
+            .send_future(TokenService::new().map(|res| match res {
+                Ok(service) => Message::TokenServiceCreated(service),
+                Err(err) => Message::Error(anyhow!("Failed to create TokenService: {}", err)),
+            }));
+        WrapPageComponent {
+            contract: String::new(),
+            stage: WrapStage::CreatingTokenService,
+            token_service: None,
+            account: None,
+            state: None,
+            pending: PendingCall::default(),
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Message::Error(err) => self.stage = WrapStage::Error(err.to_string()),
+            Message::TokenServiceCreated(service) => {
+                self.token_service = Some(service);
+                self.stage = WrapStage::EnterAccount;
+            }
+            Message::ChangeContract(contract) => {
+                self.contract = contract;
+                self.state = None;
+                ctx.link().send_message(Message::RequestState);
+            }
+            Message::RequestAccounts => {
+                self.stage = WrapStage::RequestingAccounts;
+                ctx.link().send_future(get_accounts().map(
+                    |accounts_or_err| match accounts_or_err {
+                        Ok(accounts) => Message::ReceivedAccounts(accounts),
+                        Err(err) => Message::Error(err),
+                    },
+                ));
+            }
+            Message::ReceivedAccounts(accounts) => {
+                self.stage = WrapStage::SelectAccount(accounts);
+            }
+            Message::SelectAccount(i) => {
+                if let WrapStage::SelectAccount(accounts) = &self.stage {
+                    self.account = accounts.get(i).cloned();
+                    self.stage = WrapStage::Ready;
+                    ctx.link().send_message(Message::RequestState);
+                }
+            }
+            Message::RequestState => {
+                let Some(account) = &self.account else {
+                    return false;
+                };
+                if self.contract.is_empty() {
+                    return false;
+                }
+                let Some(token_service) = self.token_service.clone() else {
+                    return false;
+                };
+                let (contract, address) = (self.contract.clone(), account.address.clone());
+                ctx.link().send_future(async move {
+                    match token_service.get_wrap_state(contract, address).await {
+                        Ok(state) => Message::ReceivedState(state),
+                        Err(err) => Message::Error(err),
+                    }
+                });
+            }
+            Message::ReceivedState(state) => {
+                self.state = Some(state);
+            }
+            Message::Call(call) => {
+                let Some(account) = self.account.clone() else {
+                    return false;
+                };
+                let Some(token_service) = self.token_service.clone() else {
+                    return false;
+                };
+                let contract = self.contract.clone();
+                ctx.link().send_future(async move {
+                    match token_service
+                        .prepare_wrap_call(contract, account.source, account.address, &call)
+                        .await
+                    {
+                        Ok((prepared, fees)) => Message::CallPrepared(call, prepared, fees),
+                        Err(err) => Message::Error(err),
+                    }
+                });
+            }
+            Message::CallPrepared(call, prepared, fees) => {
+                self.pending.replace(PreparedCall {
+                    call,
+                    prepared,
+                    fees,
+                });
+            }
+            Message::Confirm => {
+                return self.pending.confirm(
+                    ctx,
+                    self.token_service.as_ref(),
+                    Message::RequestState,
+                    Message::Error,
+                );
+            }
+            Message::Cancel => self.pending.cancel(),
+        }
+        true
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let on_contract_input = ctx.link().callback(move |event: InputEvent| {
+            let input_element = event.target_dyn_into::<HtmlInputElement>().unwrap();
+            Message::ChangeContract(input_element.value())
+        });
+
+        let stage_html: Html = match &self.stage {
+            WrapStage::Error(error_message) => {
+                html!(<div class="error"> {"Error: "} {error_message} </div>)
+            }
+            WrapStage::CreatingTokenService => {
+                html!(<div>{"Creating Online Client..."}</div>)
+            }
+            WrapStage::EnterAccount => {
+                let get_accounts_click = ctx.link().callback(|_| Message::RequestAccounts);
+                html!(
+                    <div>
+                        <button onclick={get_accounts_click}> {"=> Select an Account"} </button>
+                    </div>
+                )
+            }
+            WrapStage::RequestingAccounts => {
+                html!(<div>{"Querying extensions for accounts..."}</div>)
+            }
+            WrapStage::SelectAccount(accounts) => {
+                if accounts.is_empty() {
+                    html!(<div>{"No Web3 extension accounts found. Install Talisman or the Polkadot.js extension and add an account."}</div>)
+                } else {
+                    html!(
+                        <>
+                            <div class="mb"><b>{"Select an account you want to use for signing:"}</b></div>
+                            { for accounts.iter().enumerate().map(|(i, account)| {
+                                let select_account = ctx.link().callback(move |_| Message::SelectAccount(i));
+                                html! {
+                                    <button onclick={select_account}>
+                                        {&account.source} {" | "} {&account.name}<br/>
+                                        <small>{&account.address}</small>
+                                    </button>
+                                }
+                            }) }
+                        </>
+                    )
+                }
+            }
+            WrapStage::Ready => {
+                if self.contract.is_empty() {
+                    html!(<></>)
+                } else {
+                    html! {
+                        <>
+                            <WrapComponent
+                                state={self.state.clone()}
+                                oncall={ctx.link().callback(Message::Call)}
+                            />
+                            if let Some(pending) = self.pending.get() {
+                                <FeeConfirmationComponent
+                                    title={pending.call.to_string()}
+                                    fees={pending.fees.clone()}
+                                    onconfirm={ctx.link().callback(|_| Message::Confirm)}
+                                    oncancel={ctx.link().callback(|_| Message::Cancel)}
+                                />
+                            }
+                        </>
+                    }
+                }
+            }
+        };
+
+        html! {
+            <div>
+                <Link<Route> to={Route::Home}> <button>{"<= Back"}</button></Link<Route>>
+                <h1>{"Wrap Native"}</h1>
+                <div class="mb"><b>{"Enter Wrapped Native Contract:"}</b></div>
+                <input oninput={on_contract_input} class="mb" value={AttrValue::from(self.contract.clone())}/>
+                {stage_html}
+            </div>
+        }
+    }
+}
@@ -0,0 +1,145 @@
+use anyhow::anyhow;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fmt;
+use subxt::ext::codec::Decode;
+use subxt::utils::AccountId32;
+use subxt::{OnlineClient, PolkadotConfig};
+
+use crate::services::contracts_api::{dry_run_call, query};
+use crate::services::psp22::{encode_call_data, LangError, PSP22Error};
+
+/// selector of `Airdrop::claim`
+pub const CLAIM_SELECTOR: [u8; 4] = [0xb3, 0x88, 0x80, 0x3f];
+/// selector of `Airdrop::is_claimed`
+pub const IS_CLAIMED_SELECTOR: [u8; 4] = [0xc5, 0x8e, 0xed, 0x46];
+
+/// `AirdropError` of the airdrop contract
+#[derive(Clone, Debug, Decode, PartialEq)]
+pub enum AirdropError {
+    AlreadyClaimed,
+    InvalidProof,
+    TransferFailed(PSP22Error),
+}
+
+impl fmt::Display for AirdropError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AirdropError::TransferFailed(err) => write!(f, "TransferFailed({err})"),
+            error => write!(f, "{error:?}"),
+        }
+    }
+}
+
+/// the amount and the Merkle proof of one recipient
+#[derive(Clone, Debug, PartialEq)]
+pub struct AirdropClaim {
+    pub amount: u128,
+    pub proof: Vec<[u8; 32]>,
+}
+
+impl AirdropClaim {
+    pub fn call_data(&self) -> Vec<u8> {
+        encode_call_data(CLAIM_SELECTOR, (self.amount, &self.proof))
+    }
+}
+
+/// the JSON printed by `airdrop_tool`
+#[derive(Deserialize)]
+struct AirdropFile {
+    claims: BTreeMap<String, ClaimEntry>,
+}
+
+#[derive(Deserialize)]
+struct ClaimEntry {
+    amount: String,
+    proof: Vec<String>,
+}
+
+/// the claim of `address` in the output of `airdrop_tool`, `None` if the airdrop has none for it
+pub fn parse_claim(json: &str, address: &str) -> Result<Option<AirdropClaim>, anyhow::Error> {
+    let file: AirdropFile = serde_json::from_str(json)?;
+    let Some(entry) = file.claims.get(address) else {
+        return Ok(None);
+    };
+    let proof = entry
+        .proof
+        .iter()
+        .map(|hash| {
+            hex::decode(hash.trim_start_matches("0x"))?
+                .try_into()
+                .map_err(|_| anyhow!("Invalid proof hash: {hash}"))
+        })
+        .collect::<Result<Vec<[u8; 32]>, anyhow::Error>>()?;
+    Ok(Some(AirdropClaim {
+        amount: entry.amount.parse()?,
+        proof,
+    }))
+}
+
+pub async fn fetch_is_claimed(
+    api: &OnlineClient<PolkadotConfig>,
+    airdrop: &AccountId32,
+    account: &AccountId32,
+) -> Result<bool, anyhow::Error> {
+    query::<Result<bool, LangError>>(
+        api,
+        account,
+        airdrop,
+        encode_call_data(IS_CLAIMED_SELECTOR, account),
+    )
+    .await?
+    .map_err(|err| anyhow!("{err:?}"))
+}
+
+/// dry-runs the claim of `account`, `Err` with the reason if it would fail
+pub async fn dry_run_claim(
+    api: &OnlineClient<PolkadotConfig>,
+    airdrop: &AccountId32,
+    account: &AccountId32,
+    claim: &AirdropClaim,
+) -> Result<Result<(), String>, anyhow::Error> {
+    let dry_run = dry_run_call(api, account, airdrop, 0, claim.call_data()).await?;
+    let data = match dry_run.return_data() {
+        Ok(data) => data,
+        Err(err) => return Ok(Err(err)),
+    };
+    Ok(
+        match Result::<Result<(), AirdropError>, LangError>::decode(&mut &data[..]) {
+            Ok(Ok(Ok(()))) => Ok(()),
+            Ok(Ok(Err(err))) => Err(format!("AirdropError::{err}")),
+            Ok(Err(err)) => Err(format!("LangError::{err:?}")),
+            Err(err) => Err(format!("Could not decode return value: {err}")),
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const JSON: &str = r#"{
+        "root": "0x00",
+        "total": "100",
+        "claims": {
+            "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY": {
+                "amount": "100",
+                "proof": ["0x0101010101010101010101010101010101010101010101010101010101010101"]
+            }
+        }
+    }"#;
+
+    #[wasm_bindgen_test::wasm_bindgen_test]
+    fn parses_claim_of_account() {
+        let claim = parse_claim(JSON, "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY")
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(claim.amount, 100);
+        assert_eq!(claim.proof, vec![[1; 32]]);
+        assert_eq!(
+            parse_claim(JSON, "5FHneW46xGXgs5mUiveU4sbTyGBzmstUspZC92UhjJM694ty").unwrap(),
+            None
+        );
+    }
+}
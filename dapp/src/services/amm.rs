@@ -0,0 +1,166 @@
+use anyhow::anyhow;
+use std::fmt;
+use subxt::ext::codec::Decode;
+use subxt::utils::AccountId32;
+use subxt::{OnlineClient, PolkadotConfig};
+
+use crate::services::contracts_api::{dry_run_call, query};
+use crate::services::psp22::{encode_call_data, LangError, PSP22Error};
+
+/// selector of `Pair::swap`
+pub const SWAP_SELECTOR: [u8; 4] = [0x11, 0x00, 0x4f, 0xa6];
+/// selector of `Pair::get_amount_out`
+pub const GET_AMOUNT_OUT_SELECTOR: [u8; 4] = [0xa8, 0x54, 0x49, 0x16];
+/// selector of `Pair::reserves`
+pub const RESERVES_SELECTOR: [u8; 4] = [0xf3, 0x53, 0x5d, 0xf9];
+/// selector of `Pair::tokens`
+pub const PAIR_TOKENS_SELECTOR: [u8; 4] = [0xa8, 0xb5, 0xb8, 0xba];
+
+/// slippage tolerances are given in basis points of the quote
+pub const SLIPPAGE_DENOMINATOR: u128 = 10_000;
+
+/// `PairError` of the pair contract
+#[derive(Clone, Debug, Decode, PartialEq)]
+pub enum PairError {
+    UnknownToken,
+    InsufficientAmount,
+    InsufficientLiquidity,
+    SlippageExceeded,
+    Overflow,
+    Locked,
+    ProductDecreased,
+    PSP22(PSP22Error),
+}
+
+impl fmt::Display for PairError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PairError::PSP22(err) => write!(f, "PSP22({err})"),
+            error => write!(f, "{error:?}"),
+        }
+    }
+}
+
+/// the tokens and reserves of a pair
+#[derive(Clone, Debug, PartialEq)]
+pub struct PairState {
+    pub token_0: AccountId32,
+    pub token_1: AccountId32,
+    pub reserve_0: u128,
+    pub reserve_1: u128,
+}
+
+impl PairState {
+    /// the token received for `token_in`
+    pub fn other_token(&self, token_in: &AccountId32) -> &AccountId32 {
+        if *token_in == self.token_0 {
+            &self.token_1
+        } else {
+            &self.token_0
+        }
+    }
+}
+
+/// a swap of `amount_in` of `token_in`, reverted if the pair pays less than `min_amount_out`
+#[derive(Clone, Debug, PartialEq)]
+pub struct SwapCall {
+    pub token_in: AccountId32,
+    pub amount_in: u128,
+    pub min_amount_out: u128,
+}
+
+impl SwapCall {
+    pub fn call_data(&self) -> Vec<u8> {
+        encode_call_data(
+            SWAP_SELECTOR,
+            (&self.token_in, self.amount_in, self.min_amount_out),
+        )
+    }
+}
+
+/// the least output accepted for `quote` with a tolerance of `slippage_bps`
+pub fn min_amount_out(quote: u128, slippage_bps: u128) -> u128 {
+    let tolerance = slippage_bps.min(SLIPPAGE_DENOMINATOR);
+    quote
+        .checked_mul(SLIPPAGE_DENOMINATOR - tolerance)
+        .map(|scaled| scaled / SLIPPAGE_DENOMINATOR)
+        .unwrap_or(quote / SLIPPAGE_DENOMINATOR * (SLIPPAGE_DENOMINATOR - tolerance))
+}
+
+pub async fn fetch_pair_state(
+    api: &OnlineClient<PolkadotConfig>,
+    pair: &AccountId32,
+    origin: &AccountId32,
+) -> Result<PairState, anyhow::Error> {
+    let (token_0, token_1) = query::<Result<(AccountId32, AccountId32), LangError>>(
+        api,
+        origin,
+        pair,
+        PAIR_TOKENS_SELECTOR.to_vec(),
+    )
+    .await?
+    .map_err(|err| anyhow!("{err:?}"))?;
+    let (reserve_0, reserve_1) =
+        query::<Result<(u128, u128), LangError>>(api, origin, pair, RESERVES_SELECTOR.to_vec())
+            .await?
+            .map_err(|err| anyhow!("{err:?}"))?;
+    Ok(PairState {
+        token_0,
+        token_1,
+        reserve_0,
+        reserve_1,
+    })
+}
+
+/// the output of swapping `amount_in` of `token_in` at the current reserves
+pub async fn fetch_quote(
+    api: &OnlineClient<PolkadotConfig>,
+    pair: &AccountId32,
+    origin: &AccountId32,
+    token_in: &AccountId32,
+    amount_in: u128,
+) -> Result<Result<u128, PairError>, anyhow::Error> {
+    query::<Result<Result<u128, PairError>, LangError>>(
+        api,
+        origin,
+        pair,
+        encode_call_data(GET_AMOUNT_OUT_SELECTOR, (token_in, amount_in)),
+    )
+    .await?
+    .map_err(|err| anyhow!("{err:?}"))
+}
+
+/// dry-runs the swap as `account`, the error tells why the swap would fail
+pub async fn dry_run_swap(
+    api: &OnlineClient<PolkadotConfig>,
+    pair: &AccountId32,
+    account: &AccountId32,
+    swap: &SwapCall,
+) -> Result<Result<u128, String>, anyhow::Error> {
+    let dry_run = dry_run_call(api, account, pair, 0, swap.call_data()).await?;
+    let data = match dry_run.return_data() {
+        Ok(data) => data,
+        Err(err) => return Ok(Err(err)),
+    };
+    Ok(
+        match Result::<Result<u128, PairError>, LangError>::decode(&mut &data[..]) {
+            Ok(Ok(Ok(amount_out))) => Ok(amount_out),
+            Ok(Ok(Err(err))) => Err(format!("PairError::{err}")),
+            Ok(Err(err)) => Err(format!("LangError::{err:?}")),
+            Err(err) => Err(format!("Could not decode return value: {err}")),
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[wasm_bindgen_test::wasm_bindgen_test]
+    fn min_amount_out_applies_the_tolerance() {
+        assert_eq!(min_amount_out(1_000, 50), 995);
+        assert_eq!(min_amount_out(1_000, 0), 1_000);
+        assert_eq!(min_amount_out(1_000, 20_000), 0);
+        assert_eq!(min_amount_out(u128::MAX, 100), u128::MAX / 10_000 * 9_900);
+    }
+}
@@ -0,0 +1,52 @@
+use anyhow::anyhow;
+use subxt::utils::AccountId32;
+use subxt::{OnlineClient, PolkadotConfig};
+
+use crate::services::contracts_api::query;
+use crate::services::psp22::{
+    encode_call_data, LangError, FROZEN_ACCOUNTS_SELECTOR, FROZEN_COUNT_SELECTOR, OWNER_SELECTOR,
+};
+
+/// `MAX_PAGE_LEN` of the token contract
+const PAGE_LEN: u32 = 50;
+
+/// accounts which can neither send nor receive tokens
+pub async fn fetch_frozen_accounts(
+    api: &OnlineClient<PolkadotConfig>,
+    contract: &AccountId32,
+    caller: &AccountId32,
+) -> Result<Vec<AccountId32>, anyhow::Error> {
+    let count =
+        query::<Result<u32, LangError>>(api, caller, contract, FROZEN_COUNT_SELECTOR.to_vec())
+            .await?
+            .map_err(|err| anyhow!("{err:?}"))?;
+    let mut accounts = Vec::new();
+    for start in (0..count).step_by(PAGE_LEN as usize) {
+        let page = query::<Result<Vec<AccountId32>, LangError>>(
+            api,
+            caller,
+            contract,
+            encode_call_data(FROZEN_ACCOUNTS_SELECTOR, (start, PAGE_LEN)),
+        )
+        .await?
+        .map_err(|err| anyhow!("{err:?}"))?;
+        accounts.extend(page);
+    }
+    Ok(accounts)
+}
+
+/// the owner of the token, `None` if ownership was renounced
+pub async fn fetch_owner(
+    api: &OnlineClient<PolkadotConfig>,
+    contract: &AccountId32,
+    caller: &AccountId32,
+) -> Result<Option<AccountId32>, anyhow::Error> {
+    query::<Result<Option<AccountId32>, LangError>>(
+        api,
+        caller,
+        contract,
+        encode_call_data(OWNER_SELECTOR, ()),
+    )
+    .await?
+    .map_err(|err| anyhow!("{err:?}"))
+}
@@ -0,0 +1,156 @@
+use crate::services::polkadot::runtime_types::contracts_node_runtime::RuntimeEvent;
+use crate::services::polkadot::runtime_types::frame_system::EventRecord;
+use crate::services::polkadot::runtime_types::pallet_contracts::pallet::Event as ContractsEvent;
+use crate::services::polkadot::runtime_types::sp_runtime::DispatchError;
+use crate::services::polkadot::runtime_types::sp_weights::weight_v2::Weight;
+use subxt::ext::codec::{Decode, Encode, Input};
+use subxt::utils::{AccountId32, H256};
+use subxt::{OnlineClient, PolkadotConfig};
+
+/// arguments of the `ContractsApi_call` runtime api
+#[derive(Encode)]
+struct CallRequest {
+    origin: AccountId32,
+    dest: AccountId32,
+    value: u128,
+    gas_limit: Option<Weight>,
+    storage_deposit_limit: Option<u128>,
+    input_data: Vec<u8>,
+}
+
+/// storage deposit of a contract call, charged from or refunded to the caller
+#[derive(Clone, Debug, Decode, PartialEq)]
+pub enum StorageDeposit {
+    Refund(u128),
+    Charge(u128),
+}
+
+impl StorageDeposit {
+    pub fn charge(&self) -> u128 {
+        match self {
+            StorageDeposit::Refund(_) => 0,
+            StorageDeposit::Charge(amount) => *amount,
+        }
+    }
+}
+
+/// data returned by the contract
+#[derive(Debug, Decode)]
+pub struct ExecReturnValue {
+    pub flags: u32,
+    pub data: Vec<u8>,
+}
+
+/// the fields of `ContractExecResult` in front of the events
+#[derive(Decode)]
+struct ContractExecResultHead {
+    gas_consumed: Weight,
+    gas_required: Weight,
+    storage_deposit: StorageDeposit,
+    debug_message: Vec<u8>,
+    result: Result<ExecReturnValue, DispatchError>,
+}
+
+/// the undecoded rest of the runtime api response
+struct Remaining(Vec<u8>);
+
+impl Decode for Remaining {
+    fn decode<I: Input>(input: &mut I) -> Result<Self, subxt::ext::codec::Error> {
+        let mut bytes = vec![0; input.remaining_len()?.unwrap_or_default()];
+        input.read(&mut bytes)?;
+        Ok(Remaining(bytes))
+    }
+}
+
+/// an event emitted by a contract via `ContractEmitted`
+#[derive(Clone, Debug, PartialEq)]
+pub struct ContractEvent {
+    pub contract: AccountId32,
+    pub data: Vec<u8>,
+}
+
+/// result of a dry-run of a contract call
+#[derive(Debug)]
+pub struct ContractDryRun {
+    pub gas_consumed: Weight,
+    pub gas_required: Weight,
+    pub storage_deposit: StorageDeposit,
+    pub debug_message: Vec<u8>,
+    pub result: Result<ExecReturnValue, DispatchError>,
+    /// events emitted by contracts, empty if the node does not collect events in dry-runs
+    pub contract_events: Vec<ContractEvent>,
+}
+
+impl ContractDryRun {
+    fn from_bytes(bytes: &[u8]) -> Result<Self, subxt::ext::codec::Error> {
+        let mut input = bytes;
+        let head = ContractExecResultHead::decode(&mut input)?;
+        // events of pallets unknown to the static metadata can't be decoded, those dry-runs have no events
+        let events =
+            Option::<Vec<EventRecord<RuntimeEvent, H256>>>::decode(&mut input).unwrap_or_default();
+        let contract_events = events
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|record| match record.event {
+                RuntimeEvent::Contracts(ContractsEvent::ContractEmitted { contract, data }) => {
+                    Some(ContractEvent { contract, data })
+                }
+                _ => None,
+            })
+            .collect();
+
+        Ok(ContractDryRun {
+            gas_consumed: head.gas_consumed,
+            gas_required: head.gas_required,
+            storage_deposit: head.storage_deposit,
+            debug_message: head.debug_message,
+            result: head.result,
+            contract_events,
+        })
+    }
+
+    /// the data returned by the contract, `Err` if the call could not be executed
+    pub fn return_data(&self) -> Result<&[u8], String> {
+        match &self.result {
+            Ok(value) => Ok(&value.data),
+            Err(err) => Err(format!("{err:?}")),
+        }
+    }
+}
+
+/// dry-runs a call of `contract` with `call_data` as `origin` via the `ContractsApi_call` runtime api
+pub async fn dry_run_call(
+    api: &OnlineClient<PolkadotConfig>,
+    origin: &AccountId32,
+    contract: &AccountId32,
+    value: u128,
+    call_data: Vec<u8>,
+) -> Result<ContractDryRun, subxt::Error> {
+    let request = CallRequest {
+        origin: origin.clone(),
+        dest: contract.clone(),
+        value,
+        gas_limit: None,
+        storage_deposit_limit: None,
+        input_data: call_data,
+    };
+    let Remaining(bytes) = api
+        .runtime_api()
+        .at_latest()
+        .await?
+        .call_raw("ContractsApi_call", Some(&request.encode()))
+        .await?;
+    Ok(ContractDryRun::from_bytes(&bytes)?)
+}
+
+/// dry-runs a read-only message and decodes its return value
+pub async fn query<R: Decode>(
+    api: &OnlineClient<PolkadotConfig>,
+    origin: &AccountId32,
+    contract: &AccountId32,
+    call_data: Vec<u8>,
+) -> Result<R, anyhow::Error> {
+    let dry_run = dry_run_call(api, origin, contract, 0, call_data).await?;
+    let data = dry_run.return_data().map_err(anyhow::Error::msg)?;
+    Ok(R::decode(&mut &data[..])?)
+}
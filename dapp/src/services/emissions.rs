@@ -0,0 +1,185 @@
+use anyhow::anyhow;
+use subxt::utils::AccountId32;
+use subxt::{OnlineClient, PolkadotConfig};
+
+use crate::services::contracts_api::query;
+use crate::services::psp22::{
+    encode_call_data, LangError, EMISSION_SELECTOR, PENDING_EMISSIONS_SELECTOR,
+    SUPPLY_CAP_SELECTOR, TOTAL_SUPPLY_AT_SELECTOR,
+};
+
+/// number of points of the supply chart, half of them in the past
+const CHART_POINTS: u32 = 12;
+
+/// emission schedule of the token
+#[derive(Clone, Debug, PartialEq)]
+pub struct EmissionState {
+    /// `None` if the emission is disabled
+    pub recipient: Option<AccountId32>,
+    /// tokens per block
+    pub rate: u128,
+    /// block the emissions were last minted at
+    pub last_block: u32,
+    pub cap: Option<u128>,
+    /// emissions mintable now via `mint_emissions`
+    pub pending: u128,
+    /// latest block of the chain
+    pub block: u32,
+}
+
+/// actual and projected total supply at a block
+#[derive(Clone, Debug, PartialEq)]
+pub struct SupplyPoint {
+    pub block: u32,
+    /// `None` for future blocks
+    pub actual: Option<u128>,
+    pub projected: u128,
+}
+
+/// the supply at `block` if every emission since `start_block` was minted, bounded by the cap
+pub fn projected_supply(
+    start_supply: u128,
+    rate: u128,
+    start_block: u32,
+    cap: Option<u128>,
+    block: u32,
+) -> u128 {
+    let emitted = rate.saturating_mul(block.saturating_sub(start_block).into());
+    let supply = start_supply.saturating_add(emitted);
+    match cap {
+        Some(cap) => supply.min(cap.max(start_supply)),
+        None => supply,
+    }
+}
+
+/// blocks of the chart: from the last mint over the latest block as far into the future
+fn chart_blocks(state: &EmissionState) -> Vec<u32> {
+    let span = state
+        .block
+        .saturating_sub(state.last_block)
+        .max(CHART_POINTS);
+    let past_points = CHART_POINTS / 2;
+    let step = (span + past_points - 1) / past_points;
+    // the latest block is always one of the points
+    let start = state.block - step * past_points.min(state.block / step);
+    (0..CHART_POINTS).map(|i| start + i * step).collect()
+}
+
+pub async fn fetch_emission_state(
+    api: &OnlineClient<PolkadotConfig>,
+    contract: &AccountId32,
+    origin: &AccountId32,
+) -> Result<EmissionState, anyhow::Error> {
+    let (recipient, rate, last_block) =
+        query::<Result<(Option<AccountId32>, u128, u32), LangError>>(
+            api,
+            origin,
+            contract,
+            EMISSION_SELECTOR.to_vec(),
+        )
+        .await?
+        .map_err(|err| anyhow!("{err:?}"))?;
+    let cap = query::<Result<Option<u128>, LangError>>(
+        api,
+        origin,
+        contract,
+        SUPPLY_CAP_SELECTOR.to_vec(),
+    )
+    .await?
+    .map_err(|err| anyhow!("{err:?}"))?;
+    let pending = query::<Result<u128, LangError>>(
+        api,
+        origin,
+        contract,
+        PENDING_EMISSIONS_SELECTOR.to_vec(),
+    )
+    .await?
+    .map_err(|err| anyhow!("{err:?}"))?;
+    let block = api.blocks().at_latest().await?.number();
+    Ok(EmissionState {
+        recipient,
+        rate,
+        last_block,
+        cap,
+        pending,
+        block,
+    })
+}
+
+/// the recorded total supply of past blocks and the projected supply of the chart blocks
+pub async fn fetch_supply_chart(
+    api: &OnlineClient<PolkadotConfig>,
+    contract: &AccountId32,
+    origin: &AccountId32,
+    state: &EmissionState,
+) -> Result<Vec<SupplyPoint>, anyhow::Error> {
+    let total_supply_at = |block: u32| async move {
+        query::<Result<u128, LangError>>(
+            api,
+            origin,
+            contract,
+            encode_call_data(TOTAL_SUPPLY_AT_SELECTOR, block),
+        )
+        .await?
+        .map_err(|err| anyhow!("{err:?}"))
+    };
+    let start_supply = total_supply_at(state.last_block).await?;
+    let rate = if state.recipient.is_some() {
+        state.rate
+    } else {
+        0
+    };
+
+    let mut points = Vec::new();
+    for block in chart_blocks(state) {
+        let actual = if block <= state.block {
+            Some(total_supply_at(block).await?)
+        } else {
+            None
+        };
+        // before the last mint the schedule may have been different, the actual supply is the projection
+        let projected = if block < state.last_block {
+            actual.unwrap_or(start_supply)
+        } else {
+            projected_supply(start_supply, rate, state.last_block, state.cap, block)
+        };
+        points.push(SupplyPoint {
+            block,
+            actual,
+            projected,
+        });
+    }
+    Ok(points)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[wasm_bindgen_test::wasm_bindgen_test]
+    fn projection_stops_at_the_cap() {
+        assert_eq!(projected_supply(100, 10, 5, None, 8), 130);
+        assert_eq!(projected_supply(100, 10, 5, Some(125), 8), 125);
+        assert_eq!(projected_supply(100, 10, 5, Some(125), 4), 100);
+        // a cap below the supply doesn't burn anything
+        assert_eq!(projected_supply(100, 10, 5, Some(90), 8), 100);
+    }
+
+    #[wasm_bindgen_test::wasm_bindgen_test]
+    fn chart_reaches_as_far_into_the_future_as_into_the_past() {
+        let state = EmissionState {
+            recipient: None,
+            rate: 0,
+            last_block: 100,
+            cap: None,
+            pending: 0,
+            block: 200,
+        };
+        let blocks = chart_blocks(&state);
+
+        assert_eq!(blocks.len(), CHART_POINTS as usize);
+        assert!(blocks[0] <= 100);
+        assert!(blocks.contains(&200));
+        assert!(*blocks.last().unwrap() >= 280);
+    }
+}
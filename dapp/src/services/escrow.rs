@@ -0,0 +1,223 @@
+use anyhow::anyhow;
+use std::fmt;
+use subxt::ext::codec::Decode;
+use subxt::utils::AccountId32;
+use subxt::{OnlineClient, PolkadotConfig};
+
+use crate::services::contracts_api::{dry_run_call, query};
+use crate::services::psp22::{encode_call_data, LangError, PSP22Error};
+
+/// selector of `Escrow::release`
+pub const RELEASE_SELECTOR: [u8; 4] = [0x3f, 0x2b, 0xe1, 0x52];
+/// selector of `Escrow::refund`
+pub const REFUND_SELECTOR: [u8; 4] = [0xa5, 0xa4, 0x74, 0x41];
+/// selector of `Escrow::dispute`
+pub const DISPUTE_SELECTOR: [u8; 4] = [0x8a, 0x71, 0x88, 0x22];
+/// selector of `Escrow::deals_of`
+pub const DEALS_OF_SELECTOR: [u8; 4] = [0x53, 0x8e, 0xb6, 0x5f];
+/// selector of `Escrow::deal_count_of`
+pub const DEAL_COUNT_OF_SELECTOR: [u8; 4] = [0xdf, 0xae, 0x73, 0x32];
+
+/// `MAX_PAGE_LEN` of the escrow contract
+const PAGE_LEN: u32 = 50;
+
+/// `EscrowError` of the escrow contract
+#[derive(Clone, Debug, Decode, PartialEq)]
+pub enum EscrowError {
+    InvalidDeadline,
+    ZeroAmount,
+    DealNotFound,
+    NotAuthorized,
+    DeadlineNotReached,
+    Disputed,
+    NotFeeExempt,
+    TransferFailed(PSP22Error),
+}
+
+impl fmt::Display for EscrowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EscrowError::TransferFailed(err) => write!(f, "TransferFailed({err})"),
+            error => write!(f, "{error:?}"),
+        }
+    }
+}
+
+/// same encoding as `Deal` of the contract
+#[derive(Clone, Debug, Decode, PartialEq)]
+pub struct Deal {
+    pub buyer: AccountId32,
+    pub seller: AccountId32,
+    pub arbiter: AccountId32,
+    pub amount: u128,
+    /// timestamp in milliseconds
+    pub deadline: u64,
+    pub disputed: bool,
+}
+
+impl Deal {
+    /// the calls `account` may make at `now`, same rules as the contract
+    pub fn calls_of(&self, id: u32, account: &AccountId32, now: u64) -> Vec<EscrowCall> {
+        let mut calls = Vec::new();
+        if *account == self.buyer || *account == self.arbiter {
+            calls.push(EscrowCall::Release(id));
+        }
+        if *account == self.seller
+            || *account == self.arbiter
+            || (*account == self.buyer && !self.disputed && now > self.deadline)
+        {
+            calls.push(EscrowCall::Refund(id));
+        }
+        if *account == self.seller && !self.disputed {
+            calls.push(EscrowCall::Dispute(id));
+        }
+        calls
+    }
+
+    /// the roles of `account` in the deal, separated by " and "
+    pub fn roles_of(&self, account: &AccountId32) -> String {
+        [
+            (&self.buyer, "buyer"),
+            (&self.seller, "seller"),
+            (&self.arbiter, "arbiter"),
+        ]
+        .into_iter()
+        .filter(|(party, _)| *party == account)
+        .map(|(_, role)| role)
+        .collect::<Vec<_>>()
+        .join(" and ")
+    }
+}
+
+/// the messages of the escrow contract the dapp calls
+#[derive(Clone, Debug, PartialEq)]
+pub enum EscrowCall {
+    /// pays the tokens to the seller
+    Release(u32),
+    /// pays the tokens back to the buyer
+    Refund(u32),
+    /// the seller stops the buyer from refunding after the deadline
+    Dispute(u32),
+}
+
+impl EscrowCall {
+    pub fn call_data(&self) -> Vec<u8> {
+        match self {
+            EscrowCall::Release(id) => encode_call_data(RELEASE_SELECTOR, id),
+            EscrowCall::Refund(id) => encode_call_data(REFUND_SELECTOR, id),
+            EscrowCall::Dispute(id) => encode_call_data(DISPUTE_SELECTOR, id),
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            EscrowCall::Release(_) => "Release",
+            EscrowCall::Refund(_) => "Refund",
+            EscrowCall::Dispute(_) => "Dispute",
+        }
+    }
+}
+
+impl fmt::Display for EscrowCall {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EscrowCall::Release(id) => write!(f, "Release deal {id} to the seller"),
+            EscrowCall::Refund(id) => write!(f, "Refund deal {id} to the buyer"),
+            EscrowCall::Dispute(id) => write!(f, "Dispute deal {id}"),
+        }
+    }
+}
+
+/// the open deals `account` is a party of, with their ids
+pub async fn fetch_deals_of(
+    api: &OnlineClient<PolkadotConfig>,
+    contract: &AccountId32,
+    account: &AccountId32,
+) -> Result<Vec<(u32, Deal)>, anyhow::Error> {
+    let count = query::<Result<u32, LangError>>(
+        api,
+        account,
+        contract,
+        encode_call_data(DEAL_COUNT_OF_SELECTOR, account),
+    )
+    .await?
+    .map_err(|err| anyhow!("{err:?}"))?;
+    let mut deals = Vec::new();
+    for start in (0..count).step_by(PAGE_LEN as usize) {
+        let page = query::<Result<Vec<(u32, Deal)>, LangError>>(
+            api,
+            account,
+            contract,
+            encode_call_data(DEALS_OF_SELECTOR, (account, start, PAGE_LEN)),
+        )
+        .await?
+        .map_err(|err| anyhow!("{err:?}"))?;
+        deals.extend(page);
+    }
+    Ok(deals)
+}
+
+/// dry-runs `call` as `account`, the error tells why the call would fail
+pub async fn dry_run_escrow_call(
+    api: &OnlineClient<PolkadotConfig>,
+    contract: &AccountId32,
+    account: &AccountId32,
+    call: &EscrowCall,
+) -> Result<Result<(), String>, anyhow::Error> {
+    let dry_run = dry_run_call(api, account, contract, 0, call.call_data()).await?;
+    let data = match dry_run.return_data() {
+        Ok(data) => data,
+        Err(err) => return Ok(Err(err)),
+    };
+    Ok(
+        match Result::<Result<(), EscrowError>, LangError>::decode(&mut &data[..]) {
+            Ok(Ok(Ok(()))) => Ok(()),
+            Ok(Ok(Err(err))) => Err(format!("EscrowError::{err}")),
+            Ok(Err(err)) => Err(format!("LangError::{err:?}")),
+            Err(err) => Err(format!("Could not decode return value: {err}")),
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[wasm_bindgen_test::wasm_bindgen_test]
+    fn each_party_gets_its_calls() {
+        let (buyer, seller, arbiter) = (
+            AccountId32([1; 32]),
+            AccountId32([2; 32]),
+            AccountId32([3; 32]),
+        );
+        let mut deal = Deal {
+            buyer: buyer.clone(),
+            seller: seller.clone(),
+            arbiter: arbiter.clone(),
+            amount: 1_000,
+            deadline: 100,
+            disputed: false,
+        };
+
+        assert_eq!(deal.calls_of(0, &buyer, 100), vec![EscrowCall::Release(0)]);
+        assert_eq!(
+            deal.calls_of(0, &buyer, 101),
+            vec![EscrowCall::Release(0), EscrowCall::Refund(0)]
+        );
+        assert_eq!(
+            deal.calls_of(0, &seller, 0),
+            vec![EscrowCall::Refund(0), EscrowCall::Dispute(0)]
+        );
+        assert_eq!(
+            deal.calls_of(0, &arbiter, 0),
+            vec![EscrowCall::Release(0), EscrowCall::Refund(0)]
+        );
+
+        deal.disputed = true;
+        assert_eq!(deal.calls_of(0, &buyer, 101), vec![EscrowCall::Release(0)]);
+        assert_eq!(deal.calls_of(0, &seller, 0), vec![EscrowCall::Refund(0)]);
+
+        deal.arbiter = buyer.clone();
+        assert_eq!(deal.roles_of(&buyer), "buyer and arbiter");
+    }
+}
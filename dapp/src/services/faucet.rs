@@ -0,0 +1,54 @@
+use anyhow::anyhow;
+use subxt::utils::AccountId32;
+use subxt::{OnlineClient, PolkadotConfig};
+
+use crate::services::contracts_api::query;
+use crate::services::psp22::{
+    encode_call_data, LangError, FAUCET_SELECTOR, NEXT_FAUCET_CLAIM_SELECTOR,
+};
+
+/// faucet of the token as seen by an account
+#[derive(Clone, Debug, PartialEq)]
+pub struct FaucetState {
+    /// tokens per claim, 0 if the faucet is disabled
+    pub amount: u128,
+    /// milliseconds between two claims
+    pub cooldown: u64,
+    /// earliest timestamp the account can claim again
+    pub next_claim: u64,
+}
+
+impl FaucetState {
+    pub fn is_enabled(&self) -> bool {
+        self.amount > 0
+    }
+
+    /// milliseconds until the account can claim again, 0 if it can claim now
+    pub fn wait_at(&self, now: u64) -> u64 {
+        self.next_claim.saturating_sub(now)
+    }
+}
+
+pub async fn fetch_faucet_state(
+    api: &OnlineClient<PolkadotConfig>,
+    contract: &AccountId32,
+    account: &AccountId32,
+) -> Result<FaucetState, anyhow::Error> {
+    let (amount, cooldown) =
+        query::<Result<(u128, u64), LangError>>(api, account, contract, FAUCET_SELECTOR.to_vec())
+            .await?
+            .map_err(|err| anyhow!("{err:?}"))?;
+    let next_claim = query::<Result<u64, LangError>>(
+        api,
+        account,
+        contract,
+        encode_call_data(NEXT_FAUCET_CLAIM_SELECTOR, account),
+    )
+    .await?
+    .map_err(|err| anyhow!("{err:?}"))?;
+    Ok(FaucetState {
+        amount,
+        cooldown,
+        next_claim,
+    })
+}
@@ -0,0 +1,116 @@
+use crate::services::contracts_api::StorageDeposit;
+use crate::services::polkadot::runtime_types::frame_support::dispatch::DispatchClass;
+use crate::services::polkadot::runtime_types::sp_weights::weight_v2::Weight;
+use crate::services::PreparedExtrinsic;
+use subxt::ext::codec::{Decode, Encode};
+use subxt::utils::MultiSignature;
+use subxt::{OnlineClient, PolkadotConfig};
+
+/// result of `TransactionPaymentApi_query_info`
+#[derive(Decode)]
+struct RuntimeDispatchInfo {
+    weight: Weight,
+    _class: DispatchClass,
+    partial_fee: u128,
+}
+
+#[derive(Decode)]
+struct InclusionFee {
+    base_fee: u128,
+    len_fee: u128,
+    adjusted_weight_fee: u128,
+}
+
+/// result of `TransactionPaymentApi_query_fee_details`
+#[derive(Decode)]
+struct FeeDetails {
+    inclusion_fee: Option<InclusionFee>,
+    tip: u128,
+}
+
+/// the fees the signer pays for an extrinsic
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FeeEstimate {
+    /// fee without the tip, sum of base, length and weight fee
+    pub partial_fee: u128,
+    pub base_fee: u128,
+    pub len_fee: u128,
+    pub adjusted_weight_fee: u128,
+    pub tip: u128,
+    pub weight_ref_time: u64,
+    pub weight_proof_size: u64,
+    /// storage deposit of the contract calls, `None` if the extrinsic calls no contract
+    pub storage_deposit: Option<StorageDeposit>,
+}
+
+impl FeeEstimate {
+    /// everything that is withdrawn from the signer: fees, tip and charged storage deposit
+    pub fn total(&self) -> u128 {
+        let deposit = self
+            .storage_deposit
+            .as_ref()
+            .map(StorageDeposit::charge)
+            .unwrap_or_default();
+        self.partial_fee
+            .saturating_add(self.tip)
+            .saturating_add(deposit)
+    }
+}
+
+/// queries the fees of `prepared` before it is signed.
+///
+/// The runtime only needs the length of the signed extrinsic, so it is signed with an empty signature.
+pub async fn estimate_fees(
+    api: &OnlineClient<PolkadotConfig>,
+    prepared: &PreparedExtrinsic,
+) -> Result<FeeEstimate, subxt::Error> {
+    let fake_signed = prepared.partial_extrinsic.sign_with_address_and_signature(
+        &prepared.account_id.clone().into(),
+        &MultiSignature::Sr25519([0; 64]),
+    );
+    let mut params = fake_signed.encoded().to_vec();
+    (fake_signed.encoded().len() as u32).encode_to(&mut params);
+
+    let runtime_api = api.runtime_api().at_latest().await?;
+    let info: RuntimeDispatchInfo = runtime_api
+        .call_raw("TransactionPaymentApi_query_info", Some(&params))
+        .await?;
+    let details: FeeDetails = runtime_api
+        .call_raw("TransactionPaymentApi_query_fee_details", Some(&params))
+        .await?;
+    let inclusion_fee = details.inclusion_fee.unwrap_or(InclusionFee {
+        base_fee: 0,
+        len_fee: 0,
+        adjusted_weight_fee: 0,
+    });
+
+    Ok(FeeEstimate {
+        partial_fee: info.partial_fee,
+        base_fee: inclusion_fee.base_fee,
+        len_fee: inclusion_fee.len_fee,
+        adjusted_weight_fee: inclusion_fee.adjusted_weight_fee,
+        tip: details.tip,
+        weight_ref_time: info.weight.ref_time,
+        weight_proof_size: info.weight.proof_size,
+        storage_deposit: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[wasm_bindgen_test::wasm_bindgen_test]
+    fn total_includes_tip_and_charged_deposit() {
+        let mut fees = FeeEstimate {
+            partial_fee: 100,
+            tip: 5,
+            storage_deposit: Some(StorageDeposit::Charge(20)),
+            ..Default::default()
+        };
+        assert_eq!(fees.total(), 125);
+
+        fees.storage_deposit = Some(StorageDeposit::Refund(20));
+        assert_eq!(fees.total(), 105);
+    }
+}
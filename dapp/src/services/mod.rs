@@ -2,17 +2,43 @@ use anyhow::anyhow;
 use futures::StreamExt;
 use js_sys::Promise;
 use serde::{Deserialize, Serialize};
-use serde_json::json;
 use std::fmt::Write;
-use subxt::ext::codec::Encode;
-use subxt::tx::PartialExtrinsic;
-use subxt::utils::AccountId32;
+use std::str::FromStr;
+use subxt::blocks::ExtrinsicEvents;
+use subxt::ext::codec::Decode;
+use subxt::tx::{PartialExtrinsic, SubmittableExtrinsic, TxPayload};
+use subxt::utils::{AccountId32, MultiSignature};
 use subxt::{self, OnlineClient, PolkadotConfig};
 use wasm_bindgen::prelude::*;
 use wasm_bindgen_futures::JsFuture;
 use yew::{AttrValue, Callback};
 
+pub mod airdrop;
+pub mod amm;
+pub mod compliance;
+pub mod contracts_api;
+pub mod emissions;
+pub mod escrow;
+pub mod faucet;
+pub mod fees;
+pub mod multisig;
+pub mod nonce_manager;
+pub mod payouts;
+pub mod pending_call;
+pub mod permit;
+pub mod psp22;
+pub mod signer_payload;
+pub mod simulation;
+pub mod streams;
+pub mod token_factory;
 pub mod token_service;
+pub mod vesting;
+pub mod votes;
+pub mod watchlist;
+pub mod wrapped_native;
+pub use nonce_manager::NonceManager;
+pub use pending_call::{PendingCall, PreparedCall};
+pub use signer_payload::{SignerPayloadParams, SigningOptions};
 pub use token_service::*;
 
 #[subxt::subxt(runtime_metadata_path = "metadata.scale")]
@@ -116,40 +142,18 @@ pub async fn get_accounts() -> Result<Vec<Account>, anyhow::Error> {
     Ok(accounts)
 }
 
-fn to_hex(bytes: impl AsRef<[u8]>) -> String {
-    format!("0x{}", hex::encode(bytes.as_ref()))
-}
-
-fn encode_to_hex<E: Encode>(input: &E) -> String {
-    format!("0x{}", hex::encode(input.encode()))
-}
-
-/// this is used because numeric types (e.g. u32) are encoded as little-endian via scale (e.g. 9430 -> d6240000)
-/// while we need a big-endian representation for the json (e.g. 9430 -> 000024d6).
-fn encode_to_hex_reverse<E: Encode>(input: &E) -> String {
-    let mut bytes = input.encode();
-    bytes.reverse();
-    format!("0x{}", hex::encode(bytes))
-}
-
 /// communicates with JavaScript to obtain a signature for the `partial_extrinsic` via a browser extension (e.g. polkadot-js or Talisman)
 ///
-/// Some parameters are hard-coded here and not taken from the partial_extrinsic itself (mortality_checkpoint, era, tip).
+/// The `partial_extrinsic` must have been created with `params`, so the payload signed by the extension
+/// matches the signer payload of the partial extrinsic.
 pub async fn extension_signature_for_partial_extrinsic(
     partial_extrinsic: &PartialExtrinsic<PolkadotConfig, OnlineClient<PolkadotConfig>>,
     api: &OnlineClient<PolkadotConfig>,
-    account_id: &AccountId32,
+    params: &SignerPayloadParams,
     account_source: String,
     account_address: String,
 ) -> Result<Vec<u8>, anyhow::Error> {
-    let spec_version = encode_to_hex_reverse(&api.runtime_version().spec_version);
-    let transaction_version = encode_to_hex_reverse(&api.runtime_version().transaction_version);
-    let mortality_checkpoint = encode_to_hex(&api.genesis_hash());
-    let era = encode_to_hex(&subxt::config::extrinsic_params::Era::Immortal);
-    let genesis_hash = encode_to_hex(&api.genesis_hash());
-    let method = to_hex(partial_extrinsic.call_data());
-    let nonce = api.tx().account_nonce(account_id).await?;
-    let nonce = encode_to_hex_reverse(&nonce);
+    let runtime_version = api.runtime_version();
     let signed_extensions: Vec<String> = api
         .metadata()
         .extrinsic()
@@ -157,22 +161,14 @@ pub async fn extension_signature_for_partial_extrinsic(
         .iter()
         .map(|e| e.identifier().to_string())
         .collect();
-    let tip = encode_to_hex(&subxt::config::polkadot::PlainTip::new(0));
-
-    let payload = json!({
-        "specVersion": spec_version,
-        "transactionVersion": transaction_version,
-        "address": account_address,
-        "blockHash": mortality_checkpoint,
-        "blockNumber": "0x00000000",
-        "era": era,
-        "genesisHash": genesis_hash,
-        "method": method,
-        "nonce": nonce,
-        "signedExtensions": signed_extensions,
-        "tip": tip,
-        "version": 4,
-    });
+    let payload = params.extension_payload(
+        partial_extrinsic.call_data(),
+        runtime_version.spec_version,
+        runtime_version.transaction_version,
+        api.genesis_hash(),
+        signed_extensions,
+        &account_address,
+    );
 
     let payload = payload.to_string();
     let result = JsFuture::from(js_sign_payload(payload, account_source, account_address))
@@ -184,3 +180,119 @@ pub async fn extension_signature_for_partial_extrinsic(
     let signature = hex::decode(&signature[2..])?;
     Ok(signature)
 }
+
+/// a partial extrinsic together with the parameters and the reserved nonce it was created with
+pub struct PreparedExtrinsic {
+    pub partial_extrinsic: PartialExtrinsic<PolkadotConfig, OnlineClient<PolkadotConfig>>,
+    pub params: SignerPayloadParams,
+    pub account_id: AccountId32,
+    pub account_source: String,
+    pub account_address: String,
+}
+
+/// creates a partial extrinsic for `call` with a nonce reserved from the shared [`NonceManager`]
+pub async fn prepare_extrinsic<Call: TxPayload>(
+    api: &OnlineClient<PolkadotConfig>,
+    call: &Call,
+    account_source: String,
+    account_address: String,
+    options: &SigningOptions,
+) -> Result<PreparedExtrinsic, anyhow::Error> {
+    let account_id = AccountId32::from_str(&account_address)
+        .map_err(|_| anyhow!("Invalid address: {account_address}"))?;
+    let nonce_manager = NonceManager::shared();
+    let nonce = nonce_manager.reserve(api, &account_id).await?;
+    let prepared = async {
+        let params = SignerPayloadParams::fetch(api, nonce, options).await?;
+        let partial_extrinsic =
+            api.tx()
+                .create_partial_signed_with_nonce(call, nonce, params.extrinsic_params())?;
+        Ok::<_, anyhow::Error>((params, partial_extrinsic))
+    }
+    .await;
+    match prepared {
+        Ok((params, partial_extrinsic)) => Ok(PreparedExtrinsic {
+            partial_extrinsic,
+            params,
+            account_id,
+            account_source,
+            account_address,
+        }),
+        Err(err) => {
+            nonce_manager.release(&account_id, nonce);
+            Err(err)
+        }
+    }
+}
+
+impl PreparedExtrinsic {
+    pub fn nonce(&self) -> u64 {
+        self.params.nonce
+    }
+
+    /// gives the reserved nonce back to the [`NonceManager`]
+    pub fn release_nonce(&self) {
+        NonceManager::shared().release(&self.account_id, self.nonce());
+    }
+
+    /// obtains the signature from the browser extension. The reserved nonce is released if signing fails.
+    pub async fn sign(
+        &self,
+        api: &OnlineClient<PolkadotConfig>,
+    ) -> Result<
+        (
+            MultiSignature,
+            SubmittableExtrinsic<PolkadotConfig, OnlineClient<PolkadotConfig>>,
+        ),
+        anyhow::Error,
+    > {
+        let signature = match extension_signature_for_partial_extrinsic(
+            &self.partial_extrinsic,
+            api,
+            &self.params,
+            self.account_source.clone(),
+            self.account_address.clone(),
+        )
+        .await
+        {
+            Ok(signature) => {
+                MultiSignature::decode(&mut &signature[..]).map_err(anyhow::Error::from)
+            }
+            Err(err) => Err(err),
+        };
+        match signature {
+            Ok(multi_signature) => {
+                let signed_extrinsic = self.partial_extrinsic.sign_with_address_and_signature(
+                    &self.account_id.clone().into(),
+                    &multi_signature,
+                );
+                Ok((multi_signature, signed_extrinsic))
+            }
+            Err(err) => {
+                self.release_nonce();
+                Err(err)
+            }
+        }
+    }
+
+    /// signs with the browser extension, submits and waits until the extrinsic is finalized.
+    /// The reserved nonce is released afterwards.
+    pub async fn sign_and_submit(
+        self,
+        api: &OnlineClient<PolkadotConfig>,
+    ) -> Result<ExtrinsicEvents<PolkadotConfig>, anyhow::Error> {
+        let (_, signed_extrinsic) = self.sign(api).await?;
+        let events = async {
+            Ok::<_, anyhow::Error>(
+                signed_extrinsic
+                    .submit_and_watch()
+                    .await?
+                    .wait_for_finalized_success()
+                    .await?,
+            )
+        }
+        .await;
+        self.release_nonce();
+        events
+    }
+}
@@ -0,0 +1,237 @@
+use anyhow::anyhow;
+use std::fmt;
+use subxt::ext::codec::Decode;
+use subxt::utils::AccountId32;
+use subxt::{OnlineClient, PolkadotConfig};
+
+use crate::services::contracts_api::{dry_run_call, query};
+use crate::services::psp22::{
+    encode_call_data, LangError, TokenCall, FREEZE_SELECTOR, MINT_SELECTOR, UNFREEZE_SELECTOR,
+};
+
+/// selector of `MultisigAdmin::propose`
+pub const PROPOSE_SELECTOR: [u8; 4] = [0xcb, 0x14, 0xb5, 0xb5];
+/// selector of `MultisigAdmin::approve`
+pub const APPROVE_SELECTOR: [u8; 4] = [0x68, 0x12, 0x66, 0xa0];
+/// selector of `MultisigAdmin::revoke`
+pub const REVOKE_SELECTOR: [u8; 4] = [0x0a, 0x53, 0x8d, 0x12];
+/// selector of `MultisigAdmin::execute`
+pub const EXECUTE_SELECTOR: [u8; 4] = [0xd0, 0x0e, 0xc8, 0x94];
+/// selector of `MultisigAdmin::pending_proposals`
+pub const PENDING_PROPOSALS_SELECTOR: [u8; 4] = [0x55, 0x7b, 0xac, 0x3b];
+/// selector of `MultisigAdmin::owners`
+pub const OWNERS_SELECTOR: [u8; 4] = [0x68, 0x84, 0xda, 0xb0];
+/// selector of `MultisigAdmin::threshold`
+pub const THRESHOLD_SELECTOR: [u8; 4] = [0x36, 0xd3, 0x7d, 0xef];
+
+/// the owner-only messages of `YToken` a proposal may call, by selector
+const ADMIN_MESSAGES: [([u8; 4], &str); 14] = [
+    (MINT_SELECTOR, "mint"),
+    (FREEZE_SELECTOR, "freeze"),
+    (UNFREEZE_SELECTOR, "unfreeze"),
+    ([0xa9, 0xe8, 0x8b, 0xae], "rescue"),
+    ([0x98, 0x52, 0xf7, 0xb0], "upgrade"),
+    ([0x06, 0x0d, 0x3f, 0x50], "migrate"),
+    ([0x97, 0xb1, 0x4e, 0xbb], "set_emission"),
+    ([0x80, 0x0e, 0xba, 0x1e], "set_supply_cap"),
+    ([0x90, 0x16, 0x37, 0xff], "set_faucet"),
+    ([0x22, 0x68, 0x24, 0x9d], "set_flash_fee_bps"),
+    ([0xfe, 0x9c, 0x69, 0xd0], "set_transfer_fee_bps"),
+    ([0xe6, 0x81, 0x27, 0x81], "set_treasury"),
+    ([0x35, 0x0b, 0x81, 0xdc], "set_fee_exempt"),
+    ([0x11, 0xf4, 0x3e, 0xfd], "transfer_ownership"),
+];
+
+/// `MultisigError` of the multisig admin contract
+#[derive(Clone, Debug, Decode, PartialEq)]
+pub enum MultisigError {
+    InvalidThreshold,
+    NotOwner,
+    ProposalNotFound,
+    AlreadyApproved,
+    NotApproved,
+    ThresholdNotReached,
+    TooManyPending,
+    /// the encoded error of the called message, whose type depends on the message
+    CallFailed(Vec<u8>),
+    CallReverted,
+}
+
+impl fmt::Display for MultisigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MultisigError::CallFailed(err) => write!(f, "CallFailed(0x{})", hex::encode(err)),
+            error => write!(f, "{error:?}"),
+        }
+    }
+}
+
+/// same encoding as `Proposal` of the contract
+#[derive(Clone, Debug, Decode, PartialEq)]
+pub struct Proposal {
+    pub proposer: AccountId32,
+    pub callee: AccountId32,
+    pub selector: [u8; 4],
+    pub input: Vec<u8>,
+    pub approvals: Vec<AccountId32>,
+}
+
+impl Proposal {
+    /// the name of the called `YToken` message, or its selector if it is unknown
+    pub fn message(&self) -> String {
+        ADMIN_MESSAGES
+            .iter()
+            .find(|(selector, _)| *selector == self.selector)
+            .map(|(_, name)| name.to_string())
+            .unwrap_or_else(|| format!("0x{}", hex::encode(self.selector)))
+    }
+}
+
+/// owners, threshold and pending proposals of a multisig
+#[derive(Clone, Debug, PartialEq)]
+pub struct MultisigState {
+    pub owners: Vec<AccountId32>,
+    pub threshold: u32,
+    pub pending: Vec<(u32, Proposal)>,
+}
+
+impl MultisigState {
+    pub fn is_owner(&self, account: &AccountId32) -> bool {
+        self.owners.contains(account)
+    }
+}
+
+/// the messages of the multisig the dapp calls
+#[derive(Clone, Debug, PartialEq)]
+pub enum MultisigCall {
+    /// proposes a call of `callee`, the caller approves it right away
+    Propose {
+        callee: AccountId32,
+        call: TokenCall,
+    },
+    Approve(u32),
+    Revoke(u32),
+    Execute(u32),
+}
+
+impl MultisigCall {
+    pub fn call_data(&self) -> Vec<u8> {
+        match self {
+            MultisigCall::Propose { callee, call } => {
+                let call_data = call.call_data();
+                let mut selector = [0; 4];
+                selector.copy_from_slice(&call_data[..4]);
+                encode_call_data(
+                    PROPOSE_SELECTOR,
+                    (callee, selector, call_data[4..].to_vec()),
+                )
+            }
+            MultisigCall::Approve(id) => encode_call_data(APPROVE_SELECTOR, id),
+            MultisigCall::Revoke(id) => encode_call_data(REVOKE_SELECTOR, id),
+            MultisigCall::Execute(id) => encode_call_data(EXECUTE_SELECTOR, id),
+        }
+    }
+}
+
+impl fmt::Display for MultisigCall {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MultisigCall::Propose { call, .. } => write!(f, "Propose: {call}"),
+            MultisigCall::Approve(id) => write!(f, "Approve proposal {id}"),
+            MultisigCall::Revoke(id) => write!(f, "Revoke approval of proposal {id}"),
+            MultisigCall::Execute(id) => write!(f, "Execute proposal {id}"),
+        }
+    }
+}
+
+/// queries the message `selector` of `multisig`, which takes no arguments
+async fn query_multisig<T: Decode>(
+    api: &OnlineClient<PolkadotConfig>,
+    multisig: &AccountId32,
+    caller: &AccountId32,
+    selector: [u8; 4],
+) -> Result<T, anyhow::Error> {
+    query::<Result<T, LangError>>(api, caller, multisig, encode_call_data(selector, ()))
+        .await?
+        .map_err(|err| anyhow!("{err:?}"))
+}
+
+/// the state of `multisig`, fails if it is not a multisig admin contract
+pub async fn fetch_multisig_state(
+    api: &OnlineClient<PolkadotConfig>,
+    multisig: &AccountId32,
+    caller: &AccountId32,
+) -> Result<MultisigState, anyhow::Error> {
+    Ok(MultisigState {
+        owners: query_multisig(api, multisig, caller, OWNERS_SELECTOR).await?,
+        threshold: query_multisig(api, multisig, caller, THRESHOLD_SELECTOR).await?,
+        pending: query_multisig(api, multisig, caller, PENDING_PROPOSALS_SELECTOR).await?,
+    })
+}
+
+/// dry-runs `call` as `account`, the error tells why the call would fail
+pub async fn dry_run_multisig_call(
+    api: &OnlineClient<PolkadotConfig>,
+    multisig: &AccountId32,
+    account: &AccountId32,
+    call: &MultisigCall,
+) -> Result<Result<(), String>, anyhow::Error> {
+    let dry_run = dry_run_call(api, account, multisig, 0, call.call_data()).await?;
+    let data = match dry_run.return_data() {
+        Ok(data) => data,
+        Err(err) => return Ok(Err(err)),
+    };
+    // `propose` returns the id, the other messages nothing, the error is decoded the same way
+    let result = match call {
+        MultisigCall::Propose { .. } => {
+            Result::<Result<u32, MultisigError>, LangError>::decode(&mut &data[..])
+                .map(|result| result.map(|result| result.map(|_| ())))
+        }
+        _ => Result::<Result<(), MultisigError>, LangError>::decode(&mut &data[..]),
+    };
+    Ok(match result {
+        Ok(Ok(Ok(()))) => Ok(()),
+        Ok(Ok(Err(err))) => Err(format!("MultisigError::{err}")),
+        Ok(Err(err)) => Err(format!("LangError::{err:?}")),
+        Err(err) => Err(format!("Could not decode return value: {err}")),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use subxt::ext::codec::Encode;
+
+    #[wasm_bindgen_test::wasm_bindgen_test]
+    fn proposes_the_token_call_split_into_selector_and_input() {
+        let account = AccountId32([2; 32]);
+        let call = MultisigCall::Propose {
+            callee: AccountId32([1; 32]),
+            call: TokenCall::Freeze {
+                account: account.clone(),
+            },
+        };
+        let data = call.call_data();
+
+        assert_eq!(&data[..4], &PROPOSE_SELECTOR);
+        assert_eq!(&data[36..40], &FREEZE_SELECTOR);
+        assert_eq!(data[40..], account.encode().encode()[..]);
+
+        let proposal = Proposal {
+            proposer: account.clone(),
+            callee: AccountId32([1; 32]),
+            selector: FREEZE_SELECTOR,
+            input: account.encode(),
+            approvals: vec![account],
+        };
+        assert_eq!(proposal.message(), "freeze");
+        assert_eq!(
+            Proposal {
+                selector: [1, 2, 3, 4],
+                ..proposal
+            }
+            .message(),
+            "0x01020304"
+        );
+    }
+}
@@ -0,0 +1,115 @@
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet};
+use std::rc::Rc;
+use subxt::utils::AccountId32;
+use subxt::{rpc_params, OnlineClient, PolkadotConfig};
+
+thread_local! {
+    static SHARED: NonceManager = NonceManager::default();
+}
+
+/// hands out nonces per account for transactions which are signed but not yet part of the chain.
+///
+/// Fetching the nonce from the chain for every transaction produces duplicates as long as the
+/// previous transaction waits for the signature of the browser extension, so reserved nonces are
+/// tracked locally and reconciled with `system_accountNextIndex`.
+#[derive(Clone, Default)]
+pub struct NonceManager {
+    pending: Rc<RefCell<BTreeMap<AccountId32, BTreeSet<u64>>>>,
+}
+
+impl NonceManager {
+    /// the nonce manager shared by all routes of the app
+    pub fn shared() -> Self {
+        SHARED.with(|manager| manager.clone())
+    }
+
+    /// reserves the next free nonce for `account_id`
+    pub async fn reserve(
+        &self,
+        api: &OnlineClient<PolkadotConfig>,
+        account_id: &AccountId32,
+    ) -> Result<u64, subxt::Error> {
+        let chain_nonce = api
+            .rpc()
+            .request("system_accountNextIndex", rpc_params![account_id])
+            .await?;
+        Ok(self.reserve_with_chain_nonce(account_id, chain_nonce))
+    }
+
+    /// reserves the lowest nonce which is at least `chain_nonce` and not pending.
+    ///
+    /// Pending nonces below `chain_nonce` are already known to the chain and are dropped.
+    pub fn reserve_with_chain_nonce(&self, account_id: &AccountId32, chain_nonce: u64) -> u64 {
+        let mut pending = self.pending.borrow_mut();
+        let nonces = pending.entry(account_id.clone()).or_default();
+        nonces.retain(|nonce| *nonce >= chain_nonce);
+        let nonce = (chain_nonce..)
+            .find(|nonce| !nonces.contains(nonce))
+            .expect("there is always a free nonce");
+        nonces.insert(nonce);
+        nonce
+    }
+
+    /// releases a reserved nonce once its transaction is finalized or was never submitted
+    pub fn release(&self, account_id: &AccountId32, nonce: u64) {
+        let mut pending = self.pending.borrow_mut();
+        if let Some(nonces) = pending.get_mut(account_id) {
+            nonces.remove(&nonce);
+            if nonces.is_empty() {
+                pending.remove(account_id);
+            }
+        }
+    }
+
+    /// nonces of the queued transactions of `account_id` in ascending order
+    pub fn pending(&self, account_id: &AccountId32) -> Vec<u64> {
+        self.pending
+            .borrow()
+            .get(account_id)
+            .map(|nonces| nonces.iter().copied().collect())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn alice() -> AccountId32 {
+        AccountId32([1; 32])
+    }
+
+    #[wasm_bindgen_test::wasm_bindgen_test]
+    fn queued_transactions_get_consecutive_nonces() {
+        let manager = NonceManager::default();
+
+        assert_eq!(manager.reserve_with_chain_nonce(&alice(), 5), 5);
+        assert_eq!(manager.reserve_with_chain_nonce(&alice(), 5), 6);
+        assert_eq!(manager.reserve_with_chain_nonce(&alice(), 5), 7);
+        assert_eq!(manager.pending(&alice()), vec![5, 6, 7]);
+    }
+
+    #[wasm_bindgen_test::wasm_bindgen_test]
+    fn reconciles_with_chain_nonce() {
+        let manager = NonceManager::default();
+        manager.reserve_with_chain_nonce(&alice(), 5);
+        manager.reserve_with_chain_nonce(&alice(), 5);
+
+        assert_eq!(manager.reserve_with_chain_nonce(&alice(), 7), 7);
+        assert_eq!(manager.pending(&alice()), vec![7]);
+    }
+
+    #[wasm_bindgen_test::wasm_bindgen_test]
+    fn released_nonces_are_reused() {
+        let manager = NonceManager::default();
+        let first = manager.reserve_with_chain_nonce(&alice(), 0);
+        let second = manager.reserve_with_chain_nonce(&alice(), 0);
+        manager.release(&alice(), first);
+
+        assert_eq!(manager.reserve_with_chain_nonce(&alice(), 0), first);
+        manager.release(&alice(), first);
+        manager.release(&alice(), second);
+        assert!(manager.pending(&alice()).is_empty());
+    }
+}
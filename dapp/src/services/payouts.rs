@@ -0,0 +1,238 @@
+use anyhow::anyhow;
+use std::str::FromStr;
+use subxt::utils::AccountId32;
+use subxt::{OnlineClient, PolkadotConfig};
+
+use crate::services::contracts_api::query;
+use crate::services::psp22::{
+    encode_call_data, LangError, IS_FEE_EXEMPT_SELECTOR, TRANSFER_FEE_BPS_SELECTOR,
+    TREASURY_SELECTOR,
+};
+
+/// maximum length of a memo in bytes, the memo is passed as `data` to `PSP22::transfer`
+pub const MAX_MEMO_LEN: usize = 128;
+
+/// denominator of the transfer fee, same as `TRANSFER_FEE_DENOMINATOR` of `YToken`
+pub const TRANSFER_FEE_DENOMINATOR: u128 = 10_000;
+
+/// a validated row of a payout CSV
+#[derive(Clone, Debug, PartialEq)]
+pub struct PayoutRow {
+    /// line number in the CSV (starting at 1)
+    pub line: usize,
+    /// ss58 formatted address of the recipient
+    pub address: String,
+    pub account_id: AccountId32,
+    pub amount: u128,
+    pub memo: Option<String>,
+}
+
+/// a row of a payout CSV which could not be validated
+#[derive(Clone, Debug, PartialEq)]
+pub struct InvalidRow {
+    /// line number in the CSV (starting at 1)
+    pub line: usize,
+    pub content: String,
+    pub reason: String,
+}
+
+/// outcome of a single payout after the batch was submitted
+#[derive(Clone, Debug, PartialEq)]
+pub enum RowResult {
+    Completed,
+    Failed(String),
+}
+
+/// all rows of a payout CSV in the format `address,amount[,memo]`
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PayoutBatch {
+    pub rows: Vec<PayoutRow>,
+    pub invalid: Vec<InvalidRow>,
+}
+
+impl PayoutBatch {
+    /// parses and validates every row of `csv`.
+    ///
+    /// Empty lines, lines starting with `#` and a leading `address,amount` header are skipped.
+    pub fn parse(csv: &str) -> Self {
+        let mut batch = PayoutBatch::default();
+        for (i, content) in csv.lines().enumerate() {
+            let line = i + 1;
+            let trimmed = content.trim();
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+            if batch.rows.is_empty()
+                && batch.invalid.is_empty()
+                && trimmed.to_lowercase().starts_with("address")
+            {
+                continue;
+            }
+            match parse_row(line, trimmed) {
+                Ok(row) => batch.rows.push(row),
+                Err(reason) => batch.invalid.push(InvalidRow {
+                    line,
+                    content: trimmed.to_string(),
+                    reason,
+                }),
+            }
+        }
+        if batch.total().is_none() {
+            batch.invalid.push(InvalidRow {
+                line: 0,
+                content: "".to_string(),
+                reason: "Total amount overflows u128".to_string(),
+            });
+        }
+        batch
+    }
+
+    /// sum of all valid rows, `None` on overflow
+    pub fn total(&self) -> Option<u128> {
+        self.rows
+            .iter()
+            .try_fold(0u128, |total, row| total.checked_add(row.amount))
+    }
+
+    /// true if there is at least one row and every row is valid
+    pub fn is_valid(&self) -> bool {
+        !self.rows.is_empty() && self.invalid.is_empty()
+    }
+
+    /// transfer fees of all valid rows at `fee_bps`, each rounded down like the token does, `None` on overflow
+    pub fn transfer_fees(&self, fee_bps: u16) -> Option<u128> {
+        self.rows.iter().try_fold(0u128, |fees, row| {
+            fees.checked_add(row.amount.saturating_mul(fee_bps.into()) / TRANSFER_FEE_DENOMINATOR)
+        })
+    }
+
+    /// true if the batch and its transfer fees at `fee_bps` can be paid from `balance`
+    pub fn is_covered_by(&self, balance: u128, fee_bps: u16) -> bool {
+        let total = self
+            .total()
+            .zip(self.transfer_fees(fee_bps))
+            .and_then(|(total, fees)| total.checked_add(fees));
+        matches!(total, Some(total) if total <= balance)
+    }
+}
+
+/// the transfer fee in basis points `account` pays on payouts, 0 if there is no treasury or the account is exempt.
+///
+/// Exempt recipients are not looked up, the fee is counted for every row.
+pub async fn fetch_payout_fee_bps(
+    api: &OnlineClient<PolkadotConfig>,
+    contract: &AccountId32,
+    account: &AccountId32,
+) -> Result<u16, anyhow::Error> {
+    let treasury = query::<Result<Option<AccountId32>, LangError>>(
+        api,
+        account,
+        contract,
+        TREASURY_SELECTOR.to_vec(),
+    )
+    .await?
+    .map_err(|err| anyhow!("{err:?}"))?;
+    let exempt = query::<Result<bool, LangError>>(
+        api,
+        account,
+        contract,
+        encode_call_data(IS_FEE_EXEMPT_SELECTOR, account),
+    )
+    .await?
+    .map_err(|err| anyhow!("{err:?}"))?;
+    if treasury.is_none() || exempt {
+        return Ok(0);
+    }
+    query::<Result<u16, LangError>>(api, account, contract, TRANSFER_FEE_BPS_SELECTOR.to_vec())
+        .await?
+        .map_err(|err| anyhow!("{err:?}"))
+}
+
+fn parse_row(line: usize, content: &str) -> Result<PayoutRow, String> {
+    let mut columns = content.splitn(3, ',').map(str::trim);
+    let address = columns.next().unwrap_or_default();
+    let amount = columns
+        .next()
+        .ok_or_else(|| "Missing amount column".to_string())?;
+    let memo = columns
+        .next()
+        .filter(|memo| !memo.is_empty())
+        .map(str::to_string);
+
+    let account_id =
+        AccountId32::from_str(address).map_err(|_| format!("Invalid address: {address}"))?;
+    let amount = amount
+        .parse::<u128>()
+        .map_err(|_| format!("Invalid amount: {amount}"))?;
+    if amount == 0 {
+        return Err("Amount must be greater than zero".to_string());
+    }
+    if let Some(memo) = &memo {
+        if memo.len() > MAX_MEMO_LEN {
+            return Err(format!("Memo is longer than {MAX_MEMO_LEN} bytes"));
+        }
+    }
+
+    Ok(PayoutRow {
+        line,
+        address: address.to_string(),
+        account_id,
+        amount,
+        memo,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALICE: &str = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY";
+    const BOB: &str = "5FHneW46xGXgs5mUiveU4sbTyGBzmstUspZC92UhjJM694ty";
+
+    #[wasm_bindgen_test::wasm_bindgen_test]
+    fn parses_rows_with_header_and_memo() {
+        let csv = format!("address,amount,memo\n{ALICE},10,salary march\n\n{BOB},5\n");
+        let batch = PayoutBatch::parse(&csv);
+
+        assert!(batch.is_valid());
+        assert_eq!(batch.rows.len(), 2);
+        assert_eq!(batch.rows[0].memo, Some("salary march".to_string()));
+        assert_eq!(batch.rows[1].line, 4);
+        assert_eq!(batch.rows[1].memo, None);
+        assert_eq!(batch.total(), Some(15));
+        assert!(batch.is_covered_by(15, 0));
+        assert!(!batch.is_covered_by(14, 0));
+    }
+
+    #[wasm_bindgen_test::wasm_bindgen_test]
+    fn counts_the_transfer_fee_of_every_row() {
+        let csv = format!("{ALICE},1000\n{BOB},150\n{BOB},99");
+        let batch = PayoutBatch::parse(&csv);
+
+        // 1% of 1000 is 10, of 150 rounds down to 1 and of 99 to 0
+        assert_eq!(batch.transfer_fees(100), Some(11));
+        assert!(batch.is_covered_by(1260, 100));
+        assert!(!batch.is_covered_by(1259, 100));
+        assert!(batch.is_covered_by(1249, 0));
+    }
+
+    #[wasm_bindgen_test::wasm_bindgen_test]
+    fn reports_invalid_rows() {
+        let csv = format!("{ALICE},10\nnot-an-address,1\n{BOB},-3\n{BOB},0\n{BOB}");
+        let batch = PayoutBatch::parse(&csv);
+
+        assert!(!batch.is_valid());
+        assert_eq!(batch.rows.len(), 1);
+        let lines: Vec<usize> = batch.invalid.iter().map(|row| row.line).collect();
+        assert_eq!(lines, vec![2, 3, 4, 5]);
+    }
+
+    #[wasm_bindgen_test::wasm_bindgen_test]
+    fn detects_total_overflow() {
+        let csv = format!("{ALICE},{}\n{BOB},1", u128::MAX);
+        let batch = PayoutBatch::parse(&csv);
+
+        assert_eq!(batch.total(), None);
+        assert!(!batch.is_valid());
+    }
+}
@@ -0,0 +1,70 @@
+use yew::{Component, Context};
+
+use crate::services::fees::FeeEstimate;
+use crate::services::{PreparedExtrinsic, TokenService};
+
+/// a prepared extrinsic with its fees, `call` describes it on the fee confirmation
+pub struct PreparedCall<C> {
+    pub call: C,
+    pub prepared: PreparedExtrinsic,
+    pub fees: FeeEstimate,
+}
+
+/// the call a page shows for fee confirmation, one at a time.
+///
+/// The nonce reserved for the call is given back when it is replaced or canceled, and after signing.
+pub struct PendingCall<C>(Option<PreparedCall<C>>);
+
+impl<C> Default for PendingCall<C> {
+    fn default() -> Self {
+        PendingCall(None)
+    }
+}
+
+impl<C> PendingCall<C> {
+    pub fn get(&self) -> Option<&PreparedCall<C>> {
+        self.0.as_ref()
+    }
+
+    /// shows `call` for confirmation instead of the pending one
+    pub fn replace(&mut self, call: PreparedCall<C>) {
+        if let Some(replaced) = self.0.replace(call) {
+            replaced.prepared.release_nonce();
+        }
+    }
+
+    pub fn cancel(&mut self) {
+        if let Some(canceled) = self.0.take() {
+            canceled.prepared.release_nonce();
+        }
+    }
+
+    /// signs and submits the pending call, `submitted` is sent once it is finalized and `failed` if it fails.
+    ///
+    /// Returns whether a call was pending.
+    pub fn confirm<P: Component>(
+        &mut self,
+        ctx: &Context<P>,
+        token_service: Option<&TokenService>,
+        submitted: P::Message,
+        failed: fn(anyhow::Error) -> P::Message,
+    ) -> bool {
+        let Some(token_service) = token_service.cloned() else {
+            return false;
+        };
+        let Some(pending) = self.0.take() else {
+            return false;
+        };
+        ctx.link().send_future(async move {
+            match pending
+                .prepared
+                .sign_and_submit(&token_service.client)
+                .await
+            {
+                Ok(_) => submitted,
+                Err(err) => failed(err),
+            }
+        });
+        true
+    }
+}
@@ -0,0 +1,158 @@
+use anyhow::anyhow;
+use js_sys::Promise;
+use std::str::FromStr;
+use subxt::ext::codec::{Decode, Encode};
+use subxt::utils::AccountId32;
+use subxt::{OnlineClient, PolkadotConfig};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+
+use crate::services::contracts_api::query;
+use crate::services::psp22::{
+    encode_call_data, LangError, PermitSignature, TokenCall, PERMIT_DOMAIN, PERMIT_NONCE_SELECTOR,
+};
+use crate::services::Account;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_name = signRaw)]
+    pub fn js_sign_raw(data: String, source: String, address: String) -> Promise;
+}
+
+/// an approval signed off-chain by the owner, which any account can submit with `YToken::permit`
+#[derive(Clone, Debug, Decode, Encode, PartialEq)]
+pub struct SignedPermit {
+    pub owner: AccountId32,
+    pub spender: AccountId32,
+    pub value: u128,
+    /// timestamp in milliseconds after which the permit can't be used anymore
+    pub deadline: u64,
+    pub signature: PermitSignature,
+}
+
+impl SignedPermit {
+    /// hex of the scale encoded permit, handed from the owner to the relayer
+    pub fn to_hex(&self) -> String {
+        format!("0x{}", hex::encode(self.encode()))
+    }
+
+    pub fn from_hex(input: &str) -> Result<Self, anyhow::Error> {
+        let bytes = hex::decode(input.trim().trim_start_matches("0x"))?;
+        Ok(SignedPermit::decode(&mut &bytes[..])?)
+    }
+
+    pub fn call(&self) -> TokenCall {
+        TokenCall::Permit {
+            owner: self.owner.clone(),
+            spender: self.spender.clone(),
+            value: self.value,
+            deadline: self.deadline,
+            signature: self.signature.clone(),
+        }
+    }
+}
+
+/// the payload the owner signs, same as `YToken::permit_message` without the `<Bytes>` wrapping
+/// which the extension adds in `signRaw`
+pub fn permit_payload(
+    contract: &AccountId32,
+    owner: &AccountId32,
+    spender: &AccountId32,
+    value: u128,
+    nonce: u64,
+    deadline: u64,
+) -> Vec<u8> {
+    (
+        PERMIT_DOMAIN,
+        contract,
+        owner,
+        spender,
+        value,
+        nonce,
+        deadline,
+    )
+        .encode()
+}
+
+/// signs a permit for `spender` with the extension of `account` via `signRaw`
+pub async fn sign_permit(
+    api: &OnlineClient<PolkadotConfig>,
+    contract: &AccountId32,
+    account: &Account,
+    spender: AccountId32,
+    value: u128,
+    deadline: u64,
+) -> Result<SignedPermit, anyhow::Error> {
+    let owner = AccountId32::from_str(&account.address)
+        .map_err(|_| anyhow!("Invalid account address: {}", account.address))?;
+    let nonce = query::<Result<u64, LangError>>(
+        api,
+        &owner,
+        contract,
+        encode_call_data(PERMIT_NONCE_SELECTOR, &owner),
+    )
+    .await?
+    .map_err(|err| anyhow!("{err:?}"))?;
+    let payload = permit_payload(contract, &owner, &spender, value, nonce, deadline);
+
+    let result = JsFuture::from(js_sign_raw(
+        format!("0x{}", hex::encode(payload)),
+        account.source.clone(),
+        account.address.clone(),
+    ))
+    .await
+    .map_err(|js_err| anyhow!("{js_err:?}"))?;
+    let signature = result
+        .as_string()
+        .ok_or(anyhow!("Error converting JsValue into String"))?;
+    let signature = hex::decode(signature.trim_start_matches("0x"))?;
+
+    Ok(SignedPermit {
+        owner,
+        spender,
+        value,
+        deadline,
+        signature: permit_signature(&account.ty, &signature)?,
+    })
+}
+
+/// converts the raw signature of the extension, `ty` is the key type of the account
+fn permit_signature(ty: &str, signature: &[u8]) -> Result<PermitSignature, anyhow::Error> {
+    match ty {
+        "sr25519" => Ok(PermitSignature::Sr25519(signature.try_into().map_err(
+            |_| anyhow!("Invalid sr25519 signature length: {}", signature.len()),
+        )?)),
+        "ecdsa" => Ok(PermitSignature::Ecdsa(signature.try_into().map_err(
+            |_| anyhow!("Invalid ecdsa signature length: {}", signature.len()),
+        )?)),
+        ty => Err(anyhow!("Permits can't be signed with {ty} accounts")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[wasm_bindgen_test::wasm_bindgen_test]
+    fn signed_permit_roundtrips_through_hex() {
+        let permit = SignedPermit {
+            owner: AccountId32([1; 32]),
+            spender: AccountId32([2; 32]),
+            value: 10,
+            deadline: 1000,
+            signature: PermitSignature::Sr25519([3; 64]),
+        };
+
+        assert_eq!(SignedPermit::from_hex(&permit.to_hex()).unwrap(), permit);
+    }
+
+    #[wasm_bindgen_test::wasm_bindgen_test]
+    fn rejects_ed25519_accounts() {
+        assert!(permit_signature("ed25519", &[0; 64]).is_err());
+        assert!(permit_signature("sr25519", &[0; 65]).is_err());
+        assert!(matches!(
+            permit_signature("ecdsa", &[0; 65]),
+            Ok(PermitSignature::Ecdsa(_))
+        ));
+    }
+}
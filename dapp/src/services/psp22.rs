@@ -0,0 +1,303 @@
+use std::fmt;
+use subxt::ext::codec::{Decode, Encode};
+use subxt::utils::AccountId32;
+
+use crate::services::vesting::VestingSchedule;
+
+/// selector of `PSP22::transfer`
+pub const TRANSFER_SELECTOR: [u8; 4] = [0xdb, 0x20, 0xf9, 0xf5];
+/// selector of `PSP22::approve`
+pub const APPROVE_SELECTOR: [u8; 4] = [0xb2, 0x0f, 0x1b, 0xbd];
+/// selector of `PSP22::balance_of`
+pub const BALANCE_OF_SELECTOR: [u8; 4] = [0x65, 0x68, 0x38, 0x2f];
+/// selector of `PSP22::allowance`
+pub const ALLOWANCE_SELECTOR: [u8; 4] = [0x4d, 0x47, 0xd9, 0x21];
+/// selector of `PSP22Mintable::mint`
+pub const MINT_SELECTOR: [u8; 4] = [0xfc, 0x3c, 0x75, 0xd4];
+/// selector of `YToken::permit`
+pub const PERMIT_SELECTOR: [u8; 4] = [0x84, 0xaf, 0xf4, 0x99];
+/// selector of `YToken::permit_nonce`
+pub const PERMIT_NONCE_SELECTOR: [u8; 4] = [0x06, 0x81, 0x71, 0x6f];
+/// selector of `YToken::delegate`
+pub const DELEGATE_SELECTOR: [u8; 4] = [0xc5, 0x96, 0x54, 0xfe];
+/// selector of `YToken::delegates`
+pub const DELEGATES_SELECTOR: [u8; 4] = [0x80, 0x4c, 0x53, 0xd9];
+/// selector of `YToken::get_votes`
+pub const GET_VOTES_SELECTOR: [u8; 4] = [0x5f, 0x9d, 0x37, 0x4c];
+/// selector of `YToken::release`
+pub const RELEASE_SELECTOR: [u8; 4] = [0x3f, 0x2b, 0xe1, 0x52];
+/// selector of `YToken::vesting_schedule`
+pub const VESTING_SCHEDULE_SELECTOR: [u8; 4] = [0x71, 0xf9, 0xa8, 0x99];
+/// selector of `YToken::freeze`
+pub const FREEZE_SELECTOR: [u8; 4] = [0xb5, 0xfb, 0x2d, 0xe4];
+/// selector of `YToken::unfreeze`
+pub const UNFREEZE_SELECTOR: [u8; 4] = [0x40, 0x71, 0x5f, 0x40];
+/// selector of `YToken::frozen_accounts`
+pub const FROZEN_ACCOUNTS_SELECTOR: [u8; 4] = [0x7a, 0x63, 0x0d, 0xde];
+/// selector of `YToken::frozen_count`
+pub const FROZEN_COUNT_SELECTOR: [u8; 4] = [0xd1, 0x28, 0xff, 0xa3];
+/// selector of `YToken::claim_faucet`
+pub const CLAIM_FAUCET_SELECTOR: [u8; 4] = [0x06, 0xad, 0xbb, 0x82];
+/// selector of `YToken::faucet`
+pub const FAUCET_SELECTOR: [u8; 4] = [0x91, 0xbd, 0x0a, 0x53];
+/// selector of `YToken::next_faucet_claim`
+pub const NEXT_FAUCET_CLAIM_SELECTOR: [u8; 4] = [0xdc, 0x22, 0x45, 0x40];
+/// selector of `YToken::mint_emissions`
+pub const MINT_EMISSIONS_SELECTOR: [u8; 4] = [0x8c, 0x44, 0x16, 0xcf];
+/// selector of `YToken::pending_emissions`
+pub const PENDING_EMISSIONS_SELECTOR: [u8; 4] = [0x63, 0xab, 0xec, 0x44];
+/// selector of `YToken::emission`
+pub const EMISSION_SELECTOR: [u8; 4] = [0x93, 0x19, 0xc2, 0x62];
+/// selector of `YToken::supply_cap`
+pub const SUPPLY_CAP_SELECTOR: [u8; 4] = [0x81, 0xa4, 0x39, 0xad];
+/// selector of `YToken::total_supply_at`
+pub const TOTAL_SUPPLY_AT_SELECTOR: [u8; 4] = [0x37, 0x27, 0x36, 0x9d];
+/// selector of `YToken::transfer_fee`
+pub const TRANSFER_FEE_SELECTOR: [u8; 4] = [0xc3, 0x74, 0x76, 0x1c];
+/// selector of `YToken::transfer_fee_bps`
+pub const TRANSFER_FEE_BPS_SELECTOR: [u8; 4] = [0x64, 0xbb, 0xcd, 0x89];
+/// selector of `YToken::is_fee_exempt`
+pub const IS_FEE_EXEMPT_SELECTOR: [u8; 4] = [0x77, 0xd4, 0x23, 0xc1];
+/// selector of `YToken::treasury`
+pub const TREASURY_SELECTOR: [u8; 4] = [0xa9, 0xb7, 0x84, 0x61];
+/// selector of `Ownable::owner`
+pub const OWNER_SELECTOR: [u8; 4] = [0x4f, 0xa4, 0x3c, 0x8c];
+
+/// domain of the payload signed for `YToken::permit`
+pub const PERMIT_DOMAIN: &[u8] = b"YToken::permit";
+
+/// call data of a contract message: the selector followed by the scale encoded arguments
+pub fn encode_call_data<Args: Encode>(selector: [u8; 4], args: Args) -> Vec<u8> {
+    let mut data = selector.to_vec();
+    args.encode_to(&mut data);
+    data
+}
+
+/// error of the ink! dispatcher, wraps the result of every message
+#[derive(Clone, Debug, Decode, PartialEq)]
+pub enum LangError {
+    #[codec(index = 1)]
+    CouldNotReadInput,
+}
+
+/// `PSP22Error` of the openbrush PSP22 implementation
+#[derive(Clone, Debug, Decode, PartialEq)]
+pub enum PSP22Error {
+    Custom(String),
+    InsufficientBalance,
+    InsufficientAllowance,
+    ZeroRecipientAddress,
+    ZeroSenderAddress,
+    SafeTransferCheckFailed(String),
+}
+
+impl fmt::Display for PSP22Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PSP22Error::Custom(message) => write!(f, "Custom({message})"),
+            PSP22Error::SafeTransferCheckFailed(message) => {
+                write!(f, "SafeTransferCheckFailed({message})")
+            }
+            error => write!(f, "{error:?}"),
+        }
+    }
+}
+
+/// events emitted by `YToken`, in the order they are declared in the contract
+#[derive(Clone, Debug, Decode, PartialEq)]
+pub enum TokenEvent {
+    Transfer {
+        from: Option<AccountId32>,
+        to: Option<AccountId32>,
+        value: u128,
+    },
+    Approval {
+        owner: AccountId32,
+        spender: AccountId32,
+        value: u128,
+    },
+    VestingCreated {
+        beneficiary: AccountId32,
+        schedule: VestingSchedule,
+    },
+    Released {
+        beneficiary: AccountId32,
+        amount: u128,
+    },
+    DelegateChanged {
+        delegator: AccountId32,
+        from_delegate: Option<AccountId32>,
+        to_delegate: AccountId32,
+    },
+    DelegateVotesChanged {
+        delegate: AccountId32,
+        previous_votes: u128,
+        new_votes: u128,
+    },
+    AccountFrozen {
+        account: AccountId32,
+    },
+    AccountUnfrozen {
+        account: AccountId32,
+    },
+    Rescued {
+        token: Option<AccountId32>,
+        to: AccountId32,
+        amount: u128,
+    },
+}
+
+/// signature of a permit payload, same encoding as `PermitSignature` of the contract
+#[derive(Clone, Debug, Decode, Encode, PartialEq)]
+pub enum PermitSignature {
+    Sr25519([u8; 64]),
+    Ecdsa([u8; 65]),
+}
+
+/// the state changing messages of `YToken` which can be previewed before signing
+#[derive(Clone, Debug, PartialEq)]
+pub enum TokenCall {
+    Transfer {
+        to: AccountId32,
+        value: u128,
+    },
+    Mint {
+        account: AccountId32,
+        amount: u128,
+    },
+    Approve {
+        spender: AccountId32,
+        value: u128,
+    },
+    Permit {
+        owner: AccountId32,
+        spender: AccountId32,
+        value: u128,
+        deadline: u64,
+        signature: PermitSignature,
+    },
+    /// unlocks the vested tokens of the caller
+    Release,
+    Delegate {
+        delegatee: AccountId32,
+    },
+    /// mints the faucet amount to the caller
+    ClaimFaucet,
+    /// mints the pending emissions to the emission recipient
+    MintEmissions,
+    /// owner only
+    Freeze {
+        account: AccountId32,
+    },
+    /// owner only
+    Unfreeze {
+        account: AccountId32,
+    },
+}
+
+impl TokenCall {
+    pub fn call_data(&self) -> Vec<u8> {
+        match self {
+            TokenCall::Transfer { to, value } => {
+                encode_call_data(TRANSFER_SELECTOR, (to, value, Vec::<u8>::new()))
+            }
+            TokenCall::Mint { account, amount } => {
+                encode_call_data(MINT_SELECTOR, (account, amount))
+            }
+            TokenCall::Approve { spender, value } => {
+                encode_call_data(APPROVE_SELECTOR, (spender, value))
+            }
+            TokenCall::Permit {
+                owner,
+                spender,
+                value,
+                deadline,
+                signature,
+            } => encode_call_data(
+                PERMIT_SELECTOR,
+                (owner, spender, value, deadline, signature),
+            ),
+            TokenCall::Release => RELEASE_SELECTOR.to_vec(),
+            TokenCall::Delegate { delegatee } => encode_call_data(DELEGATE_SELECTOR, delegatee),
+            TokenCall::ClaimFaucet => CLAIM_FAUCET_SELECTOR.to_vec(),
+            TokenCall::MintEmissions => MINT_EMISSIONS_SELECTOR.to_vec(),
+            TokenCall::Freeze { account } => encode_call_data(FREEZE_SELECTOR, account),
+            TokenCall::Unfreeze { account } => encode_call_data(UNFREEZE_SELECTOR, account),
+        }
+    }
+
+    /// the account the call is targeted at, besides the caller
+    pub fn counterparty(&self) -> Option<&AccountId32> {
+        match self {
+            TokenCall::Transfer { to, .. } => Some(to),
+            TokenCall::Mint { account, .. } => Some(account),
+            TokenCall::Approve { spender, .. } => Some(spender),
+            TokenCall::Permit { spender, .. } => Some(spender),
+            TokenCall::Release => None,
+            TokenCall::Delegate { delegatee } => Some(delegatee),
+            TokenCall::ClaimFaucet => None,
+            TokenCall::MintEmissions => None,
+            TokenCall::Freeze { account } => Some(account),
+            TokenCall::Unfreeze { account } => Some(account),
+        }
+    }
+}
+
+impl fmt::Display for TokenCall {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TokenCall::Transfer { to, value } => write!(f, "Transfer {value} tokens to {to}"),
+            TokenCall::Mint { account, amount } => write!(f, "Mint {amount} tokens to {account}"),
+            TokenCall::Approve { spender, value } => {
+                write!(f, "Approve {spender} to spend {value} tokens")
+            }
+            TokenCall::Permit {
+                owner,
+                spender,
+                value,
+                ..
+            } => write!(f, "Permit {spender} to spend {value} tokens of {owner}"),
+            TokenCall::Release => write!(f, "Release vested tokens"),
+            TokenCall::Delegate { delegatee } => write!(f, "Delegate votes to {delegatee}"),
+            TokenCall::ClaimFaucet => write!(f, "Claim tokens from the faucet"),
+            TokenCall::MintEmissions => write!(f, "Mint pending emissions"),
+            TokenCall::Freeze { account } => write!(f, "Freeze {account}"),
+            TokenCall::Unfreeze { account } => write!(f, "Unfreeze {account}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[wasm_bindgen_test::wasm_bindgen_test]
+    fn encodes_transfer_call_data() {
+        let bob = AccountId32([2; 32]);
+        let data = TokenCall::Transfer {
+            to: bob.clone(),
+            value: 5,
+        }
+        .call_data();
+
+        assert_eq!(&data[..4], &TRANSFER_SELECTOR);
+        assert_eq!(&data[4..36], &bob.0);
+        assert_eq!(data.len(), 4 + 32 + 16 + 1);
+    }
+
+    #[wasm_bindgen_test::wasm_bindgen_test]
+    fn decodes_transfer_event() {
+        let bob = AccountId32([2; 32]);
+        let mut data = vec![0u8];
+        (None::<AccountId32>, Some(bob.clone()), 7u128).encode_to(&mut data);
+
+        assert_eq!(
+            TokenEvent::decode(&mut &data[..]).unwrap(),
+            TokenEvent::Transfer {
+                from: None,
+                to: Some(bob),
+                value: 7
+            }
+        );
+    }
+}
@@ -0,0 +1,198 @@
+use anyhow::anyhow;
+use serde_json::json;
+use subxt::config::extrinsic_params::Era;
+use subxt::config::polkadot::{PlainTip, PolkadotExtrinsicParamsBuilder};
+use subxt::ext::codec::Encode;
+use subxt::utils::H256;
+use subxt::{OnlineClient, PolkadotConfig};
+
+/// number of blocks a mortal transaction stays valid, starting at the checkpoint block
+pub const DEFAULT_MORTALITY_PERIOD: u64 = 64;
+
+/// user configurable parameters for signing an extrinsic
+#[derive(Clone, Debug, PartialEq)]
+pub struct SigningOptions {
+    /// validity period in blocks, `None` creates an immortal transaction
+    pub mortality_period: Option<u64>,
+    /// tip for the block author, paid on top of the fees
+    pub tip: u128,
+}
+
+impl Default for SigningOptions {
+    fn default() -> Self {
+        SigningOptions {
+            mortality_period: Some(DEFAULT_MORTALITY_PERIOD),
+            tip: 0,
+        }
+    }
+}
+
+/// the parameters of a partial extrinsic which end up in the signer payload.
+///
+/// The same values are used to create the partial extrinsic with subxt and to build the
+/// payload for the browser extension, so both sign exactly the same bytes.
+#[derive(Clone, Debug)]
+pub struct SignerPayloadParams {
+    pub era: Era,
+    /// hash of the block the mortality period starts at, the genesis hash for immortal transactions
+    pub checkpoint_hash: H256,
+    /// number of the block the mortality period starts at
+    pub checkpoint_number: u32,
+    pub nonce: u64,
+    pub tip: u128,
+}
+
+impl SignerPayloadParams {
+    /// resolves the options against the chain: mortal transactions are checkpointed at the latest finalized block.
+    ///
+    /// `nonce` is reserved by the caller, see [`NonceManager`](crate::services::NonceManager).
+    pub async fn fetch(
+        api: &OnlineClient<PolkadotConfig>,
+        nonce: u64,
+        options: &SigningOptions,
+    ) -> Result<Self, anyhow::Error> {
+        let (era, checkpoint_hash, checkpoint_number) = match options.mortality_period {
+            Some(period) => {
+                let hash = api.rpc().finalized_head().await?;
+                let header = api
+                    .rpc()
+                    .header(Some(hash))
+                    .await?
+                    .ok_or(anyhow!("Header of finalized block {hash:?} not found"))?;
+                (
+                    Era::mortal(period, header.number as u64),
+                    hash,
+                    header.number,
+                )
+            }
+            None => (Era::Immortal, api.genesis_hash(), 0),
+        };
+
+        Ok(SignerPayloadParams {
+            era,
+            checkpoint_hash,
+            checkpoint_number,
+            nonce,
+            tip: options.tip,
+        })
+    }
+
+    /// the extrinsic params to create the partial extrinsic with
+    pub fn extrinsic_params(&self) -> PolkadotExtrinsicParamsBuilder<PolkadotConfig> {
+        PolkadotExtrinsicParamsBuilder::new()
+            .tip(PlainTip::new(self.tip))
+            .era(self.era, self.checkpoint_hash)
+    }
+
+    /// the `SignerPayloadJSON` the browser extension signs for `call_data`, numbers are given as big-endian hex
+    pub fn extension_payload(
+        &self,
+        call_data: &[u8],
+        spec_version: u32,
+        transaction_version: u32,
+        genesis_hash: H256,
+        signed_extensions: Vec<String>,
+        address: &str,
+    ) -> serde_json::Value {
+        json!({
+            "specVersion": encode_to_hex_reverse(&spec_version),
+            "transactionVersion": encode_to_hex_reverse(&transaction_version),
+            "address": address,
+            "blockHash": encode_to_hex(&self.checkpoint_hash),
+            "blockNumber": encode_to_hex_reverse(&self.checkpoint_number),
+            "era": encode_to_hex(&self.era),
+            "genesisHash": encode_to_hex(&genesis_hash),
+            "method": to_hex(call_data),
+            "nonce": encode_to_hex_reverse(&self.nonce),
+            "signedExtensions": signed_extensions,
+            "tip": encode_to_hex_reverse(&self.tip),
+            "version": 4,
+        })
+    }
+}
+
+fn to_hex(bytes: impl AsRef<[u8]>) -> String {
+    format!("0x{}", hex::encode(bytes.as_ref()))
+}
+
+fn encode_to_hex<E: Encode>(input: &E) -> String {
+    format!("0x{}", hex::encode(input.encode()))
+}
+
+/// this is used because numeric types (e.g. u32) are encoded as little-endian via scale (e.g. 9430 -> d6240000)
+/// while we need a big-endian representation for the json (e.g. 9430 -> 000024d6).
+fn encode_to_hex_reverse<E: Encode>(input: &E) -> String {
+    let mut bytes = input.encode();
+    bytes.reverse();
+    format!("0x{}", hex::encode(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use subxt::config::extrinsic_params::ExtrinsicParams;
+    use subxt::config::polkadot::PolkadotExtrinsicParams;
+    use subxt::ext::codec::Compact;
+
+    /// the bytes the extension signs for `payload`, encoded like the `ExtrinsicPayload` of polkadot-js
+    fn extension_payload_bytes(payload: &serde_json::Value) -> Vec<u8> {
+        let field = |name: &str| hex::decode(&payload[name].as_str().unwrap()[2..]).unwrap();
+        let number = |name: &str| {
+            field(name)
+                .iter()
+                .fold(0u128, |number, byte| (number << 8) | u128::from(*byte))
+        };
+        let mut bytes = field("method");
+        bytes.extend(field("era"));
+        Compact(number("nonce")).encode_to(&mut bytes);
+        Compact(number("tip")).encode_to(&mut bytes);
+        (number("specVersion") as u32).encode_to(&mut bytes);
+        (number("transactionVersion") as u32).encode_to(&mut bytes);
+        bytes.extend(field("genesisHash"));
+        bytes.extend(field("blockHash"));
+        bytes
+    }
+
+    #[wasm_bindgen_test::wasm_bindgen_test]
+    fn extension_signs_the_signer_payload_of_subxt() {
+        let params = SignerPayloadParams {
+            era: Era::mortal(DEFAULT_MORTALITY_PERIOD, 1_234),
+            checkpoint_hash: H256([7; 32]),
+            checkpoint_number: 1_234,
+            nonce: 5,
+            tip: 1_000_000,
+        };
+        let (call_data, spec_version, transaction_version) = (vec![4, 0, 1, 2, 3], 9_430, 24);
+        let genesis_hash = H256([1; 32]);
+
+        let payload = params.extension_payload(
+            &call_data,
+            spec_version,
+            transaction_version,
+            genesis_hash,
+            Vec::new(),
+            "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY",
+        );
+        let extrinsic_params =
+            <PolkadotExtrinsicParams<PolkadotConfig> as ExtrinsicParams<H256>>::new(
+                spec_version,
+                transaction_version,
+                params.nonce,
+                genesis_hash,
+                params.extrinsic_params(),
+            );
+        let mut signer_payload = call_data;
+        extrinsic_params.encode_extra_to(&mut signer_payload);
+        extrinsic_params.encode_additional_to(&mut signer_payload);
+
+        assert_eq!(extension_payload_bytes(&payload), signer_payload);
+    }
+
+    #[wasm_bindgen_test::wasm_bindgen_test]
+    fn default_options_are_mortal_without_tip() {
+        let options = SigningOptions::default();
+
+        assert_eq!(options.mortality_period, Some(DEFAULT_MORTALITY_PERIOD));
+        assert_eq!(options.tip, 0);
+    }
+}
@@ -0,0 +1,367 @@
+use anyhow::anyhow;
+use subxt::ext::codec::Decode;
+use subxt::utils::AccountId32;
+use subxt::{OnlineClient, PolkadotConfig};
+
+use crate::services::contracts_api::{dry_run_call, query, ContractDryRun};
+use crate::services::psp22::{
+    encode_call_data, LangError, PSP22Error, TokenCall, TokenEvent, ALLOWANCE_SELECTOR,
+    BALANCE_OF_SELECTOR, TRANSFER_FEE_SELECTOR, TREASURY_SELECTOR,
+};
+
+/// predicted balance of an account after the call
+#[derive(Clone, Debug, PartialEq)]
+pub struct BalanceChange {
+    pub account: AccountId32,
+    pub before: u128,
+    pub after: u128,
+}
+
+/// predicted allowance of `spender` on the tokens of `owner` after the call
+#[derive(Clone, Debug, PartialEq)]
+pub struct AllowanceChange {
+    pub owner: AccountId32,
+    pub spender: AccountId32,
+    pub before: u128,
+    pub after: u128,
+}
+
+/// fee of a transfer, paid by the sender to the treasury on top of the amount
+#[derive(Clone, Debug, PartialEq)]
+pub struct TransferFee {
+    pub amount: u128,
+    pub fee: u128,
+    pub treasury: AccountId32,
+}
+
+impl TransferFee {
+    /// amount and fee together, what the sender's balance decreases by
+    pub fn total(&self) -> u128 {
+        self.amount.saturating_add(self.fee)
+    }
+}
+
+/// outcome of a dry-run of a token call
+#[derive(Clone, Debug, PartialEq)]
+pub struct Simulation {
+    pub call: TokenCall,
+    /// `Err` with the `PSP22Error` or dispatch error if the call would fail
+    pub result: Result<(), String>,
+    /// events emitted by the token during the dry-run
+    pub events: Vec<TokenEvent>,
+    pub balance_changes: Vec<BalanceChange>,
+    pub allowance_changes: Vec<AllowanceChange>,
+    /// `None` if the call is no transfer or the transfer is free
+    pub transfer_fee: Option<TransferFee>,
+}
+
+impl Simulation {
+    /// a failing call must not be submitted
+    pub fn is_blocked(&self) -> bool {
+        self.result.is_err()
+    }
+}
+
+/// dry-runs `call` as `caller` and predicts the balance and allowance changes
+pub async fn simulate(
+    api: &OnlineClient<PolkadotConfig>,
+    contract: &AccountId32,
+    caller: &AccountId32,
+    call: TokenCall,
+) -> Result<Simulation, anyhow::Error> {
+    let dry_run = dry_run_call(api, caller, contract, 0, call.call_data()).await?;
+    let result = decode_result(&dry_run);
+    let events: Vec<TokenEvent> = dry_run
+        .contract_events
+        .iter()
+        .filter(|event| &event.contract == contract)
+        .filter_map(|event| TokenEvent::decode(&mut &event.data[..]).ok())
+        .collect();
+
+    let mut simulation = Simulation {
+        call,
+        result,
+        events,
+        balance_changes: Vec::new(),
+        allowance_changes: Vec::new(),
+        transfer_fee: None,
+    };
+    if simulation.is_blocked() {
+        return Ok(simulation);
+    }
+    if let TokenCall::Transfer { to, value } = &simulation.call {
+        simulation.transfer_fee = transfer_fee(api, caller, contract, to, *value).await?;
+    }
+
+    // nodes which don't collect events in dry-runs still get a preview based on the call
+    let events = if simulation.events.is_empty() {
+        let mut events = expected_events(caller, &simulation.call);
+        if let Some(fee) = &simulation.transfer_fee {
+            events.push(TokenEvent::Transfer {
+                from: Some(caller.clone()),
+                to: Some(fee.treasury.clone()),
+                value: fee.fee,
+            });
+        }
+        events
+    } else {
+        simulation.events.clone()
+    };
+
+    for (account, credit, debit) in balance_deltas(caller, &simulation.call, &events) {
+        let before = balance_of(api, caller, contract, &account).await?;
+        simulation.balance_changes.push(BalanceChange {
+            after: before.saturating_add(credit).saturating_sub(debit),
+            account,
+            before,
+        });
+    }
+    for event in &events {
+        if let TokenEvent::Approval {
+            owner,
+            spender,
+            value,
+        } = event
+        {
+            let before = allowance(api, caller, contract, owner, spender).await?;
+            simulation.allowance_changes.push(AllowanceChange {
+                owner: owner.clone(),
+                spender: spender.clone(),
+                before,
+                after: *value,
+            });
+        }
+    }
+    Ok(simulation)
+}
+
+fn decode_result(dry_run: &ContractDryRun) -> Result<(), String> {
+    let data = dry_run.return_data()?;
+    match Result::<Result<(), PSP22Error>, LangError>::decode(&mut &data[..]) {
+        Ok(Ok(Ok(()))) => Ok(()),
+        Ok(Ok(Err(err))) => Err(describe_error(&err)),
+        Ok(Err(err)) => Err(format!("LangError::{err:?}")),
+        Err(err) => Err(format!("Could not decode return value: {err}")),
+    }
+}
+
+/// the reason a call is blocked, owner-only messages like `mint` fail for other accounts
+fn describe_error(err: &PSP22Error) -> String {
+    match err {
+        PSP22Error::Custom(message) if message == "O::CallerIsNotOwner" => String::from(
+            "Only the owner of the token can make this call, other accounts claim tokens from the faucet",
+        ),
+        err => format!("PSP22Error::{err}"),
+    }
+}
+
+/// the events a successful call emits
+fn expected_events(caller: &AccountId32, call: &TokenCall) -> Vec<TokenEvent> {
+    match call {
+        TokenCall::Transfer { to, value } => vec![TokenEvent::Transfer {
+            from: Some(caller.clone()),
+            to: Some(to.clone()),
+            value: *value,
+        }],
+        TokenCall::Mint { account, amount } => vec![TokenEvent::Transfer {
+            from: None,
+            to: Some(account.clone()),
+            value: *amount,
+        }],
+        TokenCall::Approve { spender, value } => vec![TokenEvent::Approval {
+            owner: caller.clone(),
+            spender: spender.clone(),
+            value: *value,
+        }],
+        TokenCall::Permit {
+            owner,
+            spender,
+            value,
+            ..
+        } => vec![TokenEvent::Approval {
+            owner: owner.clone(),
+            spender: spender.clone(),
+            value: *value,
+        }],
+        // released tokens stay on the account, only the lock changes
+        TokenCall::Release => Vec::new(),
+        // delegation moves votes, not tokens
+        TokenCall::Delegate { .. } => Vec::new(),
+        // the amount is configured in the contract, only the events of the dry-run show it
+        TokenCall::ClaimFaucet => Vec::new(),
+        TokenCall::MintEmissions => Vec::new(),
+        TokenCall::Freeze { account } => vec![TokenEvent::AccountFrozen {
+            account: account.clone(),
+        }],
+        TokenCall::Unfreeze { account } => vec![TokenEvent::AccountUnfrozen {
+            account: account.clone(),
+        }],
+    }
+}
+
+/// credit and debit per account, the caller and the counterparty of the call are always listed first
+fn balance_deltas(
+    caller: &AccountId32,
+    call: &TokenCall,
+    events: &[TokenEvent],
+) -> Vec<(AccountId32, u128, u128)> {
+    let mut deltas: Vec<(AccountId32, u128, u128)> = vec![(caller.clone(), 0, 0)];
+    if let Some(counterparty) = call.counterparty().filter(|account| *account != caller) {
+        deltas.push((counterparty.clone(), 0, 0));
+    }
+    let mut entry = |account: &AccountId32| -> usize {
+        match deltas.iter().position(|(a, _, _)| a == account) {
+            Some(i) => i,
+            None => {
+                deltas.push((account.clone(), 0, 0));
+                deltas.len() - 1
+            }
+        }
+    };
+    let mut changes = Vec::new();
+    for event in events {
+        if let TokenEvent::Transfer { from, to, value } = event {
+            if let Some(from) = from {
+                changes.push((entry(from), 0, *value));
+            }
+            if let Some(to) = to {
+                changes.push((entry(to), *value, 0));
+            }
+        }
+    }
+    for (i, credit, debit) in changes {
+        deltas[i].1 = deltas[i].1.saturating_add(credit);
+        deltas[i].2 = deltas[i].2.saturating_add(debit);
+    }
+    deltas
+}
+
+async fn balance_of(
+    api: &OnlineClient<PolkadotConfig>,
+    caller: &AccountId32,
+    contract: &AccountId32,
+    account: &AccountId32,
+) -> Result<u128, anyhow::Error> {
+    query::<Result<u128, LangError>>(
+        api,
+        caller,
+        contract,
+        encode_call_data(BALANCE_OF_SELECTOR, account),
+    )
+    .await?
+    .map_err(|err| anyhow!("{err:?}"))
+}
+
+/// the fee the caller pays for transferring `value` to `to`, `None` if the transfer is free
+async fn transfer_fee(
+    api: &OnlineClient<PolkadotConfig>,
+    caller: &AccountId32,
+    contract: &AccountId32,
+    to: &AccountId32,
+    value: u128,
+) -> Result<Option<TransferFee>, anyhow::Error> {
+    let fee = query::<Result<u128, LangError>>(
+        api,
+        caller,
+        contract,
+        encode_call_data(TRANSFER_FEE_SELECTOR, (caller, to, value)),
+    )
+    .await?
+    .map_err(|err| anyhow!("{err:?}"))?;
+    if fee == 0 {
+        return Ok(None);
+    }
+    let treasury = query::<Result<Option<AccountId32>, LangError>>(
+        api,
+        caller,
+        contract,
+        TREASURY_SELECTOR.to_vec(),
+    )
+    .await?
+    .map_err(|err| anyhow!("{err:?}"))?;
+    Ok(treasury.map(|treasury| TransferFee {
+        amount: value,
+        fee,
+        treasury,
+    }))
+}
+
+async fn allowance(
+    api: &OnlineClient<PolkadotConfig>,
+    caller: &AccountId32,
+    contract: &AccountId32,
+    owner: &AccountId32,
+    spender: &AccountId32,
+) -> Result<u128, anyhow::Error> {
+    query::<Result<u128, LangError>>(
+        api,
+        caller,
+        contract,
+        encode_call_data(ALLOWANCE_SELECTOR, (owner, spender)),
+    )
+    .await?
+    .map_err(|err| anyhow!("{err:?}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[wasm_bindgen_test::wasm_bindgen_test]
+    fn transfer_moves_balance_from_caller_to_recipient() {
+        let alice = AccountId32([1; 32]);
+        let bob = AccountId32([2; 32]);
+        let call = TokenCall::Transfer {
+            to: bob.clone(),
+            value: 10,
+        };
+        let deltas = balance_deltas(&alice, &call, &expected_events(&alice, &call));
+
+        assert_eq!(deltas, vec![(alice, 0, 10), (bob, 10, 0)]);
+    }
+
+    #[wasm_bindgen_test::wasm_bindgen_test]
+    fn transfer_fee_is_debited_from_caller() {
+        let alice = AccountId32([1; 32]);
+        let bob = AccountId32([2; 32]);
+        let treasury = AccountId32([3; 32]);
+        let call = TokenCall::Transfer {
+            to: bob.clone(),
+            value: 100,
+        };
+        let mut events = expected_events(&alice, &call);
+        events.push(TokenEvent::Transfer {
+            from: Some(alice.clone()),
+            to: Some(treasury.clone()),
+            value: 1,
+        });
+        let deltas = balance_deltas(&alice, &call, &events);
+
+        assert_eq!(
+            deltas,
+            vec![(alice, 0, 101), (bob, 100, 0), (treasury, 1, 0)]
+        );
+    }
+
+    #[wasm_bindgen_test::wasm_bindgen_test]
+    fn approve_changes_no_balance() {
+        let alice = AccountId32([1; 32]);
+        let bob = AccountId32([2; 32]);
+        let call = TokenCall::Approve {
+            spender: bob.clone(),
+            value: 10,
+        };
+        let deltas = balance_deltas(&alice, &call, &expected_events(&alice, &call));
+
+        assert_eq!(deltas, vec![(alice, 0, 0), (bob, 0, 0)]);
+    }
+
+    #[wasm_bindgen_test::wasm_bindgen_test]
+    fn names_the_owner_if_the_caller_is_not() {
+        let not_owner = PSP22Error::Custom(String::from("O::CallerIsNotOwner"));
+        let frozen = PSP22Error::Custom(String::from("AccountFrozen"));
+
+        assert!(describe_error(&not_owner).starts_with("Only the owner of the token"));
+        assert_eq!(describe_error(&frozen), "PSP22Error::Custom(AccountFrozen)");
+    }
+}
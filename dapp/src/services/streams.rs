@@ -0,0 +1,190 @@
+use anyhow::anyhow;
+use std::fmt;
+use subxt::ext::codec::Decode;
+use subxt::utils::AccountId32;
+use subxt::{OnlineClient, PolkadotConfig};
+
+use crate::services::contracts_api::{dry_run_call, query};
+use crate::services::psp22::{encode_call_data, LangError, PSP22Error};
+
+/// selector of `Streams::withdraw`
+pub const WITHDRAW_SELECTOR: [u8; 4] = [0x41, 0x0f, 0xcc, 0x9d];
+/// selector of `Streams::cancel`
+pub const CANCEL_SELECTOR: [u8; 4] = [0x97, 0x96, 0xe9, 0xa7];
+/// selector of `Streams::streams_of`
+pub const STREAMS_OF_SELECTOR: [u8; 4] = [0x2f, 0x4e, 0x66, 0xc7];
+/// selector of `Streams::stream_count_of`
+pub const STREAM_COUNT_OF_SELECTOR: [u8; 4] = [0xce, 0x75, 0x97, 0x31];
+
+/// `MAX_PAGE_LEN` of the streams contract
+const PAGE_LEN: u32 = 50;
+
+/// `StreamError` of the streams contract
+#[derive(Clone, Debug, Decode, PartialEq)]
+pub enum StreamError {
+    InvalidTimeRange,
+    ZeroDeposit,
+    StreamNotFound,
+    NotSender,
+    NotRecipient,
+    NothingToWithdraw,
+    NotFeeExempt,
+    TransferFailed(PSP22Error),
+}
+
+impl fmt::Display for StreamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StreamError::TransferFailed(err) => write!(f, "TransferFailed({err})"),
+            error => write!(f, "{error:?}"),
+        }
+    }
+}
+
+/// same encoding as `Stream` of the contract
+#[derive(Clone, Debug, Decode, PartialEq)]
+pub struct Stream {
+    pub sender: AccountId32,
+    pub recipient: AccountId32,
+    pub deposit: u128,
+    pub withdrawn: u128,
+    /// timestamps in milliseconds
+    pub start: u64,
+    pub stop: u64,
+}
+
+impl Stream {
+    /// amount accrued to the recipient at `now`, same computation as the contract
+    pub fn streamed_at(&self, now: u64) -> u128 {
+        if now <= self.start {
+            return 0;
+        }
+        if now >= self.stop {
+            return self.deposit;
+        }
+        let (elapsed, duration) = (
+            u128::from(now - self.start),
+            u128::from(self.stop - self.start),
+        );
+        self.deposit / duration * elapsed + self.deposit % duration * elapsed / duration
+    }
+
+    /// amount the recipient can withdraw at `now`
+    pub fn withdrawable_at(&self, now: u64) -> u128 {
+        self.streamed_at(now).saturating_sub(self.withdrawn)
+    }
+
+    /// amount the sender gets back when canceling at `now`
+    pub fn refundable_at(&self, now: u64) -> u128 {
+        self.deposit.saturating_sub(self.streamed_at(now))
+    }
+}
+
+/// the messages of the streams contract the dapp calls
+#[derive(Clone, Debug, PartialEq)]
+pub enum StreamCall {
+    /// the recipient withdraws the accrued tokens
+    Withdraw(u32),
+    /// the sender ends the stream
+    Cancel(u32),
+}
+
+impl StreamCall {
+    pub fn call_data(&self) -> Vec<u8> {
+        match self {
+            StreamCall::Withdraw(id) => encode_call_data(WITHDRAW_SELECTOR, id),
+            StreamCall::Cancel(id) => encode_call_data(CANCEL_SELECTOR, id),
+        }
+    }
+}
+
+impl fmt::Display for StreamCall {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StreamCall::Withdraw(id) => write!(f, "Withdraw from stream {id}"),
+            StreamCall::Cancel(id) => write!(f, "Cancel stream {id}"),
+        }
+    }
+}
+
+/// the active streams `account` sends or receives, with their ids
+pub async fn fetch_streams_of(
+    api: &OnlineClient<PolkadotConfig>,
+    contract: &AccountId32,
+    account: &AccountId32,
+) -> Result<Vec<(u32, Stream)>, anyhow::Error> {
+    let count = query::<Result<u32, LangError>>(
+        api,
+        account,
+        contract,
+        encode_call_data(STREAM_COUNT_OF_SELECTOR, account),
+    )
+    .await?
+    .map_err(|err| anyhow!("{err:?}"))?;
+    let mut streams = Vec::new();
+    for start in (0..count).step_by(PAGE_LEN as usize) {
+        let page = query::<Result<Vec<(u32, Stream)>, LangError>>(
+            api,
+            account,
+            contract,
+            encode_call_data(STREAMS_OF_SELECTOR, (account, start, PAGE_LEN)),
+        )
+        .await?
+        .map_err(|err| anyhow!("{err:?}"))?;
+        streams.extend(page);
+    }
+    Ok(streams)
+}
+
+/// dry-runs `call` as `account`, the error tells why the call would fail
+pub async fn dry_run_stream_call(
+    api: &OnlineClient<PolkadotConfig>,
+    contract: &AccountId32,
+    account: &AccountId32,
+    call: &StreamCall,
+) -> Result<Result<(), String>, anyhow::Error> {
+    let dry_run = dry_run_call(api, account, contract, 0, call.call_data()).await?;
+    let data = match dry_run.return_data() {
+        Ok(data) => data,
+        Err(err) => return Ok(Err(err)),
+    };
+    // `withdraw` returns the amount, `cancel` nothing, the error is decoded the same way
+    let result = match call {
+        StreamCall::Withdraw(_) => {
+            Result::<Result<u128, StreamError>, LangError>::decode(&mut &data[..])
+                .map(|result| result.map(|result| result.map(|_| ())))
+        }
+        StreamCall::Cancel(_) => {
+            Result::<Result<(), StreamError>, LangError>::decode(&mut &data[..])
+        }
+    };
+    Ok(match result {
+        Ok(Ok(Ok(()))) => Ok(()),
+        Ok(Ok(Err(err))) => Err(format!("StreamError::{err}")),
+        Ok(Err(err)) => Err(format!("LangError::{err:?}")),
+        Err(err) => Err(format!("Could not decode return value: {err}")),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[wasm_bindgen_test::wasm_bindgen_test]
+    fn splits_the_deposit_at_any_time() {
+        let stream = Stream {
+            sender: AccountId32([1; 32]),
+            recipient: AccountId32([2; 32]),
+            deposit: 1_000,
+            withdrawn: 100,
+            start: 1_000,
+            stop: 2_000,
+        };
+
+        assert_eq!(stream.withdrawable_at(500), 0);
+        assert_eq!(stream.withdrawable_at(1_500), 400);
+        assert_eq!(stream.refundable_at(1_500), 500);
+        assert_eq!(stream.withdrawable_at(3_000), 900);
+        assert_eq!(stream.refundable_at(3_000), 0);
+    }
+}
@@ -0,0 +1,87 @@
+use anyhow::anyhow;
+use subxt::ext::codec::Decode;
+use subxt::utils::AccountId32;
+use subxt::{OnlineClient, PolkadotConfig};
+
+use crate::services::contracts_api::query;
+use crate::services::psp22::{encode_call_data, LangError};
+
+/// selector of `TokenFactory::tokens`
+pub const TOKENS_SELECTOR: [u8; 4] = [0xa8, 0xb5, 0xb8, 0xba];
+/// selector of `TokenFactory::token_count`
+pub const TOKEN_COUNT_SELECTOR: [u8; 4] = [0x1c, 0x40, 0xad, 0x86];
+
+/// `MAX_PAGE_LEN` of the factory contract
+const PAGE_LEN: u32 = 50;
+
+/// registry entry of a token created by the factory, same encoding as `TokenInfo` of the contract
+#[derive(Clone, Debug, Decode, PartialEq)]
+pub struct TokenInfo {
+    pub token: AccountId32,
+    pub creator: AccountId32,
+    pub name: Option<String>,
+    pub symbol: Option<String>,
+    pub decimals: u8,
+    /// timestamp in milliseconds
+    pub created_at: u64,
+}
+
+impl TokenInfo {
+    /// name and symbol of the token, the address if it has neither
+    pub fn label(&self) -> String {
+        match (&self.name, &self.symbol) {
+            (Some(name), Some(symbol)) => format!("{name} ({symbol})"),
+            (Some(label), None) | (None, Some(label)) => label.clone(),
+            (None, None) => self.token.to_string(),
+        }
+    }
+}
+
+/// every token of the registry of `factory`, oldest first
+pub async fn fetch_registry(
+    api: &OnlineClient<PolkadotConfig>,
+    factory: &AccountId32,
+    origin: &AccountId32,
+) -> Result<Vec<TokenInfo>, anyhow::Error> {
+    let count =
+        query::<Result<u32, LangError>>(api, origin, factory, TOKEN_COUNT_SELECTOR.to_vec())
+            .await?
+            .map_err(|err| anyhow!("{err:?}"))?;
+    let mut tokens = Vec::new();
+    for start in (0..count).step_by(PAGE_LEN as usize) {
+        let page = query::<Result<Vec<TokenInfo>, LangError>>(
+            api,
+            origin,
+            factory,
+            encode_call_data(TOKENS_SELECTOR, (start, PAGE_LEN)),
+        )
+        .await?
+        .map_err(|err| anyhow!("{err:?}"))?;
+        tokens.extend(page);
+    }
+    Ok(tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[wasm_bindgen_test::wasm_bindgen_test]
+    fn label_falls_back_to_the_address() {
+        let mut info = TokenInfo {
+            token: AccountId32([1; 32]),
+            creator: AccountId32([2; 32]),
+            name: Some("Brand".into()),
+            symbol: Some("BRD".into()),
+            decimals: 12,
+            created_at: 0,
+        };
+        assert_eq!(info.label(), "Brand (BRD)");
+
+        info.name = None;
+        assert_eq!(info.label(), "BRD");
+
+        info.symbol = None;
+        assert_eq!(info.label(), AccountId32([1; 32]).to_string());
+    }
+}
@@ -1,8 +1,33 @@
+use crate::services::airdrop::{dry_run_claim, fetch_is_claimed, AirdropClaim};
+use crate::services::amm::{dry_run_swap, fetch_pair_state, fetch_quote, PairState, SwapCall};
+use crate::services::compliance::{fetch_frozen_accounts, fetch_owner};
+use crate::services::contracts_api::{dry_run_call, ContractDryRun, StorageDeposit};
+use crate::services::emissions::{
+    fetch_emission_state, fetch_supply_chart, EmissionState, SupplyPoint,
+};
+use crate::services::escrow::{dry_run_escrow_call, fetch_deals_of, Deal, EscrowCall};
+use crate::services::faucet::{fetch_faucet_state, FaucetState};
+use crate::services::fees::{estimate_fees, FeeEstimate};
+use crate::services::multisig::{
+    dry_run_multisig_call, fetch_multisig_state, MultisigCall, MultisigState,
+};
+use crate::services::payouts::{fetch_payout_fee_bps, PayoutRow, RowResult};
+use crate::services::permit::{sign_permit, SignedPermit};
 use crate::services::polkadot;
 use crate::services::polkadot::runtime_types::sp_weights::weight_v2::Weight;
+use crate::services::psp22::{encode_call_data, TokenCall, TRANSFER_SELECTOR};
+use crate::services::simulation::{simulate, Simulation};
+use crate::services::streams::{dry_run_stream_call, fetch_streams_of, Stream, StreamCall};
+use crate::services::token_factory::{fetch_registry, TokenInfo};
+use crate::services::vesting::{fetch_vesting_schedule, VestingSchedule};
+use crate::services::votes::{fetch_voting_power, VotingPower};
+use crate::services::wrapped_native::{dry_run_wrap_call, fetch_wrap_state, WrapCall, WrapState};
+use crate::services::{prepare_extrinsic, Account, PreparedExtrinsic, SigningOptions};
 use anyhow::anyhow;
 use js_sys::Promise;
 use std::str::FromStr;
+use subxt::dynamic::Value;
+use subxt::tx::TxPayload;
 use subxt::utils::AccountId32;
 use subxt::utils::MultiAddress;
 use subxt::{OnlineClient, PolkadotConfig};
@@ -13,6 +38,10 @@ use js_sys::Array;
 
 const PROOF_SIZE: u64 = u64::MAX / 2;
 
+/// gas limit for a single contract call, same values as `getGasLimit` in index.js
+const REF_TIME_LIMIT: u64 = 11_344_007_254;
+const PROOF_SIZE_LIMIT: u64 = 131_072;
+
 #[wasm_bindgen]
 extern "C" {
     #[wasm_bindgen(js_name = fetchTotalSupply)]
@@ -39,16 +68,32 @@ extern "C" {
     ) -> Promise;
 }
 
+/// a token call after its dry-run, prepared for signing unless the dry-run failed
+pub enum PreviewedCall {
+    /// the call would fail, no nonce is reserved
+    Blocked(Simulation),
+    Prepared {
+        simulation: Simulation,
+        prepared: PreparedExtrinsic,
+        fees: FeeEstimate,
+    },
+}
+
 #[derive(Clone)]
 pub struct TokenService {
     pub client: OnlineClient<PolkadotConfig>,
+    /// options for extrinsics signed with the browser extension
+    pub signing_options: SigningOptions,
 }
 
 impl TokenService {
     pub async fn new() -> Result<Self, Box<dyn std::error::Error>> {
         let client = OnlineClient::<PolkadotConfig>::new().await?;
 
-        Ok(TokenService { client })
+        Ok(TokenService {
+            client,
+            signing_options: SigningOptions::default(),
+        })
     }
 
     pub async fn get_balance(
@@ -87,31 +132,603 @@ impl TokenService {
         Ok(total_supply)
     }
 
-    pub async fn transfer_tokens(
+    /// the vesting schedule of `account`, `None` if it has none
+    pub async fn get_vesting_schedule(
+        &self,
+        contract: String,
+        account: String,
+    ) -> Result<Option<VestingSchedule>, anyhow::Error> {
+        let contract = AccountId32::from_str(&contract)
+            .map_err(|_| anyhow!("Invalid contract address: {contract}"))?;
+        let account = AccountId32::from_str(&account)
+            .map_err(|_| anyhow!("Invalid account address: {account}"))?;
+        fetch_vesting_schedule(&self.client, &contract, &account).await
+    }
+
+    /// the delegate and the votes of `account`
+    pub async fn get_voting_power(
+        &self,
+        contract: String,
+        account: String,
+    ) -> Result<VotingPower, anyhow::Error> {
+        let contract = AccountId32::from_str(&contract)
+            .map_err(|_| anyhow!("Invalid contract address: {contract}"))?;
+        let account = AccountId32::from_str(&account)
+            .map_err(|_| anyhow!("Invalid account address: {account}"))?;
+        fetch_voting_power(&self.client, &contract, &account).await
+    }
+
+    /// the faucet of the token and when `account` can claim next
+    pub async fn get_faucet_state(
+        &self,
+        contract: String,
+        account: String,
+    ) -> Result<FaucetState, anyhow::Error> {
+        let contract = AccountId32::from_str(&contract)
+            .map_err(|_| anyhow!("Invalid contract address: {contract}"))?;
+        let account = AccountId32::from_str(&account)
+            .map_err(|_| anyhow!("Invalid account address: {account}"))?;
+        fetch_faucet_state(&self.client, &contract, &account).await
+    }
+
+    /// the transfer fee in basis points `account` pays on each payout of a batch
+    pub async fn get_payout_fee_bps(
+        &self,
+        contract: String,
+        account: String,
+    ) -> Result<u16, anyhow::Error> {
+        let contract = AccountId32::from_str(&contract)
+            .map_err(|_| anyhow!("Invalid contract address: {contract}"))?;
+        let account = AccountId32::from_str(&account)
+            .map_err(|_| anyhow!("Invalid account address: {account}"))?;
+        fetch_payout_fee_bps(&self.client, &contract, &account).await
+    }
+
+    /// the emission schedule of the token and its actual and projected supply around the latest block
+    pub async fn get_emission_state(
         &self,
         contract: String,
+        account: String,
+    ) -> Result<(EmissionState, Vec<SupplyPoint>), anyhow::Error> {
+        let contract = AccountId32::from_str(&contract)
+            .map_err(|_| anyhow!("Invalid contract address: {contract}"))?;
+        let account = AccountId32::from_str(&account)
+            .map_err(|_| anyhow!("Invalid account address: {account}"))?;
+        let state = fetch_emission_state(&self.client, &contract, &account).await?;
+        let chart = fetch_supply_chart(&self.client, &contract, &account, &state).await?;
+        Ok((state, chart))
+    }
+
+    /// the tokens created by `factory`, oldest first
+    pub async fn get_token_registry(
+        &self,
+        factory: String,
+        account: String,
+    ) -> Result<Vec<TokenInfo>, anyhow::Error> {
+        let factory = AccountId32::from_str(&factory)
+            .map_err(|_| anyhow!("Invalid factory address: {factory}"))?;
+        let account = AccountId32::from_str(&account)
+            .map_err(|_| anyhow!("Invalid account address: {account}"))?;
+        fetch_registry(&self.client, &factory, &account).await
+    }
+
+    /// whether `account` claimed its tokens of `airdrop`
+    pub async fn get_airdrop_claimed(
+        &self,
+        airdrop: String,
+        account: String,
+    ) -> Result<bool, anyhow::Error> {
+        let airdrop = AccountId32::from_str(&airdrop)
+            .map_err(|_| anyhow!("Invalid airdrop address: {airdrop}"))?;
+        let account = AccountId32::from_str(&account)
+            .map_err(|_| anyhow!("Invalid account address: {account}"))?;
+        fetch_is_claimed(&self.client, &airdrop, &account).await
+    }
+
+    /// dry-runs the claim and prepares it for signing, fails with the reason if the claim would fail
+    pub async fn prepare_airdrop_claim(
+        &self,
+        airdrop: String,
         source: String,
         sender_address: String,
-        destination_address: String,
-        amount: u128,
-    ) -> Result<String, anyhow::Error> {
-        let args: Vec<String> = vec![];
-        let js_args: Array = args.iter().map(JsValue::from).collect();
-        let result = JsFuture::from(js_execute_contract_function(
-            contract,
+        claim: &AirdropClaim,
+    ) -> Result<(PreparedExtrinsic, FeeEstimate), anyhow::Error> {
+        let airdrop = AccountId32::from_str(&airdrop)
+            .map_err(|_| anyhow!("Invalid airdrop address: {airdrop}"))?;
+        let sender = AccountId32::from_str(&sender_address)
+            .map_err(|_| anyhow!("Invalid sender address: {sender_address}"))?;
+        dry_run_claim(&self.client, &airdrop, &sender, claim)
+            .await?
+            .map_err(|err| anyhow!("The claim would fail: {err}"))?;
+        let call_data = claim.call_data();
+        let payload = contract_call(&airdrop, call_data.clone());
+        let prepared = prepare_extrinsic(
+            &self.client,
+            &payload,
             source,
             sender_address,
-            "psp22::transfer".into(),
-            destination_address,
-            amount.try_into().unwrap(),
-            (js_args).into(),
-        ))
-        .await
-        .map_err(|js_err| anyhow!("{js_err:?}"))?;
-        let result = result
-            .as_string()
-            .ok_or(anyhow!("Error converting JsValue into String"))?;
-        Ok(result)
+            &self.signing_options,
+        )
+        .await?;
+        self.with_estimated_fees(prepared, &airdrop, vec![call_data])
+            .await
+    }
+
+    /// the tokens and reserves of `pair`
+    pub async fn get_pair_state(
+        &self,
+        pair: String,
+        account: String,
+    ) -> Result<PairState, anyhow::Error> {
+        let pair =
+            AccountId32::from_str(&pair).map_err(|_| anyhow!("Invalid pair address: {pair}"))?;
+        let account = AccountId32::from_str(&account)
+            .map_err(|_| anyhow!("Invalid account address: {account}"))?;
+        fetch_pair_state(&self.client, &pair, &account).await
+    }
+
+    /// the output of swapping `amount_in` of `token_in` on `pair`
+    pub async fn quote_swap(
+        &self,
+        pair: String,
+        account: String,
+        token_in: &AccountId32,
+        amount_in: u128,
+    ) -> Result<u128, anyhow::Error> {
+        let pair =
+            AccountId32::from_str(&pair).map_err(|_| anyhow!("Invalid pair address: {pair}"))?;
+        let account = AccountId32::from_str(&account)
+            .map_err(|_| anyhow!("Invalid account address: {account}"))?;
+        fetch_quote(&self.client, &pair, &account, token_in, amount_in)
+            .await?
+            .map_err(|err| anyhow!("No quote: PairError::{err}"))
+    }
+
+    /// prepares the swap after a dry-run, which fails e.g. without allowance or when the price moved too far
+    pub async fn prepare_swap(
+        &self,
+        pair: String,
+        source: String,
+        sender_address: String,
+        swap: &SwapCall,
+    ) -> Result<(PreparedExtrinsic, FeeEstimate), anyhow::Error> {
+        let pair =
+            AccountId32::from_str(&pair).map_err(|_| anyhow!("Invalid pair address: {pair}"))?;
+        let sender = AccountId32::from_str(&sender_address)
+            .map_err(|_| anyhow!("Invalid sender address: {sender_address}"))?;
+        dry_run_swap(&self.client, &pair, &sender, swap)
+            .await?
+            .map_err(|err| anyhow!("The swap would fail: {err}"))?;
+        let call_data = swap.call_data();
+        let payload = contract_call(&pair, call_data.clone());
+        let prepared = prepare_extrinsic(
+            &self.client,
+            &payload,
+            source,
+            sender_address,
+            &self.signing_options,
+        )
+        .await?;
+        self.with_estimated_fees(prepared, &pair, vec![call_data])
+            .await
+    }
+
+    /// the active streams of `streams` which `account` sends or receives
+    pub async fn get_streams(
+        &self,
+        streams: String,
+        account: String,
+    ) -> Result<Vec<(u32, Stream)>, anyhow::Error> {
+        let streams = AccountId32::from_str(&streams)
+            .map_err(|_| anyhow!("Invalid streams address: {streams}"))?;
+        let account = AccountId32::from_str(&account)
+            .map_err(|_| anyhow!("Invalid account address: {account}"))?;
+        fetch_streams_of(&self.client, &streams, &account).await
+    }
+
+    /// prepares a withdrawal or cancellation of a stream after a dry-run
+    pub async fn prepare_stream_call(
+        &self,
+        streams: String,
+        source: String,
+        sender_address: String,
+        call: &StreamCall,
+    ) -> Result<(PreparedExtrinsic, FeeEstimate), anyhow::Error> {
+        let streams = AccountId32::from_str(&streams)
+            .map_err(|_| anyhow!("Invalid streams address: {streams}"))?;
+        let sender = AccountId32::from_str(&sender_address)
+            .map_err(|_| anyhow!("Invalid sender address: {sender_address}"))?;
+        dry_run_stream_call(&self.client, &streams, &sender, call)
+            .await?
+            .map_err(|err| anyhow!("The call would fail: {err}"))?;
+        let call_data = call.call_data();
+        let payload = contract_call(&streams, call_data.clone());
+        let prepared = prepare_extrinsic(
+            &self.client,
+            &payload,
+            source,
+            sender_address,
+            &self.signing_options,
+        )
+        .await?;
+        self.with_estimated_fees(prepared, &streams, vec![call_data])
+            .await
+    }
+
+    /// the open deals of `escrow` which `account` is a party of
+    pub async fn get_deals(
+        &self,
+        escrow: String,
+        account: String,
+    ) -> Result<Vec<(u32, Deal)>, anyhow::Error> {
+        let escrow = AccountId32::from_str(&escrow)
+            .map_err(|_| anyhow!("Invalid escrow address: {escrow}"))?;
+        let account = AccountId32::from_str(&account)
+            .map_err(|_| anyhow!("Invalid account address: {account}"))?;
+        fetch_deals_of(&self.client, &escrow, &account).await
+    }
+
+    /// prepares a release, refund or dispute of a deal after a dry-run
+    pub async fn prepare_escrow_call(
+        &self,
+        escrow: String,
+        source: String,
+        sender_address: String,
+        call: &EscrowCall,
+    ) -> Result<(PreparedExtrinsic, FeeEstimate), anyhow::Error> {
+        let escrow = AccountId32::from_str(&escrow)
+            .map_err(|_| anyhow!("Invalid escrow address: {escrow}"))?;
+        let sender = AccountId32::from_str(&sender_address)
+            .map_err(|_| anyhow!("Invalid sender address: {sender_address}"))?;
+        dry_run_escrow_call(&self.client, &escrow, &sender, call)
+            .await?
+            .map_err(|err| anyhow!("The call would fail: {err}"))?;
+        let call_data = call.call_data();
+        let payload = contract_call(&escrow, call_data.clone());
+        let prepared = prepare_extrinsic(
+            &self.client,
+            &payload,
+            source,
+            sender_address,
+            &self.signing_options,
+        )
+        .await?;
+        self.with_estimated_fees(prepared, &escrow, vec![call_data])
+            .await
+    }
+
+    /// the native and wrapped balance of `account`
+    pub async fn get_wrap_state(
+        &self,
+        contract: String,
+        account: String,
+    ) -> Result<WrapState, anyhow::Error> {
+        let contract = AccountId32::from_str(&contract)
+            .map_err(|_| anyhow!("Invalid contract address: {contract}"))?;
+        let account = AccountId32::from_str(&account)
+            .map_err(|_| anyhow!("Invalid account address: {account}"))?;
+        fetch_wrap_state(&self.client, &contract, &account).await
+    }
+
+    /// prepares wrapping or unwrapping after a dry-run
+    pub async fn prepare_wrap_call(
+        &self,
+        contract: String,
+        source: String,
+        sender_address: String,
+        call: &WrapCall,
+    ) -> Result<(PreparedExtrinsic, FeeEstimate), anyhow::Error> {
+        let contract = AccountId32::from_str(&contract)
+            .map_err(|_| anyhow!("Invalid contract address: {contract}"))?;
+        let sender = AccountId32::from_str(&sender_address)
+            .map_err(|_| anyhow!("Invalid sender address: {sender_address}"))?;
+        dry_run_wrap_call(&self.client, &contract, &sender, call)
+            .await?
+            .map_err(|err| anyhow!("The call would fail: {err}"))?;
+        let call_data = call.call_data();
+        let payload = payable_contract_call(&contract, call.value(), call_data.clone());
+        let prepared = prepare_extrinsic(
+            &self.client,
+            &payload,
+            source,
+            sender_address,
+            &self.signing_options,
+        )
+        .await?;
+        self.with_estimated_payable_fees(prepared, &contract, call.value(), vec![call_data])
+            .await
+    }
+
+    /// the frozen accounts of the token and its owner, queried as `account`
+    pub async fn get_compliance_state(
+        &self,
+        contract: String,
+        account: String,
+    ) -> Result<(Vec<AccountId32>, Option<AccountId32>), anyhow::Error> {
+        let contract = AccountId32::from_str(&contract)
+            .map_err(|_| anyhow!("Invalid contract address: {contract}"))?;
+        let account = AccountId32::from_str(&account)
+            .map_err(|_| anyhow!("Invalid account address: {account}"))?;
+        let frozen = fetch_frozen_accounts(&self.client, &contract, &account).await?;
+        let owner = fetch_owner(&self.client, &contract, &account).await?;
+        Ok((frozen, owner))
+    }
+
+    /// the owners, threshold and pending proposals of `multisig`, fails if it is not a multisig
+    pub async fn get_multisig_state(
+        &self,
+        multisig: String,
+        account: String,
+    ) -> Result<MultisigState, anyhow::Error> {
+        let multisig = AccountId32::from_str(&multisig)
+            .map_err(|_| anyhow!("Invalid multisig address: {multisig}"))?;
+        let account = AccountId32::from_str(&account)
+            .map_err(|_| anyhow!("Invalid account address: {account}"))?;
+        fetch_multisig_state(&self.client, &multisig, &account).await
+    }
+
+    /// prepares a proposal, approval, revocation or execution of `multisig` after a dry-run
+    pub async fn prepare_multisig_call(
+        &self,
+        multisig: String,
+        source: String,
+        sender_address: String,
+        call: &MultisigCall,
+    ) -> Result<(PreparedExtrinsic, FeeEstimate), anyhow::Error> {
+        let multisig = AccountId32::from_str(&multisig)
+            .map_err(|_| anyhow!("Invalid multisig address: {multisig}"))?;
+        let sender = AccountId32::from_str(&sender_address)
+            .map_err(|_| anyhow!("Invalid sender address: {sender_address}"))?;
+        dry_run_multisig_call(&self.client, &multisig, &sender, call)
+            .await?
+            .map_err(|err| anyhow!("The call would fail: {err}"))?;
+        let call_data = call.call_data();
+        let payload = contract_call(&multisig, call_data.clone());
+        let prepared = prepare_extrinsic(
+            &self.client,
+            &payload,
+            source,
+            sender_address,
+            &self.signing_options,
+        )
+        .await?;
+        self.with_estimated_fees(prepared, &multisig, vec![call_data])
+            .await
+    }
+
+    /// dry-runs `call` as the sender and predicts its balance and allowance changes
+    pub async fn simulate_token_call(
+        &self,
+        contract: String,
+        sender_address: String,
+        call: TokenCall,
+    ) -> Result<Simulation, anyhow::Error> {
+        let contract = AccountId32::from_str(&contract)
+            .map_err(|_| anyhow!("Invalid contract address: {contract}"))?;
+        let sender = AccountId32::from_str(&sender_address)
+            .map_err(|_| anyhow!("Invalid sender address: {sender_address}"))?;
+        simulate(&self.client, &contract, &sender, call).await
+    }
+
+    /// dry-runs `call` and, if it succeeds, prepares it for signing
+    pub async fn preview_token_call(
+        &self,
+        contract: String,
+        source: String,
+        sender_address: String,
+        call: TokenCall,
+    ) -> Result<PreviewedCall, anyhow::Error> {
+        let simulation = self
+            .simulate_token_call(contract.clone(), sender_address.clone(), call.clone())
+            .await?;
+        if simulation.is_blocked() {
+            return Ok(PreviewedCall::Blocked(simulation));
+        }
+        let (prepared, fees) = self
+            .prepare_token_call(contract, source, sender_address, &call)
+            .await?;
+        Ok(PreviewedCall::Prepared {
+            simulation,
+            prepared,
+            fees,
+        })
+    }
+
+    /// prepares an extrinsic calling `call` on the token with a reserved nonce, so several calls can be queued,
+    /// and estimates its fees
+    pub async fn prepare_token_call(
+        &self,
+        contract: String,
+        source: String,
+        sender_address: String,
+        call: &TokenCall,
+    ) -> Result<(PreparedExtrinsic, FeeEstimate), anyhow::Error> {
+        let contract = AccountId32::from_str(&contract)
+            .map_err(|_| anyhow!("Invalid contract address: {contract}"))?;
+        let call_data = call.call_data();
+        let payload = contract_call(&contract, call_data.clone());
+        let prepared = prepare_extrinsic(
+            &self.client,
+            &payload,
+            source,
+            sender_address,
+            &self.signing_options,
+        )
+        .await?;
+        self.with_estimated_fees(prepared, &contract, vec![call_data])
+            .await
+    }
+
+    /// signs a permit for `spender` with the extension of `account`, it is valid until `deadline` (milliseconds)
+    pub async fn sign_permit(
+        &self,
+        contract: String,
+        account: Account,
+        spender_address: String,
+        value: u128,
+        deadline: u64,
+    ) -> Result<SignedPermit, anyhow::Error> {
+        let contract = AccountId32::from_str(&contract)
+            .map_err(|_| anyhow!("Invalid contract address: {contract}"))?;
+        let spender = AccountId32::from_str(&spender_address)
+            .map_err(|_| anyhow!("Invalid spender address: {spender_address}"))?;
+        sign_permit(&self.client, &contract, &account, spender, value, deadline).await
+    }
+
+    /// prepares a single `Utility::batch_all` extrinsic which transfers tokens to every row of the payout batch
+    /// and estimates its fees.
+    ///
+    /// Every transfer is dry-run first and gets the gas it requires, with the default gas limit of a single call
+    /// a batch of a few dozen rows would exceed the block weight.
+    pub async fn prepare_batch_transfer(
+        &self,
+        contract: String,
+        source: String,
+        sender_address: String,
+        rows: &[PayoutRow],
+    ) -> Result<(PreparedExtrinsic, FeeEstimate), anyhow::Error> {
+        let contract = AccountId32::from_str(&contract)
+            .map_err(|_| anyhow!("Invalid contract address: {contract}"))?;
+        let sender = AccountId32::from_str(&sender_address)
+            .map_err(|_| anyhow!("Invalid sender address: {sender_address}"))?;
+        let mut calls = Vec::with_capacity(rows.len());
+        let mut dry_runs = Vec::with_capacity(rows.len());
+        for row in rows {
+            let memo = row.memo.clone().unwrap_or_default().into_bytes();
+            let call_data = encode_call_data(
+                TRANSFER_SELECTOR,
+                (row.account_id.clone(), row.amount, memo),
+            );
+            let dry_run =
+                dry_run_call(&self.client, &sender, &contract, 0, call_data.clone()).await?;
+            calls.push(contract_call_value(
+                &contract,
+                call_data,
+                &dry_run.gas_required,
+            ));
+            dry_runs.push(dry_run);
+        }
+        let batch = subxt::dynamic::tx(
+            "Utility",
+            "batch_all",
+            vec![Value::unnamed_composite(calls)],
+        );
+
+        let prepared = prepare_extrinsic(
+            &self.client,
+            &batch,
+            source,
+            sender_address,
+            &self.signing_options,
+        )
+        .await?;
+        self.with_fees_of_dry_runs(prepared, &dry_runs).await
+    }
+
+    /// signs and submits a prepared batch transfer of `rows` payouts.
+    ///
+    /// `batch_all` is atomic, so either every row completes or every row fails.
+    pub async fn submit_batch_transfer(
+        &self,
+        prepared: PreparedExtrinsic,
+        rows: usize,
+    ) -> Vec<RowResult> {
+        let events = match prepared.sign_and_submit(&self.client).await {
+            Ok(events) => events,
+            Err(err) => {
+                let reason = err.to_string();
+                return (0..rows)
+                    .map(|_| RowResult::Failed(reason.clone()))
+                    .collect();
+            }
+        };
+
+        let completed = events
+            .iter()
+            .filter_map(Result::ok)
+            .filter(|event| {
+                event.pallet_name() == "Utility" && event.variant_name() == "ItemCompleted"
+            })
+            .count();
+        (0..rows)
+            .map(|i| {
+                if i < completed {
+                    RowResult::Completed
+                } else {
+                    RowResult::Failed("Not executed".to_string())
+                }
+            })
+            .collect()
+    }
+
+    /// estimates the fees of `prepared` and the storage deposit of the contract `calls` it contains.
+    /// The reserved nonce is released if the estimation fails.
+    async fn with_estimated_fees(
+        &self,
+        prepared: PreparedExtrinsic,
+        contract: &AccountId32,
+        calls: Vec<Vec<u8>>,
+    ) -> Result<(PreparedExtrinsic, FeeEstimate), anyhow::Error> {
+        self.with_estimated_payable_fees(prepared, contract, 0, calls)
+            .await
+    }
+
+    /// like `with_estimated_fees`, the `calls` are dry-run with `value` transferred
+    async fn with_estimated_payable_fees(
+        &self,
+        prepared: PreparedExtrinsic,
+        contract: &AccountId32,
+        value: u128,
+        calls: Vec<Vec<u8>>,
+    ) -> Result<(PreparedExtrinsic, FeeEstimate), anyhow::Error> {
+        let mut dry_runs = Vec::with_capacity(calls.len());
+        for call_data in calls {
+            match dry_run_call(
+                &self.client,
+                &prepared.account_id,
+                contract,
+                value,
+                call_data,
+            )
+            .await
+            {
+                Ok(dry_run) => dry_runs.push(dry_run),
+                Err(err) => {
+                    prepared.release_nonce();
+                    return Err(err.into());
+                }
+            }
+        }
+        self.with_fees_of_dry_runs(prepared, &dry_runs).await
+    }
+
+    /// estimates the fees of `prepared`, the storage deposit is summed up from the `dry_runs` of the contract
+    /// calls it contains. The reserved nonce is released if the estimation fails.
+    async fn with_fees_of_dry_runs(
+        &self,
+        prepared: PreparedExtrinsic,
+        dry_runs: &[ContractDryRun],
+    ) -> Result<(PreparedExtrinsic, FeeEstimate), anyhow::Error> {
+        let mut fees = match estimate_fees(&self.client, &prepared).await {
+            Ok(fees) => fees,
+            Err(err) => {
+                prepared.release_nonce();
+                return Err(err.into());
+            }
+        };
+        let (mut charge, mut refund) = (0u128, 0u128);
+        for dry_run in dry_runs {
+            match dry_run.storage_deposit {
+                StorageDeposit::Charge(amount) => charge = charge.saturating_add(amount),
+                StorageDeposit::Refund(amount) => refund = refund.saturating_add(amount),
+            }
+        }
+        fees.storage_deposit = Some(if charge >= refund {
+            StorageDeposit::Charge(charge - refund)
+        } else {
+            StorageDeposit::Refund(refund - charge)
+        });
+        Ok((prepared, fees))
     }
 
     pub async fn get_total_supply_native(&self, contract: String) -> Result<u128, anyhow::Error> {
@@ -153,6 +770,55 @@ impl TokenService {
     }
 }
 
+/// `Contracts::call` of `contract` with the default gas limit
+fn contract_call(contract: &AccountId32, call_data: Vec<u8>) -> impl TxPayload {
+    payable_contract_call(contract, 0, call_data)
+}
+
+/// `Contracts::call` of `contract` transferring `value` to it, with the default gas limit
+fn payable_contract_call(
+    contract: &AccountId32,
+    value: u128,
+    call_data: Vec<u8>,
+) -> impl TxPayload {
+    polkadot::tx().contracts().call(
+        MultiAddress::Id(contract.clone()),
+        value,
+        Weight {
+            ref_time: REF_TIME_LIMIT,
+            proof_size: PROOF_SIZE_LIMIT,
+        },
+        None,
+        call_data,
+    )
+}
+
+/// dynamic `Contracts::call` with `gas_limit` which can be nested into `Utility::batch_all`
+fn contract_call_value(contract: &AccountId32, call_data: Vec<u8>, gas_limit: &Weight) -> Value {
+    Value::unnamed_variant(
+        "Contracts",
+        [Value::named_variant(
+            "call",
+            [
+                (
+                    "dest",
+                    Value::unnamed_variant("Id", [Value::from_bytes(contract.0)]),
+                ),
+                ("value", Value::u128(0)),
+                (
+                    "gas_limit",
+                    Value::named_composite([
+                        ("ref_time", Value::u128(gas_limit.ref_time as u128)),
+                        ("proof_size", Value::u128(gas_limit.proof_size as u128)),
+                    ]),
+                ),
+                ("storage_deposit_limit", Value::unnamed_variant("None", [])),
+                ("data", Value::from_bytes(call_data)),
+            ],
+        )],
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,120 @@
+use anyhow::anyhow;
+use subxt::ext::codec::Decode;
+use subxt::utils::AccountId32;
+use subxt::{OnlineClient, PolkadotConfig};
+
+use crate::services::contracts_api::query;
+use crate::services::psp22::{encode_call_data, LangError, VESTING_SCHEDULE_SELECTOR};
+
+/// number of points of the vesting timeline, start and end included
+pub const TIMELINE_POINTS: u64 = 5;
+
+/// vesting schedule of a beneficiary, same encoding as `VestingSchedule` of the contract
+#[derive(Clone, Debug, Decode, PartialEq)]
+pub struct VestingSchedule {
+    pub total: u128,
+    pub released: u128,
+    /// timestamps and durations in milliseconds
+    pub start: u64,
+    pub cliff: u64,
+    pub duration: u64,
+}
+
+/// vested and unvested amount at a point in time
+#[derive(Clone, Debug, PartialEq)]
+pub struct VestingPoint {
+    pub timestamp: u64,
+    pub vested: u128,
+    pub unvested: u128,
+}
+
+impl VestingSchedule {
+    /// amount vested at `now`, same calculation as the contract
+    pub fn vested_at(&self, now: u64) -> u128 {
+        if now < self.start.saturating_add(self.cliff) {
+            return 0;
+        }
+        let elapsed = now - self.start;
+        if elapsed >= self.duration {
+            return self.total;
+        }
+        let (elapsed, duration) = (elapsed as u128, self.duration as u128);
+        self.total / duration * elapsed + self.total % duration * elapsed / duration
+    }
+
+    /// vested amount which can be released at `now`
+    pub fn releasable_at(&self, now: u64) -> u128 {
+        self.vested_at(now).saturating_sub(self.released)
+    }
+
+    /// vested and unvested amounts from the start to the end of the schedule, including the end of the cliff
+    pub fn timeline(&self) -> Vec<VestingPoint> {
+        let mut timestamps: Vec<u64> = (0..TIMELINE_POINTS)
+            .map(|i| self.start + self.duration / (TIMELINE_POINTS - 1) * i)
+            .collect();
+        timestamps.push(self.start + self.cliff);
+        timestamps.push(self.start + self.duration);
+        timestamps.sort_unstable();
+        timestamps.dedup();
+        timestamps
+            .into_iter()
+            .map(|timestamp| {
+                let vested = self.vested_at(timestamp);
+                VestingPoint {
+                    timestamp,
+                    vested,
+                    unvested: self.total - vested,
+                }
+            })
+            .collect()
+    }
+}
+
+/// fetches the vesting schedule of `beneficiary`, `None` if it has none
+pub async fn fetch_vesting_schedule(
+    api: &OnlineClient<PolkadotConfig>,
+    contract: &AccountId32,
+    beneficiary: &AccountId32,
+) -> Result<Option<VestingSchedule>, anyhow::Error> {
+    query::<Result<Option<VestingSchedule>, LangError>>(
+        api,
+        beneficiary,
+        contract,
+        encode_call_data(VESTING_SCHEDULE_SELECTOR, beneficiary),
+    )
+    .await?
+    .map_err(|err| anyhow!("{err:?}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schedule() -> VestingSchedule {
+        VestingSchedule {
+            total: 100,
+            released: 0,
+            start: 0,
+            cliff: 100,
+            duration: 1000,
+        }
+    }
+
+    #[wasm_bindgen_test::wasm_bindgen_test]
+    fn vests_linearly_after_cliff() {
+        let schedule = schedule();
+
+        assert_eq!(schedule.vested_at(99), 0);
+        assert_eq!(schedule.vested_at(250), 25);
+        assert_eq!(schedule.vested_at(2000), 100);
+    }
+
+    #[wasm_bindgen_test::wasm_bindgen_test]
+    fn timeline_includes_cliff_and_end() {
+        let timeline = schedule().timeline();
+
+        let timestamps: Vec<u64> = timeline.iter().map(|point| point.timestamp).collect();
+        assert_eq!(timestamps, vec![0, 100, 250, 500, 750, 1000]);
+        assert_eq!(timeline.last().unwrap().unvested, 0);
+    }
+}
@@ -0,0 +1,37 @@
+use anyhow::anyhow;
+use subxt::utils::AccountId32;
+use subxt::{OnlineClient, PolkadotConfig};
+
+use crate::services::contracts_api::query;
+use crate::services::psp22::{encode_call_data, LangError, DELEGATES_SELECTOR, GET_VOTES_SELECTOR};
+
+/// the delegate of an account and the votes delegated to the account
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct VotingPower {
+    pub delegate: Option<AccountId32>,
+    pub votes: u128,
+}
+
+pub async fn fetch_voting_power(
+    api: &OnlineClient<PolkadotConfig>,
+    contract: &AccountId32,
+    account: &AccountId32,
+) -> Result<VotingPower, anyhow::Error> {
+    let delegate = query::<Result<Option<AccountId32>, LangError>>(
+        api,
+        account,
+        contract,
+        encode_call_data(DELEGATES_SELECTOR, account),
+    )
+    .await?
+    .map_err(|err| anyhow!("{err:?}"))?;
+    let votes = query::<Result<u128, LangError>>(
+        api,
+        account,
+        contract,
+        encode_call_data(GET_VOTES_SELECTOR, account),
+    )
+    .await?
+    .map_err(|err| anyhow!("{err:?}"))?;
+    Ok(VotingPower { delegate, votes })
+}
@@ -0,0 +1,73 @@
+use serde::{Deserialize, Serialize};
+
+/// key of the watchlist in the local storage of the browser
+const WATCHLIST_KEY: &str = "ytoken.watchlist";
+
+/// a token contract the user wants to switch to quickly on the token page
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WatchedToken {
+    /// ss58 address of the contract
+    pub address: String,
+    pub label: String,
+}
+
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
+
+/// the watched tokens, empty if the local storage is unavailable or holds no valid watchlist
+pub fn load_watchlist() -> Vec<WatchedToken> {
+    local_storage()
+        .and_then(|storage| storage.get_item(WATCHLIST_KEY).ok()?)
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+fn save_watchlist(tokens: &[WatchedToken]) {
+    if let (Some(storage), Ok(json)) = (local_storage(), serde_json::to_string(tokens)) {
+        // a full or disabled storage only loses the change
+        storage.set_item(WATCHLIST_KEY, &json).ok();
+    }
+}
+
+/// adds `token` to the stored watchlist, replacing an entry with the same address
+pub fn watch(token: WatchedToken) -> Vec<WatchedToken> {
+    let tokens = with_token(load_watchlist(), token);
+    save_watchlist(&tokens);
+    tokens
+}
+
+/// removes the token with `address` from the stored watchlist
+pub fn unwatch(address: &str) -> Vec<WatchedToken> {
+    let mut tokens = load_watchlist();
+    tokens.retain(|token| token.address != address);
+    save_watchlist(&tokens);
+    tokens
+}
+
+fn with_token(mut tokens: Vec<WatchedToken>, token: WatchedToken) -> Vec<WatchedToken> {
+    match tokens
+        .iter_mut()
+        .find(|watched| watched.address == token.address)
+    {
+        Some(watched) => *watched = token,
+        None => tokens.push(token),
+    }
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[wasm_bindgen_test::wasm_bindgen_test]
+    fn watching_a_token_twice_updates_its_label() {
+        let token = |label: &str| WatchedToken {
+            address: "5FbxgE9CZgib7p4oWi34Tx5vqLHsXKNGEWnfMn6pMT7VzwTx".into(),
+            label: label.into(),
+        };
+        let tokens = with_token(vec![token("Old")], token("New"));
+
+        assert_eq!(tokens, vec![token("New")]);
+    }
+}
@@ -0,0 +1,115 @@
+use anyhow::anyhow;
+use std::fmt;
+use subxt::ext::codec::Decode;
+use subxt::utils::AccountId32;
+use subxt::{OnlineClient, PolkadotConfig};
+
+use crate::services::contracts_api::{dry_run_call, query};
+use crate::services::polkadot;
+use crate::services::psp22::{encode_call_data, LangError, PSP22Error, BALANCE_OF_SELECTOR};
+
+/// selector of `WrappedNative::deposit`
+pub const DEPOSIT_SELECTOR: [u8; 4] = [0x2d, 0x10, 0xc9, 0xbd];
+/// selector of `WrappedNative::withdraw`
+pub const WITHDRAW_SELECTOR: [u8; 4] = [0x41, 0x0f, 0xcc, 0x9d];
+
+/// free native balance and wrapped balance of an account
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct WrapState {
+    pub native: u128,
+    pub wrapped: u128,
+}
+
+/// the messages of the wrapper contract
+#[derive(Clone, Debug, PartialEq)]
+pub enum WrapCall {
+    /// deposits native currency for the same amount of wrapped tokens
+    Wrap(u128),
+    /// burns wrapped tokens for the same amount of native currency
+    Unwrap(u128),
+}
+
+impl WrapCall {
+    pub fn call_data(&self) -> Vec<u8> {
+        match self {
+            WrapCall::Wrap(_) => DEPOSIT_SELECTOR.to_vec(),
+            WrapCall::Unwrap(amount) => encode_call_data(WITHDRAW_SELECTOR, amount),
+        }
+    }
+
+    /// native value transferred with the call
+    pub fn value(&self) -> u128 {
+        match self {
+            WrapCall::Wrap(amount) => *amount,
+            WrapCall::Unwrap(_) => 0,
+        }
+    }
+}
+
+impl fmt::Display for WrapCall {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WrapCall::Wrap(amount) => write!(f, "Wrap {amount} native tokens"),
+            WrapCall::Unwrap(amount) => write!(f, "Unwrap {amount} tokens"),
+        }
+    }
+}
+
+pub async fn fetch_wrap_state(
+    api: &OnlineClient<PolkadotConfig>,
+    contract: &AccountId32,
+    account: &AccountId32,
+) -> Result<WrapState, anyhow::Error> {
+    let native = api
+        .storage()
+        .at_latest()
+        .await?
+        .fetch_or_default(&polkadot::storage().system().account(account))
+        .await?
+        .data
+        .free;
+    let wrapped = query::<Result<u128, LangError>>(
+        api,
+        account,
+        contract,
+        encode_call_data(BALANCE_OF_SELECTOR, account),
+    )
+    .await?
+    .map_err(|err| anyhow!("{err:?}"))?;
+    Ok(WrapState { native, wrapped })
+}
+
+/// dry-runs `call` as `account` with its value, the error tells why the call would fail
+pub async fn dry_run_wrap_call(
+    api: &OnlineClient<PolkadotConfig>,
+    contract: &AccountId32,
+    account: &AccountId32,
+    call: &WrapCall,
+) -> Result<Result<(), String>, anyhow::Error> {
+    let dry_run = dry_run_call(api, account, contract, call.value(), call.call_data()).await?;
+    let data = match dry_run.return_data() {
+        Ok(data) => data,
+        Err(err) => return Ok(Err(err)),
+    };
+    Ok(
+        match Result::<Result<(), PSP22Error>, LangError>::decode(&mut &data[..]) {
+            Ok(Ok(Ok(()))) => Ok(()),
+            Ok(Ok(Err(err))) => Err(format!("PSP22Error::{err}")),
+            Ok(Err(err)) => Err(format!("LangError::{err:?}")),
+            Err(err) => Err(format!("Could not decode return value: {err}")),
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[wasm_bindgen_test::wasm_bindgen_test]
+    fn only_wrapping_transfers_value() {
+        assert_eq!(WrapCall::Wrap(5).value(), 5);
+        assert_eq!(WrapCall::Wrap(5).call_data(), DEPOSIT_SELECTOR.to_vec());
+        assert_eq!(WrapCall::Unwrap(5).value(), 0);
+        assert_eq!(WrapCall::Unwrap(5).call_data().len(), 4 + 16);
+    }
+}
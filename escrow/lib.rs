@@ -0,0 +1,652 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+/// escrow of `YToken` for OTC deals: a buyer locks tokens for a seller, who delivers off-chain.
+///
+/// The buyer or the arbiter releases the tokens to the seller, the seller or the arbiter refunds them to the
+/// buyer. Once the deadline passed, the buyer refunds the deal itself, unless the seller disputed it before, then
+/// only the arbiter decides.
+#[openbrush::contract]
+pub mod escrow {
+    use ink::prelude::vec::Vec;
+    use ink::storage::Mapping;
+    use openbrush::contracts::psp22::{PSP22Error, PSP22Ref};
+    use y_psp22_receiver::{accept_tokens, is_fee_exempt, PSP22Receiver, PSP22ReceiverError};
+
+    /// maximum number of deals `deals_of` returns
+    pub const MAX_PAGE_LEN: u32 = 50;
+
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum EscrowError {
+        /// the deadline is in the past
+        InvalidDeadline,
+        ZeroAmount,
+        DealNotFound,
+        /// the caller is not a party of the deal allowed to do this
+        NotAuthorized,
+        /// the buyer refunds only after the deadline
+        DeadlineNotReached,
+        /// the deal was disputed, only the arbiter decides
+        Disputed,
+        /// the escrow is not exempt from the transfer fee of the token
+        NotFeeExempt,
+        TransferFailed(PSP22Error),
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct Deal {
+        pub buyer: AccountId,
+        pub seller: AccountId,
+        pub arbiter: AccountId,
+        pub amount: Balance,
+        /// the buyer can refund the deal after it
+        pub deadline: Timestamp,
+        pub disputed: bool,
+    }
+
+    impl Deal {
+        /// whether `caller` may release the tokens to the seller
+        pub fn can_release(&self, caller: AccountId) -> bool {
+            caller == self.buyer || caller == self.arbiter
+        }
+
+        /// whether `caller` may refund the tokens to the buyer at `now`
+        pub fn can_refund(&self, caller: AccountId, now: Timestamp) -> Result<(), EscrowError> {
+            if caller == self.seller || caller == self.arbiter {
+                return Ok(());
+            }
+            if caller != self.buyer {
+                return Err(EscrowError::NotAuthorized);
+            }
+            if self.disputed {
+                return Err(EscrowError::Disputed);
+            }
+            if now <= self.deadline {
+                return Err(EscrowError::DeadlineNotReached);
+            }
+            Ok(())
+        }
+    }
+
+    #[ink(event)]
+    pub struct DealCreated {
+        #[ink(topic)]
+        id: u32,
+        #[ink(topic)]
+        buyer: AccountId,
+        #[ink(topic)]
+        seller: AccountId,
+        arbiter: AccountId,
+        amount: Balance,
+        deadline: Timestamp,
+    }
+
+    #[ink(event)]
+    pub struct Released {
+        #[ink(topic)]
+        id: u32,
+        #[ink(topic)]
+        by: AccountId,
+        amount: Balance,
+    }
+
+    #[ink(event)]
+    pub struct Refunded {
+        #[ink(topic)]
+        id: u32,
+        #[ink(topic)]
+        by: AccountId,
+        amount: Balance,
+    }
+
+    #[ink(event)]
+    pub struct Disputed {
+        #[ink(topic)]
+        id: u32,
+    }
+
+    #[ink(storage)]
+    pub struct Escrow {
+        token: AccountId,
+        /// open deals, released and refunded deals are removed
+        deals: Mapping<u32, Deal>,
+        next_id: u32,
+        /// ids of the open deals an account is a party of, by position
+        deals_of: Mapping<(AccountId, u32), u32>,
+        /// the position of a deal in `deals_of` of an account
+        deal_positions: Mapping<(AccountId, u32), u32>,
+        deal_count_of: Mapping<AccountId, u32>,
+    }
+
+    impl Escrow {
+        /// escrow of `token`
+        #[ink(constructor)]
+        pub fn new(token: AccountId) -> Self {
+            Self {
+                token,
+                deals: Mapping::default(),
+                next_id: 0,
+                deals_of: Mapping::default(),
+                deal_positions: Mapping::default(),
+                deal_count_of: Mapping::default(),
+            }
+        }
+
+        /// locks `amount` of the caller as buyer until it is released to `seller` or refunded.
+        ///
+        /// The caller approves the contract to spend `amount` first, and the owner of the token exempts the escrow
+        /// from the transfer fee.
+        #[ink(message)]
+        pub fn create_deal(
+            &mut self,
+            seller: AccountId,
+            arbiter: AccountId,
+            amount: Balance,
+            deadline: Timestamp,
+        ) -> Result<u32, EscrowError> {
+            if deadline <= self.env().block_timestamp() {
+                return Err(EscrowError::InvalidDeadline);
+            }
+            if amount == 0 {
+                return Err(EscrowError::ZeroAmount);
+            }
+            if !is_fee_exempt(self.token, self.env().account_id()) {
+                return Err(EscrowError::NotFeeExempt);
+            }
+            let buyer = self.env().caller();
+            self.transfer_in(buyer, amount)?;
+
+            let id = self.next_id;
+            self.next_id += 1;
+            let deal = Deal {
+                buyer,
+                seller,
+                arbiter,
+                amount,
+                deadline,
+                disputed: false,
+            };
+            self.deals.insert(id, &deal);
+            for account in Self::parties(&deal) {
+                self.add_deal_of(account, id);
+            }
+            self.env().emit_event(DealCreated {
+                id,
+                buyer,
+                seller,
+                arbiter,
+                amount,
+                deadline,
+            });
+            Ok(id)
+        }
+
+        /// transfers the tokens of deal `id` to the seller, called by the buyer or the arbiter
+        #[ink(message)]
+        pub fn release(&mut self, id: u32) -> Result<(), EscrowError> {
+            let deal = self.deals.get(id).ok_or(EscrowError::DealNotFound)?;
+            let caller = self.env().caller();
+            if !deal.can_release(caller) {
+                return Err(EscrowError::NotAuthorized);
+            }
+            self.remove_deal(id, &deal);
+            PSP22Ref::transfer(&self.token, deal.seller, deal.amount, Vec::new())
+                .map_err(EscrowError::TransferFailed)?;
+            self.env().emit_event(Released {
+                id,
+                by: caller,
+                amount: deal.amount,
+            });
+            Ok(())
+        }
+
+        /// transfers the tokens of deal `id` back to the buyer.
+        ///
+        /// The seller and the arbiter refund at any time, the buyer after the deadline of an undisputed deal.
+        #[ink(message)]
+        pub fn refund(&mut self, id: u32) -> Result<(), EscrowError> {
+            let deal = self.deals.get(id).ok_or(EscrowError::DealNotFound)?;
+            let caller = self.env().caller();
+            deal.can_refund(caller, self.env().block_timestamp())?;
+            self.remove_deal(id, &deal);
+            PSP22Ref::transfer(&self.token, deal.buyer, deal.amount, Vec::new())
+                .map_err(EscrowError::TransferFailed)?;
+            self.env().emit_event(Refunded {
+                id,
+                by: caller,
+                amount: deal.amount,
+            });
+            Ok(())
+        }
+
+        /// the seller disputes deal `id`, the buyer can't refund it after the deadline anymore
+        #[ink(message)]
+        pub fn dispute(&mut self, id: u32) -> Result<(), EscrowError> {
+            let mut deal = self.deals.get(id).ok_or(EscrowError::DealNotFound)?;
+            if self.env().caller() != deal.seller {
+                return Err(EscrowError::NotAuthorized);
+            }
+            if deal.disputed {
+                return Err(EscrowError::Disputed);
+            }
+            deal.disputed = true;
+            self.deals.insert(id, &deal);
+            self.env().emit_event(Disputed { id });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn deal(&self, id: u32) -> Option<Deal> {
+            self.deals.get(id)
+        }
+
+        /// the open deals `account` is the buyer, seller or arbiter of, from position `start` on, at most
+        /// `MAX_PAGE_LEN`
+        #[ink(message)]
+        pub fn deals_of(&self, account: AccountId, start: u32, limit: u32) -> Vec<(u32, Deal)> {
+            let end = start
+                .saturating_add(limit.min(MAX_PAGE_LEN))
+                .min(self.deal_count_of(account));
+            (start..end)
+                .filter_map(|index| self.deals_of.get((account, index)))
+                .filter_map(|id| Some((id, self.deals.get(id)?)))
+                .collect()
+        }
+
+        #[ink(message)]
+        pub fn deal_count_of(&self, account: AccountId) -> u32 {
+            self.deal_count_of.get(account).unwrap_or_default()
+        }
+
+        #[ink(message)]
+        pub fn token(&self) -> AccountId {
+            self.token
+        }
+
+        /// transfers `amount` from `from` to the contract with the allowance of the contract
+        fn transfer_in(&self, from: AccountId, amount: Balance) -> Result<(), EscrowError> {
+            y_psp22_receiver::transfer_in(self.token, from, amount)
+                .map_err(EscrowError::TransferFailed)
+        }
+
+        /// the distinct accounts of a deal
+        fn parties(deal: &Deal) -> Vec<AccountId> {
+            let mut parties = Vec::new();
+            for account in [deal.buyer, deal.seller, deal.arbiter] {
+                if !parties.contains(&account) {
+                    parties.push(account);
+                }
+            }
+            parties
+        }
+
+        fn add_deal_of(&mut self, account: AccountId, id: u32) {
+            let index = self.deal_count_of(account);
+            self.deals_of.insert((account, index), &id);
+            self.deal_positions.insert((account, id), &index);
+            self.deal_count_of.insert(account, &(index + 1));
+        }
+
+        /// the last deal of each party takes the position of the removed one in `deals_of`
+        fn remove_deal(&mut self, id: u32, deal: &Deal) {
+            self.deals.remove(id);
+            for account in Self::parties(deal) {
+                let Some(index) = self.deal_positions.get((account, id)) else {
+                    continue;
+                };
+                let last = self.deal_count_of(account) - 1;
+                if let Some(moved) = self.deals_of.get((account, last)).filter(|_| index != last) {
+                    self.deals_of.insert((account, index), &moved);
+                    self.deal_positions.insert((account, moved), &index);
+                }
+                self.deal_positions.remove((account, id));
+                self.deals_of.remove((account, last));
+                self.deal_count_of.insert(account, &last);
+            }
+        }
+    }
+
+    /// accepts transfers of the escrowed token only
+    impl PSP22Receiver for Escrow {
+        #[ink(message)]
+        fn before_received(
+            &mut self,
+            _operator: AccountId,
+            _from: AccountId,
+            _value: Balance,
+            _data: Vec<u8>,
+        ) -> Result<(), PSP22ReceiverError> {
+            accept_tokens(self.env().caller(), &[self.token])
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn deal() -> Deal {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            Deal {
+                buyer: accounts.alice,
+                seller: accounts.bob,
+                arbiter: accounts.charlie,
+                amount: 1_000,
+                deadline: 100,
+                disputed: false,
+            }
+        }
+
+        fn escrow_with_deal() -> Escrow {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut escrow = Escrow::new(accounts.django);
+            let deal = deal();
+            escrow.deals.insert(0, &deal);
+            for account in Escrow::parties(&deal) {
+                escrow.add_deal_of(account, 0);
+            }
+            escrow
+        }
+
+        #[ink::test]
+        fn buyer_refunds_only_undisputed_deals_after_the_deadline() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut deal = deal();
+
+            assert_eq!(
+                deal.can_refund(accounts.alice, 100),
+                Err(EscrowError::DeadlineNotReached)
+            );
+            assert_eq!(deal.can_refund(accounts.alice, 101), Ok(()));
+            assert_eq!(deal.can_refund(accounts.bob, 0), Ok(()));
+            assert_eq!(deal.can_refund(accounts.charlie, 0), Ok(()));
+            assert_eq!(
+                deal.can_refund(accounts.eve, 101),
+                Err(EscrowError::NotAuthorized)
+            );
+
+            deal.disputed = true;
+            assert_eq!(
+                deal.can_refund(accounts.alice, 101),
+                Err(EscrowError::Disputed)
+            );
+            assert_eq!(deal.can_refund(accounts.charlie, 101), Ok(()));
+        }
+
+        #[ink::test]
+        fn rejects_invalid_deals() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut escrow = Escrow::new(accounts.django);
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(100);
+
+            assert_eq!(
+                escrow.create_deal(accounts.bob, accounts.charlie, 10, 100),
+                Err(EscrowError::InvalidDeadline)
+            );
+            assert_eq!(
+                escrow.create_deal(accounts.bob, accounts.charlie, 0, 200),
+                Err(EscrowError::ZeroAmount)
+            );
+            assert_eq!(escrow.release(0), Err(EscrowError::DealNotFound));
+        }
+
+        #[ink::test]
+        fn only_the_parties_release_or_dispute() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut escrow = escrow_with_deal();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(escrow.release(0), Err(EscrowError::NotAuthorized));
+            assert_eq!(escrow.dispute(0), Ok(()));
+            assert_eq!(escrow.dispute(0), Err(EscrowError::Disputed));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(escrow.dispute(0), Err(EscrowError::NotAuthorized));
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(200);
+            assert_eq!(escrow.refund(0), Err(EscrowError::Disputed));
+
+            assert_eq!(escrow.deals_of(accounts.charlie, 0, 10).len(), 1);
+            assert_eq!(escrow.deals_of(accounts.eve, 0, 10), vec![]);
+        }
+
+        #[ink::test]
+        fn removing_a_deal_moves_the_last_deal_of_each_party() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut escrow = escrow_with_deal();
+            for id in [1, 2] {
+                escrow.deals.insert(id, &deal());
+                for account in Escrow::parties(&deal()) {
+                    escrow.add_deal_of(account, id);
+                }
+            }
+            let ids = |escrow: &Escrow, account: AccountId| -> Vec<u32> {
+                let deals = escrow.deals_of(account, 0, 10);
+                deals.into_iter().map(|(id, _)| id).collect()
+            };
+            assert_eq!(escrow.deals_of(accounts.bob, 1, 1)[0].0, 1);
+
+            escrow.remove_deal(0, &deal());
+            assert_eq!(escrow.deal_count_of(accounts.bob), 2);
+            assert_eq!(ids(&escrow, accounts.bob), vec![2, 1]);
+            escrow.remove_deal(1, &deal());
+            escrow.remove_deal(2, &deal());
+            assert_eq!(escrow.deal_count_of(accounts.alice), 0);
+            assert_eq!(ids(&escrow, accounts.charlie), vec![]);
+        }
+    }
+
+    #[cfg(all(test, feature = "e2e-tests"))]
+    pub mod e2e_tests {
+        use super::*;
+        use ink_e2e::subxt::tx::Signer;
+        use ink_e2e::subxt::utils::AccountId32;
+        use ink_e2e::{build_message, Keypair, PolkadotConfig};
+        use openbrush::contracts::psp22::psp22_external::PSP22;
+        use y_psp22_token::y_psp22_token::YTokenRef;
+
+        type E2EResult<T> = Result<T, Box<dyn std::error::Error>>;
+
+        fn address_of(keypair: &Keypair) -> AccountId {
+            let address: AccountId32 = <Keypair as Signer<PolkadotConfig>>::account_id(keypair);
+            address.0.into()
+        }
+
+        /// deploys the token and the escrow of alice, exempting the escrow from the transfer fee if `fee_exempt`
+        async fn deploy(
+            client: &mut ink_e2e::Client<C, E>,
+            fee_exempt: bool,
+        ) -> (AccountId, AccountId) {
+            let token = client
+                .instantiate(
+                    "y_psp22_token",
+                    &ink_e2e::alice(),
+                    YTokenRef::new(1_000),
+                    0,
+                    None,
+                )
+                .await
+                .expect("instantiate token failed")
+                .account_id;
+            let address = client
+                .instantiate("escrow", &ink_e2e::alice(), EscrowRef::new(token), 0, None)
+                .await
+                .expect("instantiate escrow failed")
+                .account_id;
+            if fee_exempt {
+                let exempt = build_message::<YTokenRef>(token.clone())
+                    .call(|token| token.set_fee_exempt(address, true));
+                client
+                    .call(&ink_e2e::alice(), exempt, 0, None)
+                    .await
+                    .expect("set fee exempt failed");
+            }
+            (token, address)
+        }
+
+        /// deploys the token and the escrow and creates a deal of alice with bob as seller and charlie as arbiter
+        async fn deal(
+            client: &mut ink_e2e::Client<C, E>,
+            amount: Balance,
+            deadline: Timestamp,
+        ) -> (AccountId, AccountId, u32) {
+            let (bob, charlie) = (address_of(&ink_e2e::bob()), address_of(&ink_e2e::charlie()));
+            let (token, address) = deploy(client, true).await;
+            let approve = build_message::<YTokenRef>(token.clone())
+                .call(|token| token.approve(address, amount));
+            client
+                .call(&ink_e2e::alice(), approve, 0, None)
+                .await
+                .expect("approve failed");
+
+            let create = build_message::<EscrowRef>(address.clone())
+                .call(|escrow| escrow.create_deal(bob, charlie, amount, deadline));
+            let id = client
+                .call(&ink_e2e::alice(), create, 0, None)
+                .await
+                .expect("create deal failed")
+                .return_value()
+                .expect("create deal returned an error");
+            (token, address, id)
+        }
+
+        async fn balance_of(
+            client: &mut ink_e2e::Client<C, E>,
+            token: AccountId,
+            account: AccountId,
+        ) -> Balance {
+            let balance_of =
+                build_message::<YTokenRef>(token.clone()).call(|token| token.balance_of(account));
+            client
+                .call_dry_run(&ink_e2e::alice(), &balance_of, 0, None)
+                .await
+                .return_value()
+        }
+
+        #[ink_e2e::test(additional_contracts = "../contract/Cargo.toml")]
+        async fn buyer_releases_to_the_seller(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            let bob = address_of(&ink_e2e::bob());
+            let (token, address, id) = deal(&mut client, 600, u64::MAX).await;
+
+            let deals_of_bob = build_message::<EscrowRef>(address.clone())
+                .call(|escrow| escrow.deals_of(bob, 0, MAX_PAGE_LEN));
+            let deals = client
+                .call_dry_run(&ink_e2e::alice(), &deals_of_bob, 0, None)
+                .await
+                .return_value();
+            assert_eq!(deals.len(), 1);
+            assert_eq!(deals[0].0, id);
+
+            // the seller can't release to itself
+            let release =
+                build_message::<EscrowRef>(address.clone()).call(|escrow| escrow.release(id));
+            let result = client
+                .call_dry_run(&ink_e2e::bob(), &release, 0, None)
+                .await;
+            assert_eq!(result.return_value(), Err(EscrowError::NotAuthorized));
+
+            client
+                .call(&ink_e2e::alice(), release, 0, None)
+                .await
+                .expect("release failed");
+            assert_eq!(balance_of(&mut client, token, bob).await, 600);
+            let deals = client
+                .call_dry_run(&ink_e2e::alice(), &deals_of_bob, 0, None)
+                .await
+                .return_value();
+            assert!(deals.is_empty());
+
+            Ok(())
+        }
+
+        #[ink_e2e::test(additional_contracts = "../contract/Cargo.toml")]
+        async fn arbiter_refunds_a_disputed_deal(
+            mut client: ink_e2e::Client<C, E>,
+        ) -> E2EResult<()> {
+            let alice = address_of(&ink_e2e::alice());
+            let (token, address, id) = deal(&mut client, 600, u64::MAX).await;
+            assert_eq!(balance_of(&mut client, token, alice).await, 400);
+
+            let dispute =
+                build_message::<EscrowRef>(address.clone()).call(|escrow| escrow.dispute(id));
+            client
+                .call(&ink_e2e::bob(), dispute, 0, None)
+                .await
+                .expect("dispute failed");
+
+            // the deadline is far in the future, the buyer can't refund yet
+            let refund =
+                build_message::<EscrowRef>(address.clone()).call(|escrow| escrow.refund(id));
+            let result = client
+                .call_dry_run(&ink_e2e::alice(), &refund, 0, None)
+                .await;
+            assert_eq!(result.return_value(), Err(EscrowError::Disputed));
+
+            client
+                .call(&ink_e2e::charlie(), refund, 0, None)
+                .await
+                .expect("refund failed");
+            assert_eq!(balance_of(&mut client, token, alice).await, 1_000);
+
+            Ok(())
+        }
+
+        #[ink_e2e::test(additional_contracts = "../contract/Cargo.toml")]
+        async fn buyer_refunds_after_the_deadline(
+            mut client: ink_e2e::Client<C, E>,
+        ) -> E2EResult<()> {
+            let alice = address_of(&ink_e2e::alice());
+            let now = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)?
+                .as_millis() as Timestamp;
+            let (token, address, id) = deal(&mut client, 600, now + 5_000).await;
+
+            let refund =
+                build_message::<EscrowRef>(address.clone()).call(|escrow| escrow.refund(id));
+            let result = client
+                .call_dry_run(&ink_e2e::alice(), &refund, 0, None)
+                .await;
+            assert_eq!(result.return_value(), Err(EscrowError::DeadlineNotReached));
+
+            // the timestamp of the chain only moves on with a new block
+            std::thread::sleep(std::time::Duration::from_secs(6));
+            let approve =
+                build_message::<YTokenRef>(token.clone()).call(|token| token.approve(address, 0));
+            client
+                .call(&ink_e2e::alice(), approve, 0, None)
+                .await
+                .expect("approve failed");
+
+            client
+                .call(&ink_e2e::alice(), refund, 0, None)
+                .await
+                .expect("refund failed");
+            assert_eq!(balance_of(&mut client, token, alice).await, 1_000);
+
+            Ok(())
+        }
+
+        #[ink_e2e::test(additional_contracts = "../contract/Cargo.toml")]
+        async fn deals_need_an_exempt_escrow(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            let (bob, charlie) = (address_of(&ink_e2e::bob()), address_of(&ink_e2e::charlie()));
+            let (token, address) = deploy(&mut client, false).await;
+            let approve =
+                build_message::<YTokenRef>(token.clone()).call(|token| token.approve(address, 600));
+            client
+                .call(&ink_e2e::alice(), approve, 0, None)
+                .await
+                .expect("approve failed");
+
+            let create = build_message::<EscrowRef>(address.clone())
+                .call(|escrow| escrow.create_deal(bob, charlie, 600, u64::MAX));
+            let result = client
+                .call_dry_run(&ink_e2e::alice(), &create, 0, None)
+                .await;
+            assert_eq!(result.return_value(), Err(EscrowError::NotFeeExempt));
+
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,595 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+/// m-of-n multisig acting as owner of `YToken`, so no single key controls the owner-only messages.
+///
+/// An owner proposes a call of a contract, which executes once `threshold` owners approved it. The called
+/// messages return a `Result`, its error is passed on encoded, since the owner-only messages of `YToken` return
+/// `PSP22Error` and `transfer_ownership` returns `OwnableError`. Owners and threshold are fixed, to change them
+/// deploy a new multisig and propose `transfer_ownership` to it.
+#[openbrush::contract]
+pub mod multisig_admin {
+    use ink::env::call::{build_call, ExecutionInput, Selector};
+    use ink::prelude::vec::Vec;
+    use ink::storage::Mapping;
+
+    /// bounds the weight of `pending_proposals`
+    pub const MAX_PENDING: u32 = 50;
+
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum MultisigError {
+        /// the threshold is zero or above the number of owners
+        InvalidThreshold,
+        NotOwner,
+        ProposalNotFound,
+        AlreadyApproved,
+        NotApproved,
+        ThresholdNotReached,
+        /// `MAX_PENDING` proposals are pending
+        TooManyPending,
+        /// the called message returned the encoded error
+        CallFailed(Vec<u8>),
+        /// the call could not be made or its output not decoded
+        CallReverted,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct Proposal {
+        pub proposer: AccountId,
+        pub callee: AccountId,
+        pub selector: [u8; 4],
+        /// the encoded arguments of the message
+        pub input: Vec<u8>,
+        /// the owners which approved the proposal
+        pub approvals: Vec<AccountId>,
+    }
+
+    /// the already encoded arguments of a call
+    struct CallInput<'a>(&'a [u8]);
+
+    impl scale::Encode for CallInput<'_> {
+        fn encode_to<T: scale::Output + ?Sized>(&self, dest: &mut T) {
+            dest.write(self.0);
+        }
+    }
+
+    /// output of a called message returning `Result<_, E>`, the error stays encoded as the callee returned it
+    struct CallOutput(Result<(), Vec<u8>>);
+
+    impl scale::Decode for CallOutput {
+        fn decode<I: scale::Input>(input: &mut I) -> Result<Self, scale::Error> {
+            let is_err = input.read_byte()? != 0;
+            let mut rest = Vec::new();
+            while let Ok(byte) = input.read_byte() {
+                rest.push(byte);
+            }
+            Ok(CallOutput(if is_err { Err(rest) } else { Ok(()) }))
+        }
+    }
+
+    #[ink(event)]
+    pub struct Proposed {
+        #[ink(topic)]
+        id: u32,
+        #[ink(topic)]
+        proposer: AccountId,
+        callee: AccountId,
+        selector: [u8; 4],
+    }
+
+    #[ink(event)]
+    pub struct Approved {
+        #[ink(topic)]
+        id: u32,
+        #[ink(topic)]
+        owner: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct Revoked {
+        #[ink(topic)]
+        id: u32,
+        #[ink(topic)]
+        owner: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct Executed {
+        #[ink(topic)]
+        id: u32,
+        #[ink(topic)]
+        by: AccountId,
+    }
+
+    #[ink(storage)]
+    pub struct MultisigAdmin {
+        owners: Vec<AccountId>,
+        threshold: u32,
+        /// pending proposals, executed proposals are removed
+        proposals: Mapping<u32, Proposal>,
+        next_id: u32,
+        pending: Vec<u32>,
+    }
+
+    impl MultisigAdmin {
+        /// `threshold` of the distinct `owners` have to approve a call
+        #[ink(constructor)]
+        pub fn new(owners: Vec<AccountId>, threshold: u32) -> Result<Self, MultisigError> {
+            let mut distinct = Vec::new();
+            for owner in owners {
+                if !distinct.contains(&owner) {
+                    distinct.push(owner);
+                }
+            }
+            if threshold == 0 || threshold as usize > distinct.len() {
+                return Err(MultisigError::InvalidThreshold);
+            }
+            Ok(Self {
+                owners: distinct,
+                threshold,
+                proposals: Mapping::default(),
+                next_id: 0,
+                pending: Vec::new(),
+            })
+        }
+
+        /// proposes to call the message `selector` of `callee` with the encoded `input`, approved by the caller
+        #[ink(message)]
+        pub fn propose(
+            &mut self,
+            callee: AccountId,
+            selector: [u8; 4],
+            input: Vec<u8>,
+        ) -> Result<u32, MultisigError> {
+            let proposer = self.ensure_owner()?;
+            if self.pending.len() as u32 >= MAX_PENDING {
+                return Err(MultisigError::TooManyPending);
+            }
+            let id = self.next_id;
+            self.next_id += 1;
+            self.proposals.insert(
+                id,
+                &Proposal {
+                    proposer,
+                    callee,
+                    selector,
+                    input,
+                    approvals: Vec::from([proposer]),
+                },
+            );
+            self.pending.push(id);
+            self.env().emit_event(Proposed {
+                id,
+                proposer,
+                callee,
+                selector,
+            });
+            self.env().emit_event(Approved {
+                id,
+                owner: proposer,
+            });
+            Ok(id)
+        }
+
+        #[ink(message)]
+        pub fn approve(&mut self, id: u32) -> Result<(), MultisigError> {
+            let owner = self.ensure_owner()?;
+            let mut proposal = self
+                .proposals
+                .get(id)
+                .ok_or(MultisigError::ProposalNotFound)?;
+            if proposal.approvals.contains(&owner) {
+                return Err(MultisigError::AlreadyApproved);
+            }
+            proposal.approvals.push(owner);
+            self.proposals.insert(id, &proposal);
+            self.env().emit_event(Approved { id, owner });
+            Ok(())
+        }
+
+        /// withdraws the approval of the caller, a proposal without approvals is removed
+        #[ink(message)]
+        pub fn revoke(&mut self, id: u32) -> Result<(), MultisigError> {
+            let owner = self.ensure_owner()?;
+            let mut proposal = self
+                .proposals
+                .get(id)
+                .ok_or(MultisigError::ProposalNotFound)?;
+            if !proposal.approvals.contains(&owner) {
+                return Err(MultisigError::NotApproved);
+            }
+            proposal.approvals.retain(|approval| *approval != owner);
+            if proposal.approvals.is_empty() {
+                self.remove_proposal(id);
+            } else {
+                self.proposals.insert(id, &proposal);
+            }
+            self.env().emit_event(Revoked { id, owner });
+            Ok(())
+        }
+
+        /// makes the call of proposal `id` once `threshold` owners approved it
+        #[ink(message)]
+        pub fn execute(&mut self, id: u32) -> Result<(), MultisigError> {
+            let by = self.ensure_owner()?;
+            let proposal = self
+                .proposals
+                .get(id)
+                .ok_or(MultisigError::ProposalNotFound)?;
+            if (proposal.approvals.len() as u32) < self.threshold {
+                return Err(MultisigError::ThresholdNotReached);
+            }
+            self.remove_proposal(id);
+            let result = build_call::<ink::env::DefaultEnvironment>()
+                .call(proposal.callee)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(proposal.selector))
+                        .push_arg(CallInput(&proposal.input)),
+                )
+                .returns::<CallOutput>()
+                .try_invoke();
+            match result {
+                Ok(Ok(CallOutput(Ok(())))) => {}
+                Ok(Ok(CallOutput(Err(err)))) => return Err(MultisigError::CallFailed(err)),
+                Ok(Err(_)) | Err(_) => return Err(MultisigError::CallReverted),
+            }
+            self.env().emit_event(Executed { id, by });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn proposal(&self, id: u32) -> Option<Proposal> {
+            self.proposals.get(id)
+        }
+
+        /// the proposals which were not executed yet, with their ids
+        #[ink(message)]
+        pub fn pending_proposals(&self) -> Vec<(u32, Proposal)> {
+            self.pending
+                .iter()
+                .filter_map(|id| Some((*id, self.proposals.get(id)?)))
+                .collect()
+        }
+
+        #[ink(message)]
+        pub fn owners(&self) -> Vec<AccountId> {
+            self.owners.clone()
+        }
+
+        #[ink(message)]
+        pub fn threshold(&self) -> u32 {
+            self.threshold
+        }
+
+        fn ensure_owner(&self) -> Result<AccountId, MultisigError> {
+            let caller = self.env().caller();
+            if !self.owners.contains(&caller) {
+                return Err(MultisigError::NotOwner);
+            }
+            Ok(caller)
+        }
+
+        fn remove_proposal(&mut self, id: u32) {
+            self.proposals.remove(id);
+            self.pending.retain(|pending_id| *pending_id != id);
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn multisig() -> MultisigAdmin {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            MultisigAdmin::new(
+                vec![
+                    accounts.alice,
+                    accounts.bob,
+                    accounts.charlie,
+                    accounts.alice,
+                ],
+                2,
+            )
+            .unwrap()
+        }
+
+        #[ink::test]
+        fn rejects_invalid_thresholds() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            assert!(matches!(
+                MultisigAdmin::new(vec![accounts.alice], 0),
+                Err(MultisigError::InvalidThreshold)
+            ));
+            assert!(matches!(
+                MultisigAdmin::new(vec![accounts.alice, accounts.alice], 2),
+                Err(MultisigError::InvalidThreshold)
+            ));
+            assert_eq!(multisig().owners().len(), 3);
+        }
+
+        #[ink::test]
+        fn proposals_need_threshold_approvals() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut multisig = multisig();
+
+            let id = multisig
+                .propose(accounts.django, [1, 2, 3, 4], Vec::new())
+                .unwrap();
+            assert_eq!(multisig.approve(id), Err(MultisigError::AlreadyApproved));
+            assert_eq!(
+                multisig.execute(id),
+                Err(MultisigError::ThresholdNotReached)
+            );
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.eve);
+            assert_eq!(multisig.approve(id), Err(MultisigError::NotOwner));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(multisig.revoke(id), Err(MultisigError::NotApproved));
+            assert_eq!(multisig.approve(id), Ok(()));
+            assert_eq!(
+                multisig.proposal(id).unwrap().approvals,
+                vec![accounts.alice, accounts.bob]
+            );
+        }
+
+        #[ink::test]
+        fn revoking_the_last_approval_removes_the_proposal() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut multisig = multisig();
+
+            let id = multisig
+                .propose(accounts.django, [1, 2, 3, 4], Vec::new())
+                .unwrap();
+            assert_eq!(multisig.pending_proposals().len(), 1);
+            assert_eq!(multisig.revoke(id), Ok(()));
+            assert_eq!(multisig.pending_proposals(), vec![]);
+            assert_eq!(multisig.approve(id), Err(MultisigError::ProposalNotFound));
+        }
+    }
+
+    #[cfg(all(test, feature = "e2e-tests"))]
+    pub mod e2e_tests {
+        use super::*;
+        use ink_e2e::subxt::tx::Signer;
+        use ink_e2e::subxt::utils::AccountId32;
+        use ink_e2e::{build_message, Keypair, PolkadotConfig};
+        use openbrush::contracts::ownable::ownable_external::Ownable;
+        use openbrush::contracts::ownable::OwnableError;
+        use openbrush::contracts::psp22::extensions::mintable::psp22mintable_external::PSP22Mintable;
+        use openbrush::contracts::psp22::psp22_external::PSP22;
+        use scale::Encode;
+        use y_psp22_token::y_psp22_token::YTokenRef;
+
+        type E2EResult<T> = Result<T, Box<dyn std::error::Error>>;
+
+        fn address_of(keypair: &Keypair) -> AccountId {
+            let address: AccountId32 = <Keypair as Signer<PolkadotConfig>>::account_id(keypair);
+            address.0.into()
+        }
+
+        #[ink_e2e::test(additional_contracts = "../contract/Cargo.toml")]
+        async fn two_of_three_owners_mint(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            let owners = vec![
+                address_of(&ink_e2e::alice()),
+                address_of(&ink_e2e::bob()),
+                address_of(&ink_e2e::charlie()),
+            ];
+            let dave = address_of(&ink_e2e::dave());
+            let token = client
+                .instantiate(
+                    "y_psp22_token",
+                    &ink_e2e::alice(),
+                    YTokenRef::new(1_000),
+                    0,
+                    None,
+                )
+                .await
+                .expect("instantiate token failed")
+                .account_id;
+            let address = client
+                .instantiate(
+                    "multisig_admin",
+                    &ink_e2e::alice(),
+                    MultisigAdminRef::new(owners, 2),
+                    0,
+                    None,
+                )
+                .await
+                .expect("instantiate multisig failed")
+                .account_id;
+            let transfer_ownership = build_message::<YTokenRef>(token.clone())
+                .call(|token| token.transfer_ownership(Some(address)));
+            client
+                .call(&ink_e2e::alice(), transfer_ownership, 0, None)
+                .await
+                .expect("transfer ownership failed");
+
+            // the key which deployed the token can't mint on its own anymore
+            let mint =
+                build_message::<YTokenRef>(token.clone()).call(|token| token.mint(dave, 500));
+            let result = client.call_dry_run(&ink_e2e::alice(), &mint, 0, None).await;
+            assert!(result.return_value().is_err());
+
+            let propose = build_message::<MultisigAdminRef>(address.clone()).call(|multisig| {
+                multisig.propose(
+                    token,
+                    ink::selector_bytes!("PSP22Mintable::mint"),
+                    (dave, 500 as Balance).encode(),
+                )
+            });
+            let id = client
+                .call(&ink_e2e::alice(), propose, 0, None)
+                .await
+                .expect("propose failed")
+                .return_value()
+                .expect("propose returned an error");
+
+            let execute = build_message::<MultisigAdminRef>(address.clone())
+                .call(|multisig| multisig.execute(id));
+            let result = client
+                .call_dry_run(&ink_e2e::alice(), &execute, 0, None)
+                .await;
+            assert_eq!(
+                result.return_value(),
+                Err(MultisigError::ThresholdNotReached)
+            );
+
+            let approve = build_message::<MultisigAdminRef>(address.clone())
+                .call(|multisig| multisig.approve(id));
+            client
+                .call(&ink_e2e::bob(), approve, 0, None)
+                .await
+                .expect("approve failed");
+            client
+                .call(&ink_e2e::charlie(), execute, 0, None)
+                .await
+                .expect("execute failed");
+
+            let balance_of_dave =
+                build_message::<YTokenRef>(token.clone()).call(|token| token.balance_of(dave));
+            let balance = client
+                .call_dry_run(&ink_e2e::alice(), &balance_of_dave, 0, None)
+                .await
+                .return_value();
+            assert_eq!(balance, 500);
+
+            let pending = build_message::<MultisigAdminRef>(address.clone())
+                .call(|multisig| multisig.pending_proposals());
+            let pending = client
+                .call_dry_run(&ink_e2e::alice(), &pending, 0, None)
+                .await
+                .return_value();
+            assert!(pending.is_empty());
+
+            Ok(())
+        }
+
+        #[ink_e2e::test(additional_contracts = "../contract/Cargo.toml")]
+        async fn former_owner_key_is_powerless(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            let alice = address_of(&ink_e2e::alice());
+            let token = client
+                .instantiate(
+                    "y_psp22_token",
+                    &ink_e2e::alice(),
+                    YTokenRef::new(1_000),
+                    0,
+                    None,
+                )
+                .await
+                .expect("instantiate token failed")
+                .account_id;
+            let address = client
+                .instantiate(
+                    "multisig_admin",
+                    &ink_e2e::alice(),
+                    MultisigAdminRef::new(vec![alice, address_of(&ink_e2e::bob())], 2),
+                    0,
+                    None,
+                )
+                .await
+                .expect("instantiate multisig failed")
+                .account_id;
+            let transfer_ownership = build_message::<YTokenRef>(token.clone())
+                .call(|token| token.transfer_ownership(Some(address)));
+            client
+                .call(&ink_e2e::alice(), transfer_ownership, 0, None)
+                .await
+                .expect("transfer ownership failed");
+
+            let freeze =
+                build_message::<YTokenRef>(token.clone()).call(|token| token.freeze(alice));
+            let result = client
+                .call_dry_run(&ink_e2e::alice(), &freeze, 0, None)
+                .await;
+            assert!(result.return_value().is_err());
+
+            Ok(())
+        }
+
+        #[ink_e2e::test(additional_contracts = "../contract/Cargo.toml")]
+        async fn executes_transfer_ownership_and_passes_on_its_error(
+            mut client: ink_e2e::Client<C, E>,
+        ) -> E2EResult<()> {
+            let alice = address_of(&ink_e2e::alice());
+            let token = client
+                .instantiate(
+                    "y_psp22_token",
+                    &ink_e2e::alice(),
+                    YTokenRef::new(1_000),
+                    0,
+                    None,
+                )
+                .await
+                .expect("instantiate token failed")
+                .account_id;
+            let address = client
+                .instantiate(
+                    "multisig_admin",
+                    &ink_e2e::alice(),
+                    MultisigAdminRef::new(vec![alice, address_of(&ink_e2e::bob())], 1),
+                    0,
+                    None,
+                )
+                .await
+                .expect("instantiate multisig failed")
+                .account_id;
+            let transfer_ownership = build_message::<YTokenRef>(token.clone())
+                .call(|token| token.transfer_ownership(Some(address)));
+            client
+                .call(&ink_e2e::alice(), transfer_ownership, 0, None)
+                .await
+                .expect("transfer ownership failed");
+
+            // `transfer_ownership` returns `OwnableError`, which is passed on encoded
+            let selector = ink::selector_bytes!("Ownable::transfer_ownership");
+            let propose = build_message::<MultisigAdminRef>(address.clone())
+                .call(|multisig| multisig.propose(token, selector, None::<AccountId>.encode()));
+            let id = client
+                .call(&ink_e2e::alice(), propose, 0, None)
+                .await
+                .expect("propose failed")
+                .return_value()
+                .expect("propose returned an error");
+            let execute = build_message::<MultisigAdminRef>(address.clone())
+                .call(|multisig| multisig.execute(id));
+            let result = client
+                .call_dry_run(&ink_e2e::alice(), &execute, 0, None)
+                .await;
+            assert_eq!(
+                result.return_value(),
+                Err(MultisigError::CallFailed(
+                    OwnableError::NewOwnerIsNotSet.encode()
+                ))
+            );
+
+            let propose = build_message::<MultisigAdminRef>(address.clone())
+                .call(|multisig| multisig.propose(token, selector, Some(alice).encode()));
+            let id = client
+                .call(&ink_e2e::alice(), propose, 0, None)
+                .await
+                .expect("propose failed")
+                .return_value()
+                .expect("propose returned an error");
+            let execute = build_message::<MultisigAdminRef>(address.clone())
+                .call(|multisig| multisig.execute(id));
+            client
+                .call(&ink_e2e::alice(), execute, 0, None)
+                .await
+                .expect("execute failed");
+
+            let owner = build_message::<YTokenRef>(token.clone()).call(|token| token.owner());
+            let owner = client
+                .call_dry_run(&ink_e2e::alice(), &owner, 0, None)
+                .await
+                .return_value();
+            assert_eq!(owner, Some(alice));
+
+            Ok(())
+        }
+    }
+}
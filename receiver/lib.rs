@@ -0,0 +1,83 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! `PSP22Receiver`, which `YToken` calls before it transfers tokens to a contract.
+//!
+//! Contracts implement the trait once to receive tokens. A contract which pulls tokens into itself with
+//! `transfer_from` is called back by the token during that call, so the call has to allow reentry. `transfer_in`
+//! makes the call with the flag set, the contract has to guard its state against the reentry itself.
+//!
+//! A contract which holds the tokens of many parties in one balance has to be exempt from the transfer fee of the
+//! token, otherwise paying out one party would pay its fee from the tokens of the others. `is_fee_exempt` asks the
+//! token before the contract takes tokens in.
+
+use ink::env::call::{build_call, CallFlags, ExecutionInput, Selector};
+use ink::env::{DefaultEnvironment, Environment};
+use ink::prelude::string::String;
+use ink::prelude::vec::Vec;
+use ink::primitives::AccountId;
+use openbrush::contracts::psp22::{PSP22Error, PSP22Ref};
+
+type Balance = <DefaultEnvironment as Environment>::Balance;
+
+/// selector of `YToken::is_fee_exempt`
+pub const IS_FEE_EXEMPT_SELECTOR: [u8; 4] = [0x77, 0xd4, 0x23, 0xc1];
+
+/// error a receiver returns from `before_received` to reject a transfer
+#[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum PSP22ReceiverError {
+    TransferRejected(String),
+}
+
+/// the selector of `before_received` is `0xfda6f1a9`
+#[ink::trait_definition]
+pub trait PSP22Receiver {
+    /// called by the token before it transfers `value` from `from` on behalf of `operator`, with the `data` of
+    /// the transfer. An error rejects the transfer.
+    #[ink(message)]
+    fn before_received(
+        &mut self,
+        operator: AccountId,
+        from: AccountId,
+        value: Balance,
+        data: Vec<u8>,
+    ) -> Result<(), PSP22ReceiverError>;
+}
+
+/// accepts transfers of the `accepted` tokens, `caller` is the caller of `before_received`, the token contract
+pub fn accept_tokens(caller: AccountId, accepted: &[AccountId]) -> Result<(), PSP22ReceiverError> {
+    if !accepted.contains(&caller) {
+        return Err(PSP22ReceiverError::TransferRejected(String::from(
+            "UnknownToken",
+        )));
+    }
+    Ok(())
+}
+
+/// transfers `amount` of `token` from `from` to the calling contract with its allowance, allowing the reentry of
+/// `before_received`
+pub fn transfer_in(token: AccountId, from: AccountId, amount: Balance) -> Result<(), PSP22Error> {
+    let call_failed = || PSP22Error::Custom(String::from("CallFailed"));
+    let contract = ink::env::account_id::<DefaultEnvironment>();
+    PSP22Ref::transfer_from_builder(&token, from, contract, amount, Vec::new())
+        .call_flags(CallFlags::default().set_allow_reentry(true))
+        .try_invoke()
+        .map_err(|_| call_failed())?
+        .map_err(|_| call_failed())?
+}
+
+/// whether `token` charges `account` no transfer fee, false if the token doesn't answer
+pub fn is_fee_exempt(token: AccountId, account: AccountId) -> bool {
+    let result = build_call::<DefaultEnvironment>()
+        .call(token)
+        .exec_input(ExecutionInput::new(Selector::new(IS_FEE_EXEMPT_SELECTOR)).push_arg(account))
+        .returns::<bool>()
+        .try_invoke();
+    matches!(result, Ok(Ok(true)))
+}
+
+/// `amount * numerator / denominator` rounded down, split at `denominator` so neither product overflows for
+/// `numerator < denominator` up to `u64::MAX`
+pub fn mul_div(amount: Balance, numerator: Balance, denominator: Balance) -> Balance {
+    amount / denominator * numerator + amount % denominator * numerator / denominator
+}
@@ -0,0 +1,532 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+/// streaming payments of `YToken`: a sender locks tokens for a recipient, which accrue linearly between
+/// `start` and `stop`.
+///
+/// The recipient withdraws the accrued tokens at any time, the sender cancels a stream to get back the tokens
+/// which have not accrued yet.
+#[openbrush::contract]
+pub mod streams {
+    use ink::prelude::vec::Vec;
+    use ink::storage::Mapping;
+    use openbrush::contracts::psp22::{PSP22Error, PSP22Ref};
+    use y_psp22_receiver::{
+        accept_tokens, is_fee_exempt, mul_div, PSP22Receiver, PSP22ReceiverError,
+    };
+
+    /// maximum number of streams `streams_of` returns
+    pub const MAX_PAGE_LEN: u32 = 50;
+
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum StreamError {
+        /// `stop` is not after `start` or `start` is in the past
+        InvalidTimeRange,
+        ZeroDeposit,
+        StreamNotFound,
+        /// only the sender cancels a stream
+        NotSender,
+        /// only the recipient withdraws from a stream
+        NotRecipient,
+        NothingToWithdraw,
+        /// the contract is not exempt from the transfer fee of the token
+        NotFeeExempt,
+        TransferFailed(PSP22Error),
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct Stream {
+        pub sender: AccountId,
+        pub recipient: AccountId,
+        pub deposit: Balance,
+        /// amount the recipient withdrew so far
+        pub withdrawn: Balance,
+        pub start: Timestamp,
+        pub stop: Timestamp,
+    }
+
+    impl Stream {
+        /// amount accrued to the recipient at `now`, withdrawn or not
+        pub fn streamed_at(&self, now: Timestamp) -> Balance {
+            if now <= self.start {
+                return 0;
+            }
+            if now >= self.stop {
+                return self.deposit;
+            }
+            mul_div(
+                self.deposit,
+                Balance::from(now - self.start),
+                Balance::from(self.stop - self.start),
+            )
+        }
+
+        /// amount the recipient can withdraw at `now`
+        pub fn withdrawable_at(&self, now: Timestamp) -> Balance {
+            self.streamed_at(now).saturating_sub(self.withdrawn)
+        }
+    }
+
+    #[ink(event)]
+    pub struct StreamCreated {
+        #[ink(topic)]
+        id: u32,
+        #[ink(topic)]
+        sender: AccountId,
+        #[ink(topic)]
+        recipient: AccountId,
+        deposit: Balance,
+        start: Timestamp,
+        stop: Timestamp,
+    }
+
+    #[ink(event)]
+    pub struct Withdrawn {
+        #[ink(topic)]
+        id: u32,
+        amount: Balance,
+    }
+
+    #[ink(event)]
+    pub struct StreamCanceled {
+        #[ink(topic)]
+        id: u32,
+        /// accrued tokens paid out to the recipient
+        recipient_amount: Balance,
+        /// tokens refunded to the sender
+        sender_amount: Balance,
+    }
+
+    #[ink(storage)]
+    pub struct Streams {
+        token: AccountId,
+        /// active streams, finished and canceled streams are removed
+        streams: Mapping<u32, Stream>,
+        next_id: u32,
+        /// ids of the active streams an account sends or receives, by position
+        streams_of: Mapping<(AccountId, u32), u32>,
+        /// the position of a stream in `streams_of` of an account
+        stream_positions: Mapping<(AccountId, u32), u32>,
+        stream_count_of: Mapping<AccountId, u32>,
+    }
+
+    impl Streams {
+        /// streams of `token`
+        #[ink(constructor)]
+        pub fn new(token: AccountId) -> Self {
+            Self {
+                token,
+                streams: Mapping::default(),
+                next_id: 0,
+                streams_of: Mapping::default(),
+                stream_positions: Mapping::default(),
+                stream_count_of: Mapping::default(),
+            }
+        }
+
+        /// locks `deposit` of the caller for `recipient`, streamed between `start` and `stop`.
+        ///
+        /// The caller approves the contract to spend `deposit` first, and the owner of the token exempts the
+        /// contract from the transfer fee.
+        #[ink(message)]
+        pub fn create_stream(
+            &mut self,
+            recipient: AccountId,
+            deposit: Balance,
+            start: Timestamp,
+            stop: Timestamp,
+        ) -> Result<u32, StreamError> {
+            if stop <= start || start < self.env().block_timestamp() {
+                return Err(StreamError::InvalidTimeRange);
+            }
+            if deposit == 0 {
+                return Err(StreamError::ZeroDeposit);
+            }
+            if !is_fee_exempt(self.token, self.env().account_id()) {
+                return Err(StreamError::NotFeeExempt);
+            }
+            let sender = self.env().caller();
+            self.transfer_in(sender, deposit)?;
+
+            let id = self.next_id;
+            self.next_id += 1;
+            self.streams.insert(
+                id,
+                &Stream {
+                    sender,
+                    recipient,
+                    deposit,
+                    withdrawn: 0,
+                    start,
+                    stop,
+                },
+            );
+            self.add_stream_of(sender, id);
+            if recipient != sender {
+                self.add_stream_of(recipient, id);
+            }
+            self.env().emit_event(StreamCreated {
+                id,
+                sender,
+                recipient,
+                deposit,
+                start,
+                stop,
+            });
+            Ok(id)
+        }
+
+        /// transfers the accrued tokens of stream `id` to its recipient, the stream ends once it is paid out
+        #[ink(message)]
+        pub fn withdraw(&mut self, id: u32) -> Result<Balance, StreamError> {
+            let mut stream = self.streams.get(id).ok_or(StreamError::StreamNotFound)?;
+            if self.env().caller() != stream.recipient {
+                return Err(StreamError::NotRecipient);
+            }
+            let amount = stream.withdrawable_at(self.env().block_timestamp());
+            if amount == 0 {
+                return Err(StreamError::NothingToWithdraw);
+            }
+            stream.withdrawn += amount;
+            if stream.withdrawn == stream.deposit {
+                self.remove_stream(id, &stream);
+            } else {
+                self.streams.insert(id, &stream);
+            }
+            PSP22Ref::transfer(&self.token, stream.recipient, amount, Vec::new())
+                .map_err(StreamError::TransferFailed)?;
+            self.env().emit_event(Withdrawn { id, amount });
+            Ok(amount)
+        }
+
+        /// ends stream `id`, pays the accrued tokens to the recipient and refunds the rest to the sender
+        #[ink(message)]
+        pub fn cancel(&mut self, id: u32) -> Result<(), StreamError> {
+            let stream = self.streams.get(id).ok_or(StreamError::StreamNotFound)?;
+            if self.env().caller() != stream.sender {
+                return Err(StreamError::NotSender);
+            }
+            let recipient_amount = stream.withdrawable_at(self.env().block_timestamp());
+            let sender_amount = stream
+                .deposit
+                .saturating_sub(stream.withdrawn)
+                .saturating_sub(recipient_amount);
+            self.remove_stream(id, &stream);
+            for (account, amount) in [
+                (stream.recipient, recipient_amount),
+                (stream.sender, sender_amount),
+            ] {
+                if amount > 0 {
+                    PSP22Ref::transfer(&self.token, account, amount, Vec::new())
+                        .map_err(StreamError::TransferFailed)?;
+                }
+            }
+            self.env().emit_event(StreamCanceled {
+                id,
+                recipient_amount,
+                sender_amount,
+            });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn stream(&self, id: u32) -> Option<Stream> {
+            self.streams.get(id)
+        }
+
+        /// the active streams `account` sends or receives, from position `start` on, at most `MAX_PAGE_LEN`
+        #[ink(message)]
+        pub fn streams_of(&self, account: AccountId, start: u32, limit: u32) -> Vec<(u32, Stream)> {
+            let end = start
+                .saturating_add(limit.min(MAX_PAGE_LEN))
+                .min(self.stream_count_of(account));
+            (start..end)
+                .filter_map(|index| self.streams_of.get((account, index)))
+                .filter_map(|id| Some((id, self.streams.get(id)?)))
+                .collect()
+        }
+
+        #[ink(message)]
+        pub fn stream_count_of(&self, account: AccountId) -> u32 {
+            self.stream_count_of.get(account).unwrap_or_default()
+        }
+
+        #[ink(message)]
+        pub fn token(&self) -> AccountId {
+            self.token
+        }
+
+        /// transfers `amount` from `from` to the contract with the allowance of the contract
+        fn transfer_in(&self, from: AccountId, amount: Balance) -> Result<(), StreamError> {
+            y_psp22_receiver::transfer_in(self.token, from, amount)
+                .map_err(StreamError::TransferFailed)
+        }
+
+        fn add_stream_of(&mut self, account: AccountId, id: u32) {
+            let index = self.stream_count_of(account);
+            self.streams_of.insert((account, index), &id);
+            self.stream_positions.insert((account, id), &index);
+            self.stream_count_of.insert(account, &(index + 1));
+        }
+
+        /// the last stream of the sender and the recipient takes the position of the removed one in `streams_of`
+        fn remove_stream(&mut self, id: u32, stream: &Stream) {
+            self.streams.remove(id);
+            for account in [stream.sender, stream.recipient] {
+                let Some(index) = self.stream_positions.get((account, id)) else {
+                    continue;
+                };
+                let last = self.stream_count_of(account) - 1;
+                if let Some(moved) = self
+                    .streams_of
+                    .get((account, last))
+                    .filter(|_| index != last)
+                {
+                    self.streams_of.insert((account, index), &moved);
+                    self.stream_positions.insert((account, moved), &index);
+                }
+                self.stream_positions.remove((account, id));
+                self.streams_of.remove((account, last));
+                self.stream_count_of.insert(account, &last);
+            }
+        }
+    }
+
+    /// accepts transfers of the streamed token only
+    impl PSP22Receiver for Streams {
+        #[ink(message)]
+        fn before_received(
+            &mut self,
+            _operator: AccountId,
+            _from: AccountId,
+            _value: Balance,
+            _data: Vec<u8>,
+        ) -> Result<(), PSP22ReceiverError> {
+            accept_tokens(self.env().caller(), &[self.token])
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn stream() -> Stream {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            Stream {
+                sender: accounts.alice,
+                recipient: accounts.bob,
+                deposit: 1_000,
+                withdrawn: 0,
+                start: 100,
+                stop: 200,
+            }
+        }
+
+        #[ink::test]
+        fn streams_linearly_between_start_and_stop() {
+            let mut stream = stream();
+            assert_eq!(stream.streamed_at(50), 0);
+            assert_eq!(stream.streamed_at(100), 0);
+            assert_eq!(stream.streamed_at(125), 250);
+            assert_eq!(stream.streamed_at(200), 1_000);
+            assert_eq!(stream.streamed_at(300), 1_000);
+
+            stream.withdrawn = 250;
+            assert_eq!(stream.withdrawable_at(150), 250);
+            assert_eq!(stream.withdrawable_at(110), 0);
+        }
+
+        #[ink::test]
+        fn large_deposits_stream_monotonically() {
+            let mut stream = stream();
+            stream.deposit = 1_856_910_061_563_319_565_735_765_138;
+            stream.start = 0;
+            stream.stop = u64::MAX;
+
+            // `deposit * 183_251_937_703` overflows
+            assert_eq!(
+                stream.streamed_at(183_251_937_702),
+                18_446_744_073_648_834_036
+            );
+            assert_eq!(
+                stream.streamed_at(183_251_937_703),
+                18_446_744_073_749_497_332
+            );
+            assert_eq!(
+                stream.streamed_at(u64::MAX - 1),
+                stream.deposit - 100_663_297
+            );
+        }
+
+        #[ink::test]
+        fn rejects_invalid_streams() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut streams = Streams::new(accounts.django);
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(100);
+
+            assert_eq!(
+                streams.create_stream(accounts.bob, 10, 200, 200),
+                Err(StreamError::InvalidTimeRange)
+            );
+            assert_eq!(
+                streams.create_stream(accounts.bob, 10, 50, 200),
+                Err(StreamError::InvalidTimeRange)
+            );
+            assert_eq!(
+                streams.create_stream(accounts.bob, 0, 100, 200),
+                Err(StreamError::ZeroDeposit)
+            );
+            assert_eq!(streams.withdraw(0), Err(StreamError::StreamNotFound));
+        }
+
+        #[ink::test]
+        fn only_the_parties_withdraw_or_cancel() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut streams = Streams::new(accounts.django);
+            streams.streams.insert(0, &stream());
+            streams.add_stream_of(accounts.alice, 0);
+            streams.add_stream_of(accounts.bob, 0);
+
+            assert_eq!(streams.withdraw(0), Err(StreamError::NotRecipient));
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(streams.cancel(0), Err(StreamError::NotSender));
+            assert_eq!(streams.withdraw(0), Err(StreamError::NothingToWithdraw));
+            assert_eq!(streams.streams_of(accounts.bob, 0, 10), vec![(0, stream())]);
+        }
+
+        #[ink::test]
+        fn removing_a_stream_moves_the_last_stream_of_each_party() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut streams = Streams::new(accounts.django);
+            for id in 0..3 {
+                streams.streams.insert(id, &stream());
+                streams.add_stream_of(accounts.alice, id);
+                streams.add_stream_of(accounts.bob, id);
+            }
+            assert_eq!(streams.streams_of(accounts.bob, 1, 1), vec![(1, stream())]);
+
+            streams.remove_stream(0, &stream());
+            assert_eq!(streams.stream_count_of(accounts.alice), 2);
+            assert_eq!(
+                streams.streams_of(accounts.bob, 0, 10),
+                vec![(2, stream()), (1, stream())]
+            );
+            streams.remove_stream(1, &stream());
+            streams.remove_stream(2, &stream());
+            assert_eq!(streams.stream_count_of(accounts.bob), 0);
+            assert_eq!(streams.streams_of(accounts.alice, 0, 10), vec![]);
+        }
+    }
+
+    #[cfg(all(test, feature = "e2e-tests"))]
+    pub mod e2e_tests {
+        use super::*;
+        use ink_e2e::subxt::tx::Signer;
+        use ink_e2e::subxt::utils::AccountId32;
+        use ink_e2e::{build_message, Keypair, PolkadotConfig};
+        use openbrush::contracts::psp22::psp22_external::PSP22;
+        use y_psp22_token::y_psp22_token::YTokenRef;
+
+        type E2EResult<T> = Result<T, Box<dyn std::error::Error>>;
+
+        fn address_of(keypair: &Keypair) -> AccountId {
+            let address: AccountId32 = <Keypair as Signer<PolkadotConfig>>::account_id(keypair);
+            address.0.into()
+        }
+
+        #[ink_e2e::test(additional_contracts = "../contract/Cargo.toml")]
+        async fn canceled_stream_splits_the_deposit(
+            mut client: ink_e2e::Client<C, E>,
+        ) -> E2EResult<()> {
+            let (alice, bob) = (address_of(&ink_e2e::alice()), address_of(&ink_e2e::bob()));
+            let token = client
+                .instantiate(
+                    "y_psp22_token",
+                    &ink_e2e::alice(),
+                    YTokenRef::new(1_000),
+                    0,
+                    None,
+                )
+                .await
+                .expect("instantiate token failed")
+                .account_id;
+            let address = client
+                .instantiate(
+                    "streams",
+                    &ink_e2e::alice(),
+                    StreamsRef::new(token),
+                    0,
+                    None,
+                )
+                .await
+                .expect("instantiate streams failed")
+                .account_id;
+            let approve =
+                build_message::<YTokenRef>(token.clone()).call(|token| token.approve(address, 600));
+            client
+                .call(&ink_e2e::alice(), approve, 0, None)
+                .await
+                .expect("approve failed");
+
+            let create = build_message::<StreamsRef>(address.clone())
+                .call(|streams| streams.create_stream(bob, 600, u64::MAX - 1, u64::MAX));
+            let result = client
+                .call_dry_run(&ink_e2e::alice(), &create, 0, None)
+                .await;
+            assert_eq!(result.return_value(), Err(StreamError::NotFeeExempt));
+            let exempt = build_message::<YTokenRef>(token.clone())
+                .call(|token| token.set_fee_exempt(address, true));
+            client
+                .call(&ink_e2e::alice(), exempt, 0, None)
+                .await
+                .expect("set fee exempt failed");
+
+            // a stream far in the future, nothing accrues before it is canceled
+            let create = build_message::<StreamsRef>(address.clone())
+                .call(|streams| streams.create_stream(bob, 600, u64::MAX - 1, u64::MAX));
+            let id = client
+                .call(&ink_e2e::alice(), create, 0, None)
+                .await
+                .expect("create stream failed")
+                .return_value()
+                .expect("create stream returned an error");
+
+            let streams_of_bob = build_message::<StreamsRef>(address.clone())
+                .call(|streams| streams.streams_of(bob, 0, MAX_PAGE_LEN));
+            let streams = client
+                .call_dry_run(&ink_e2e::alice(), &streams_of_bob, 0, None)
+                .await
+                .return_value();
+            assert_eq!(streams.len(), 1);
+            assert_eq!(streams[0].0, id);
+
+            let withdraw =
+                build_message::<StreamsRef>(address.clone()).call(|streams| streams.withdraw(id));
+            let result = client
+                .call_dry_run(&ink_e2e::bob(), &withdraw, 0, None)
+                .await;
+            assert_eq!(result.return_value(), Err(StreamError::NothingToWithdraw));
+
+            let cancel =
+                build_message::<StreamsRef>(address.clone()).call(|streams| streams.cancel(id));
+            client
+                .call(&ink_e2e::alice(), cancel, 0, None)
+                .await
+                .expect("cancel failed");
+
+            let balance_of_alice =
+                build_message::<YTokenRef>(token.clone()).call(|token| token.balance_of(alice));
+            let balance = client
+                .call_dry_run(&ink_e2e::alice(), &balance_of_alice, 0, None)
+                .await
+                .return_value();
+            assert_eq!(balance, 1_000);
+
+            Ok(())
+        }
+    }
+}
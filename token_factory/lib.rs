@@ -0,0 +1,280 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+/// instantiates `YToken`s with name, symbol and decimals from one uploaded code hash and keeps a registry of them.
+///
+/// The caller of `create_token` owns the new token and receives its initial supply.
+#[openbrush::contract]
+pub mod token_factory {
+    use ink::prelude::vec::Vec;
+    use ink::storage::Mapping;
+    use ink::ToAccountId;
+    use openbrush::traits::String;
+    use y_psp22_token::y_psp22_token::YTokenRef;
+
+    /// maximum number of registry entries returned by `tokens`
+    pub const MAX_PAGE_LEN: u32 = 50;
+
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum FactoryError {
+        NotOwner,
+        /// the code hash is not uploaded or the constructor of the token failed
+        InstantiationFailed,
+    }
+
+    /// registry entry of a token created by the factory
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct TokenInfo {
+        pub token: AccountId,
+        pub creator: AccountId,
+        pub name: Option<String>,
+        pub symbol: Option<String>,
+        pub decimals: u8,
+        pub created_at: Timestamp,
+    }
+
+    #[ink(event)]
+    pub struct TokenCreated {
+        #[ink(topic)]
+        token: AccountId,
+        #[ink(topic)]
+        creator: AccountId,
+        /// position of the token in the registry
+        index: u32,
+    }
+
+    #[ink(storage)]
+    pub struct TokenFactory {
+        owner: AccountId,
+        /// code hash of `YToken`, new tokens are instantiated from it
+        token_code_hash: Hash,
+        /// registry of the created tokens, indexed by their position
+        tokens: Mapping<u32, TokenInfo>,
+        token_count: u32,
+    }
+
+    impl TokenFactory {
+        /// the factory of tokens with the uploaded `YToken` code `token_code_hash`, the caller owns the factory
+        #[ink(constructor)]
+        pub fn new(token_code_hash: Hash) -> Self {
+            Self {
+                owner: Self::env().caller(),
+                token_code_hash,
+                tokens: Mapping::default(),
+                token_count: 0,
+            }
+        }
+
+        /// instantiates a token owned by the caller, mints `initial_supply` to the caller and registers it
+        #[ink(message)]
+        pub fn create_token(
+            &mut self,
+            name: Option<String>,
+            symbol: Option<String>,
+            decimals: u8,
+            initial_supply: Balance,
+        ) -> Result<AccountId, FactoryError> {
+            let creator = self.env().caller();
+            let index = self.token_count;
+            // the index as salt gives tokens with the same arguments different addresses
+            let token = YTokenRef::with_metadata(
+                name.clone(),
+                symbol.clone(),
+                decimals,
+                initial_supply,
+                creator,
+            )
+            .code_hash(self.token_code_hash)
+            .endowment(0)
+            .salt_bytes(index.to_le_bytes())
+            .try_instantiate()
+            .map_err(|_| FactoryError::InstantiationFailed)?
+            .map_err(|_| FactoryError::InstantiationFailed)?
+            .to_account_id();
+
+            self.tokens.insert(
+                index,
+                &TokenInfo {
+                    token,
+                    creator,
+                    name,
+                    symbol,
+                    decimals,
+                    created_at: self.env().block_timestamp(),
+                },
+            );
+            self.token_count = index + 1;
+            self.env().emit_event(TokenCreated {
+                token,
+                creator,
+                index,
+            });
+            Ok(token)
+        }
+
+        /// registry entries from position `start` on, at most `MAX_PAGE_LEN`
+        #[ink(message)]
+        pub fn tokens(&self, start: u32, limit: u32) -> Vec<TokenInfo> {
+            let end = start
+                .saturating_add(limit.min(MAX_PAGE_LEN))
+                .min(self.token_count);
+            (start..end)
+                .filter_map(|index| self.tokens.get(index))
+                .collect()
+        }
+
+        #[ink(message)]
+        pub fn token_count(&self) -> u32 {
+            self.token_count
+        }
+
+        #[ink(message)]
+        pub fn token_code_hash(&self) -> Hash {
+            self.token_code_hash
+        }
+
+        /// new tokens are instantiated from `token_code_hash`, already created tokens keep their code
+        #[ink(message)]
+        pub fn set_token_code_hash(&mut self, token_code_hash: Hash) -> Result<(), FactoryError> {
+            if self.env().caller() != self.owner {
+                return Err(FactoryError::NotOwner);
+            }
+            self.token_code_hash = token_code_hash;
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn owner(&self) -> AccountId {
+            self.owner
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[ink::test]
+        fn only_the_owner_sets_the_code_hash() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut factory = TokenFactory::new(Hash::from([1; 32]));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(
+                factory.set_token_code_hash(Hash::from([2; 32])),
+                Err(FactoryError::NotOwner)
+            );
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(factory.set_token_code_hash(Hash::from([2; 32])), Ok(()));
+            assert_eq!(factory.token_code_hash(), Hash::from([2; 32]));
+        }
+
+        #[ink::test]
+        fn tokens_pages_are_bounded_by_the_registry() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut factory = TokenFactory::new(Hash::from([1; 32]));
+            for index in 0..3 {
+                factory.tokens.insert(
+                    index,
+                    &TokenInfo {
+                        token: AccountId::from([index as u8; 32]),
+                        creator: accounts.alice,
+                        name: None,
+                        symbol: None,
+                        decimals: 0,
+                        created_at: 0,
+                    },
+                );
+            }
+            factory.token_count = 3;
+
+            assert_eq!(factory.tokens(1, 10).len(), 2);
+            assert_eq!(factory.tokens(3, 10).len(), 0);
+            assert_eq!(factory.tokens(0, u32::MAX).len(), 3);
+        }
+    }
+
+    #[cfg(all(test, feature = "e2e-tests"))]
+    pub mod e2e_tests {
+        use super::*;
+        use ink_e2e::subxt::tx::Signer;
+        use ink_e2e::subxt::utils::AccountId32;
+        use ink_e2e::{build_message, Keypair, PolkadotConfig};
+        use openbrush::contracts::psp22::extensions::metadata::psp22metadata_external::PSP22Metadata;
+        use openbrush::contracts::psp22::psp22_external::PSP22;
+
+        type E2EResult<T> = Result<T, Box<dyn std::error::Error>>;
+
+        fn address_of(keypair: &Keypair) -> AccountId {
+            let address: AccountId32 = <Keypair as Signer<PolkadotConfig>>::account_id(keypair);
+            address.0.into()
+        }
+
+        #[ink_e2e::test(additional_contracts = "../contract/Cargo.toml")]
+        async fn creates_and_registers_tokens(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            let code_hash = client
+                .upload("y_psp22_token", &ink_e2e::alice(), None)
+                .await
+                .expect("upload token failed")
+                .code_hash;
+            let factory = client
+                .instantiate(
+                    "token_factory",
+                    &ink_e2e::alice(),
+                    TokenFactoryRef::new(code_hash),
+                    0,
+                    None,
+                )
+                .await
+                .expect("instantiate factory failed")
+                .account_id;
+
+            let create = build_message::<TokenFactoryRef>(factory.clone()).call(|factory| {
+                factory.create_token(
+                    Some(String::from("Brand")),
+                    Some(String::from("BRD")),
+                    12,
+                    1000,
+                )
+            });
+            let token = client
+                .call(&ink_e2e::bob(), create, 0, None)
+                .await
+                .expect("create token failed")
+                .return_value()
+                .expect("create token returned an error");
+
+            let tokens = build_message::<TokenFactoryRef>(factory.clone())
+                .call(|factory| factory.tokens(0, 10));
+            let tokens = client
+                .call_dry_run(&ink_e2e::alice(), &tokens, 0, None)
+                .await
+                .return_value();
+            assert_eq!(tokens.len(), 1);
+            assert_eq!(tokens[0].token, token);
+            assert_eq!(tokens[0].creator, address_of(&ink_e2e::bob()));
+
+            let symbol =
+                build_message::<YTokenRef>(token.clone()).call(|token| token.token_symbol());
+            let symbol = client
+                .call_dry_run(&ink_e2e::alice(), &symbol, 0, None)
+                .await
+                .return_value();
+            assert_eq!(symbol, Some(String::from("BRD")));
+
+            let bob = address_of(&ink_e2e::bob());
+            let balance_of_bob =
+                build_message::<YTokenRef>(token.clone()).call(|token| token.balance_of(bob));
+            let balance = client
+                .call_dry_run(&ink_e2e::alice(), &balance_of_bob, 0, None)
+                .await
+                .return_value();
+            assert_eq!(balance, 1000);
+
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,238 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+/// PSP22 token backed 1:1 by the native currency of the chain.
+///
+/// `deposit` mints the transferred value to the caller, `withdraw` burns tokens of the caller and pays out the
+/// same amount of native currency, so the total supply never exceeds the native balance of the contract.
+#[openbrush::implementation(PSP22, PSP22Metadata)]
+#[openbrush::contract]
+pub mod wrapped_native {
+    use openbrush::traits::{Storage, String};
+
+    #[ink(event)]
+    pub struct Transfer {
+        #[ink(topic)]
+        from: Option<AccountId>,
+        #[ink(topic)]
+        to: Option<AccountId>,
+        value: Balance,
+    }
+
+    #[ink(event)]
+    pub struct Approval {
+        #[ink(topic)]
+        owner: AccountId,
+        #[ink(topic)]
+        spender: AccountId,
+        value: Balance,
+    }
+
+    #[ink(event)]
+    pub struct Deposited {
+        #[ink(topic)]
+        account: AccountId,
+        amount: Balance,
+    }
+
+    #[ink(event)]
+    pub struct Withdrawn {
+        #[ink(topic)]
+        account: AccountId,
+        amount: Balance,
+    }
+
+    #[ink(storage)]
+    #[derive(Default, Storage)]
+    pub struct WrappedNative {
+        #[storage_field]
+        psp22: psp22::Data,
+        #[storage_field]
+        metadata: metadata::Data,
+    }
+
+    #[overrider(psp22::Internal)]
+    fn _emit_transfer_event(
+        &self,
+        from: Option<AccountId>,
+        to: Option<AccountId>,
+        amount: Balance,
+    ) {
+        self.env().emit_event(Transfer {
+            from,
+            to,
+            value: amount,
+        });
+    }
+
+    #[overrider(psp22::Internal)]
+    fn _emit_approval_event(&self, owner: AccountId, spender: AccountId, amount: Balance) {
+        self.env().emit_event(Approval {
+            owner,
+            spender,
+            value: amount,
+        });
+    }
+
+    impl WrappedNative {
+        /// the decimals should match the native currency
+        #[ink(constructor)]
+        pub fn new(name: Option<String>, symbol: Option<String>, decimals: u8) -> Self {
+            let mut instance = Self::default();
+            instance.metadata.name.set(&name);
+            instance.metadata.symbol.set(&symbol);
+            instance.metadata.decimals.set(&decimals);
+            instance
+        }
+
+        /// mints the transferred native value to the caller
+        #[ink(message, payable)]
+        pub fn deposit(&mut self) -> Result<(), PSP22Error> {
+            let amount = self.env().transferred_value();
+            if amount == 0 {
+                return Err(PSP22Error::Custom(String::from("ZeroDeposit")));
+            }
+            let account = self.env().caller();
+            psp22::Internal::_mint_to(self, account, amount)?;
+            self.env().emit_event(Deposited { account, amount });
+            Ok(())
+        }
+
+        /// burns `amount` tokens of the caller and transfers the same native value to it
+        #[ink(message)]
+        pub fn withdraw(&mut self, amount: Balance) -> Result<(), PSP22Error> {
+            let account = self.env().caller();
+            psp22::Internal::_burn_from(self, account, amount)?;
+            self.env()
+                .transfer(account, amount)
+                .map_err(|_| PSP22Error::Custom(String::from("NativeTransferFailed")))?;
+            self.env().emit_event(Withdrawn { account, amount });
+            Ok(())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[ink::test]
+        fn deposit_and_withdraw_are_one_to_one() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut token = WrappedNative::new(
+                Some(String::from("Wrapped")),
+                Some(String::from("WNAT")),
+                12,
+            );
+            let contract = ink::env::account_id::<ink::env::DefaultEnvironment>();
+
+            assert_eq!(
+                token.deposit(),
+                Err(PSP22Error::Custom(String::from("ZeroDeposit")))
+            );
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(100);
+            assert_eq!(token.deposit(), Ok(()));
+            assert_eq!(PSP22Impl::balance_of(&token, accounts.alice), 100);
+            assert_eq!(PSP22Impl::total_supply(&token), 100);
+
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(0);
+            ink::env::test::set_account_balance::<ink::env::DefaultEnvironment>(contract, 100);
+            let native_before =
+                ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.alice)
+                    .unwrap();
+            assert_eq!(token.withdraw(40), Ok(()));
+            assert_eq!(PSP22Impl::balance_of(&token, accounts.alice), 60);
+            assert_eq!(
+                ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.alice),
+                Ok(native_before + 40)
+            );
+            assert_eq!(token.withdraw(61), Err(PSP22Error::InsufficientBalance));
+        }
+    }
+
+    #[cfg(all(test, feature = "e2e-tests"))]
+    pub mod e2e_tests {
+        use super::*;
+        use ink_e2e::subxt::tx::Signer;
+        use ink_e2e::subxt::utils::AccountId32;
+        use ink_e2e::{build_message, Keypair, PolkadotConfig};
+        use openbrush::contracts::psp22::psp22_external::PSP22;
+
+        type E2EResult<T> = Result<T, Box<dyn std::error::Error>>;
+
+        fn address_of(keypair: &Keypair) -> AccountId {
+            let address: AccountId32 = <Keypair as Signer<PolkadotConfig>>::account_id(keypair);
+            address.0.into()
+        }
+
+        async fn deploy(client: &mut ink_e2e::Client<C, E>) -> AccountId {
+            let constructor = WrappedNativeRef::new(
+                Some(String::from("Wrapped")),
+                Some(String::from("WNAT")),
+                12,
+            );
+            client
+                .instantiate("wrapped_native", &ink_e2e::alice(), constructor, 0, None)
+                .await
+                .expect("instantiate failed")
+                .account_id
+        }
+
+        #[ink_e2e::test]
+        async fn deposit_mints_and_withdraw_pays_out(
+            mut client: ink_e2e::Client<C, E>,
+        ) -> E2EResult<()> {
+            let address = deploy(&mut client).await;
+            let bob = address_of(&ink_e2e::bob());
+            let native_before = client.balance(address).await.expect("balance failed");
+
+            let deposit =
+                build_message::<WrappedNativeRef>(address.clone()).call(|token| token.deposit());
+            client
+                .call(&ink_e2e::bob(), deposit, 1_000, None)
+                .await
+                .expect("deposit failed");
+            let balance_of_bob = build_message::<WrappedNativeRef>(address.clone())
+                .call(|token| token.balance_of(bob));
+            let balance = client
+                .call_dry_run(&ink_e2e::bob(), &balance_of_bob, 0, None)
+                .await
+                .return_value();
+            assert_eq!(balance, 1_000);
+            assert_eq!(
+                client.balance(address).await.expect("balance failed"),
+                native_before + 1_000
+            );
+
+            let withdraw = build_message::<WrappedNativeRef>(address.clone())
+                .call(|token| token.withdraw(400));
+            client
+                .call(&ink_e2e::bob(), withdraw, 0, None)
+                .await
+                .expect("withdraw failed");
+            let balance = client
+                .call_dry_run(&ink_e2e::bob(), &balance_of_bob, 0, None)
+                .await
+                .return_value();
+            assert_eq!(balance, 600);
+            assert_eq!(
+                client.balance(address).await.expect("balance failed"),
+                native_before + 600
+            );
+
+            Ok(())
+        }
+
+        #[ink_e2e::test]
+        async fn withdraw_above_balance_fails(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            let address = deploy(&mut client).await;
+
+            let withdraw =
+                build_message::<WrappedNativeRef>(address.clone()).call(|token| token.withdraw(1));
+            let result = client
+                .call_dry_run(&ink_e2e::charlie(), &withdraw, 0, None)
+                .await;
+            assert_eq!(result.return_value(), Err(PSP22Error::InsufficientBalance));
+
+            Ok(())
+        }
+    }
+}